@@ -0,0 +1,388 @@
+# Deutsche Übersetzung. Fehlende Schlüssel fallen auf Englisch zurück.
+
+decimal-separator = ,
+group-separator = .
+unit-mb = MB
+unit-gb = GB
+date-format = { $day }.{ $month }.{ $year }
+date-time-format = { $date }, { $hour }:{ $minute } UTC
+
+app-title = Speicherplatz-Visualisierung
+language-label = Sprache:
+language-system = Systemstandard
+ui-scale-label = Skalierung:
+
+scan-disk = Laufwerk scannen
+quick-scan = Schnellscan
+deep-scan = Tiefenscan
+refresh = Laufwerksinfo aktualisieren
+export = Exportieren
+format-json = JSON
+format-csv = CSV
+format-yaml = YAML
+format-toml = TOML (nur Übersicht)
+format-xlsx = Excel-Arbeitsmappe (XLSX)
+format-markdown = Markdown-Bericht
+format-ndjson = NDJSON (eine Zeile pro Datei)
+copy-markdown-summary = Übersicht als Markdown kopieren
+md-usage = { $used } von { $total } belegt ({ $percent } %), { $files } Dateien
+md-path = Pfad
+md-size = Größe
+md-modified = Geändert
+md-extension = Endung
+md-files = Dateien
+md-no-extension = (keine)
+import-json = JSON importieren
+export-per-disk = Eine Datei pro Laufwerk (Ordner wählen)
+detect-clone-dirs = Geklonte Verzeichnisse finden
+detecting-clone-dirs = Verzeichnisse werden verglichen...
+clone-dirs-summary = { $count } vermutlich geklonte Verzeichnispaare
+clone-dirs-pair = { $left } ≈ { $right }: { $percent } % ähnlich, { $files } gemeinsame Dateien ({ $size })
+growth-log-enabled = Nach jedem Scan Laufwerkssummen an ein Wachstumsprotokoll anhängen
+growth-log-locked = Anhängen an { $path } fehlgeschlagen ({ $error }); die Zeilen wurden aufbewahrt und beim nächsten Mal ergänzt
+auto-export-enabled = Nach jedem Scan automatisch exportieren
+auto-export-path-placeholder = Ziel, z. B. exports/disk_usage_{date}.{ext}
+auto-export-filtered = Nur Dateien exportieren, die den aktuellen Filtern entsprechen
+auto-export-done = Automatisch exportiert nach { $path }
+auto-export-failed = Automatischer Export fehlgeschlagen: { $error }
+auto-export-worker-stopped = Automatischer Export unerwartet beendet
+compress-exports = Exporte komprimieren (gzip)
+export-per-disk-summary = { $written } von { $total } Dateien geschrieben
+export-file-written = { $file } geschrieben
+export-file-failed = { $file } konnte nicht geschrieben werden: { $error }
+export-checksum = Exportiert, SHA-256 { $checksum }
+done = Fertig
+cancel-scan = Scan abbrechen
+cancelling = Wird abgebrochen...
+rescan = Neu scannen
+rescanning = Wird neu gescannt...
+select-all-temp = Alle temporären Dateien auswählen
+clear-selection = Auswahl aufheben
+
+file-type-filter-placeholder = Dateityp-Filter (z. B. .txt oder .jpg, .png)
+file-name-filter-placeholder = Dateiname-Filter (z. B. bericht)
+inspect-archives = .zip/.jar/.apk-Archive untersuchen (langsamer)
+include-network = Netzwerk- und Cloud-Speicher einbeziehen (kann langsam sein oder Dateien herunterladen)
+notify-on-complete = Benachrichtigen, wenn Scans länger als { $seconds } Sekunden dauern
+scan-profile-label = Scan-Profil:
+profile-quick = Schnell (4 Ebenen, 100.000 Dateien je Laufwerk)
+profile-deep = Gründlich (unbegrenzt)
+profile-custom = Benutzerdefiniert
+max-depth-placeholder = Max. Tiefe (leer = unbegrenzt)
+max-files-placeholder = Max. Dateien (leer = unbegrenzt)
+time-limit-placeholder = Zeitlimit für Scans in Sekunden (leer = unbegrenzt)
+
+scanning-header = Scan läuft... { $percent } %
+current-dir-slow = { $path } (langsam)
+cpu-usage = CPU { $percent } %
+memory-usage = Arbeitsspeicher { $size }
+scan-counters = Gescannte Dateien: { $files }    Gescannte Daten: { $size }    Verstrichene Zeit: { $seconds } Sekunden
+scan-duration = Scan-Dauer: { $seconds } Sekunden
+currently-scanning = Wird gerade gescannt: { $path }
+scan-retries = Nach vorübergehenden Fehlern wiederholte Einträge: { $count }
+scans-performed = Durchgeführte Scans: { $count }
+
+disk-label = Laufwerk: { $name }
+total-space = Gesamtspeicher: { $size }
+used-space = Belegter Speicher: { $size }
+skipped = Übersprungen: { $reason }
+skipped-network-reason = Netzwerk-/Cloud-Dateisystem
+skipped-placeholders = { $count } Cloud-Platzhalterdateien übersprungen
+scan-truncated = Scan nach { $count } Dateien und { $seconds } Sekunden abgebrochen (Zeitlimit erreicht)
+temp-files-summary = Temporäre Dateien: { $count } Dateien, { $size } verschwendet
+projects-header = Projekte ({ $count })
+project-row = { $path } [{ $type }]: { $size }
+dedup-ratio = Deduplizierungsfaktor: { $ratio }x (spart { $saving })
+combined-totals = { $disks } Laufwerke von { $sources } Rechnern: { $used } von { $total } belegt
+remove-source = Entfernen
+preset-name-placeholder = Name der Vorlage
+save-preset = Filter als Vorlage speichern
+filter-presets-header = Filtervorlagen
+delete-preset = Löschen
+hardlinks-summary = Hardlinks: { $entries } Einträge teilen sich { $inodes } Dateien; einzeln gezählt ergeben sich { $size } zu viel. Das Löschen eines Links gibt keinen Platz frei, solange die anderen bestehen.
+hardlink-group = { $count } Links, { $size }: { $paths }
+category-image = Bilder
+category-video = Videos
+category-document = Dokumente
+category-archive = Archive
+category-code = Code
+category-other = Sonstige
+quit-confirm-scan = Ein Scan läuft — abbrechen und beenden?
+quit-confirm-export = Ein Export wird geschrieben — abschließen und beenden?
+cancel = Abbrechen
+quit = Beenden
+quitting = Scan wird gestoppt und Exporte werden abgeschlossen…
+sort-label = Sortieren nach:
+sort-size = Größe
+sort-name = Name
+sort-path = Pfad
+sort-modified = Geändert
+sort-extension = Endung
+sort-ascending = ▲ Aufsteigend
+sort-descending = ▼ Absteigend
+scope-size = Dieser Ordner: { $size } ({ $percent } % des Laufwerks)
+chart-other-dirs = weitere ({ $count } Ordner) — { $size }
+file-column = Datei
+share-of-disk = % des Laufwerks
+share-of-folder = % des Ordners
+share-of-filtered = % der Auswahl
+cumulative-column = Kumuliert
+show-more-files = { $count } weitere anzeigen
+collapse-files = Einklappen
+files-shown = { $shown } von { $total } Dateien angezeigt
+scan-priority-label = Scan-Priorität:
+priority-background = Hintergrund (niedrig)
+priority-normal = Normal
+priority-high = Hoch
+depth-histogram-header = Dateien nach Tiefe:
+depth-row = Tiefe { $depth }: { $count } Dateien
+size-histogram-header = Dateien nach Größe:
+size-histogram-row = { $range }: { $count } Dateien
+size-range-1kb = 0–1 KB
+size-range-100kb = 1–100 KB
+size-range-1mb = 100 KB–1 MB
+size-range-100mb = 1–100 MB
+size-range-1gb = 100 MB–1 GB
+size-range-over-1gb = über 1 GB
+nearly-full-banner = ⚠ { $disk } ist zu { $percent } % voll — { $free } frei
+nearly-full-threshold = Warnen, wenn ein Laufwerk zu diesem Prozentsatz voll ist:
+notify-nearly-full = Zusätzlich eine Desktop-Benachrichtigung senden
+notify-nearly-full-body = Fast voll (über { $percent } %): { $disks }
+minimize-to-tray = Schließen des Fensters minimiert in den Infobereich
+tray-show = Fenster anzeigen
+tray-scan-now = Jetzt scannen
+tray-no-scan-yet = Noch kein Scan
+tray-last-scan = Letzter Scan { $date }: { $files } Dateien in { $seconds } s
+use-duignore = In .duignore-Dateien aufgeführte Pfade überspringen
+btrfs-dedup = Btrfs-Deduplizierungsfaktor ermitteln (liest das ganze Dateisystem)
+duignore-patterns = Von .duignore übersprungen: { $count } Muster
+drop-folder-hint = Einen Ordner hierher ziehen, um ihn zu scannen
+drop-not-folder = Zum Scannen können nur Ordner abgelegt werden: { $path }
+scanning-folder = Scanne Ordner { $path }
+scan-queue = { $count } weitere abgelegte Ordner in der Warteschlange
+summary-headline = { $disks } Laufwerke: { $used } von { $total } belegt, { $free } frei ({ $percent } % voll)
+summary-details = { $files } Dateien · { $nearly_full } fast voll · letzter Scan { $last_scan }
+summary-never-scanned = nie
+summary-col-disk = Laufwerk
+summary-col-used = Belegt
+summary-col-free = Frei
+summary-col-files = Dateien
+summary-used-cell = { $used } von { $total } ({ $percent } %)
+binary-filter-label = Inhalt:
+binary-all = Beliebig
+binary-only = Nur binär
+binary-text-only = Nur Text
+binary-check-placeholder = Dateien bis zu so vielen MB auf Binärinhalt prüfen (leer = aus)
+dark-mode = Dunkles Design
+palette-placeholder = Befehl eingeben…
+palette-no-matches = Keine passenden Befehle
+palette-rescan = { $disk } neu scannen
+palette-jump = Zu { $disk } springen
+palette-export = Als { $format } exportieren
+palette-toggle-dark-mode = Dunkles Design umschalten
+checksum-top-n-placeholder = SHA-256 der N größten Dateien je Laufwerk (leer = aus)
+file-sha256 = SHA-256 { $hash }
+mime-filter-placeholder = Nach MIME-Typ des Inhalts filtern (z. B. image/png, pdf)
+mime-mismatch-only = Nur Dateien, deren Inhalt nicht zur Endung passt
+file-mime-mismatch = ⚠ Inhalt sieht aus wie { $mime }
+usage-history-point = { $date }: { $used } belegt
+min-dir-size-label = Ordner im Diagramm ab (MB):
+chart-small-dirs = { $count } Ordner unter { $size } nicht im Diagramm
+chart-coloring-label = Ordner einfärben nach:
+chart-color-size = Größenanteil
+chart-color-age = Alter der neuesten Datei
+age-band-day = Letzter Tag
+age-band-week = Letzte Woche
+age-band-month = Letzter Monat
+age-band-quarter = Letzte 3 Monate
+age-band-year = Letztes Jahr
+age-band-three-years = 1–3 Jahre
+age-band-older = Älter
+path-memory-saved = Gemeinsam genutzte Dateipfade sparten { $size } Speicher
+what-grew = Was ist gewachsen
+hide-what-grew = Zuwachs ausblenden
+growth-header = Was ist gewachsen
+growth-no-baseline = Noch nichts zum Vergleichen: später erneut scannen oder einen Export eines anderen Scans importieren.
+growth-baseline-label = Verglichen mit:
+growth-baseline-scan = Scan vom { $date }
+growth-baseline-import = Import von { $host }
+growth-export = Als CSV exportieren
+growth-nothing = Kein Ordner ist gewachsen.
+growth-row = { $path }: +{ $size } (+{ $percent } %)
+growth-row-new = { $path }: +{ $size } (neu)
+growth-freed-header = Freigegeben: { $count } Ordner entfernt oder jetzt unter 1 MB, { $size }
+growth-freed-row = { $path } — { $size }
+growth-exported = Gespeichert unter { $path }
+io-quota-placeholder = Max. E/A pro Laufwerk (MB; leer oder 0 = unbegrenzt)
+scan-quota-truncated = Scan nach { $count } Dateien angehalten (E/A-Kontingent erreicht)
+scan-quota-exceeded = Scan-Kontingent überschritten: Scan nach etwa { $size } gelesenen Daten auf einem Laufwerk angehalten, Ergebnisse sind unvollständig
+storage-order-label = Gescannte Dateien speichern:
+storage-order-size = Größte zuerst
+storage-order-name = Nach Name
+storage-order-modified = Neueste zuerst
+storage-order-unsorted = Unsortiert (am schnellsten)
+extensions-header = Größte Dateitypen (zum Auflisten anklicken):
+extension-row = { $extension } — { $count } Dateien, { $size }
+extension-unlisted-row = Nicht einzeln aufgeführt — { $count } Dateien, { $size }
+extension-back = ← Zurück zur Übersicht
+export-extension-list = Diese Liste exportieren
+auto-refresh-label = Neu scannen alle (Minuten):
+auto-refresh-off = aus
+low-space-threshold = Bei automatischem Scan warnen unter % frei:
+notify-low-space-body = Datenträger { $disk } ist fast voll: nur noch { $size } frei
+min-size-filter-placeholder = Mindestgröße (MB)
+older-than-filter-placeholder = Nicht geändert seit (Tagen)
+preset-show-chip = Über den Filtern anzeigen
+preset-large-videos = Videos > 500 MB
+preset-large-archives = Archive > 100 MB
+preset-huge-files = Dateien > 1 GB
+preset-old-files = Älter als 1 Jahr
+preset-downloads = In Downloads
+store-files-above-label = Nur Dateien ab (MB, 0 = alle) auflisten:
+unstored-files = { $count } von { $total } Dateien ({ $size }) liegen unter der Listengröße oder über der Eintragsgrenze und werden nur gezählt
+trash-size = Papierkorb: { $size } in { $count } Dateien
+trash-size-partial = Papierkorb: mindestens { $size } in { $count } Dateien (teilweise gemessen; manches ist nicht lesbar)
+empty-trash = Papierkorb leeren
+emptying-trash = Papierkorb wird geleert…
+empty-trash-confirm = Alles, was du von { $disk } in den Papierkorb verschoben hast, endgültig löschen? Dadurch werden bis zu { $size } frei.
+trash-empty-failed = Das Leeren des Papierkorbs wurde unerwartet abgebrochen
+trash-empty-error = Papierkorb konnte nicht geleert werden: { $error }
+trash-empty-unsupported = Das Leeren des Papierkorbs wird auf diesem System nicht unterstützt
+export-sqlite = Als SQLite exportieren
+sqlite-unavailable = Dieser Build kann nicht nach SQLite exportieren; mit dem Feature „sqlite“ neu bauen
+show-same-name = Dateien mit gleichem Namen
+hide-same-name = Dateien mit gleichem Namen ausblenden
+finding-same-name = Dateien mit gleichem Namen werden gesucht…
+same-name-summary = { $count } Dateinamen in mehr als einem Ordner gefunden
+same-name-group = { $name }: { $count } Orte, { $size } falls es Kopien sind
+same-name-more = … und { $count } weitere Namen
+reveal-in-folder = Im Ordner zeigen
+reveal-failed = Ordner konnte nicht geöffnet werden: { $error }
+show-all-disks-treemap = Treemap aller Laufwerke
+hide-all-disks-treemap = Treemap aller Laufwerke ausblenden
+all-disks-treemap-header = Alle Laufwerke
+all-disks-treemap-empty = Keine Dateien anzuzeigen
+treemap-other-files = { $count } kleinere Dateien — { $size }
+analysis-duplicates = Duplikate exportieren…
+analysis-reclaimable = Freigebbare Dateien exportieren…
+recurse-archives = Dateien in .zip- und .tar-Archiven auflisten (langsamer)
+scan-perf-header = Letzte { $count } Scans: Dauer (blau) und MB/s (orange); ● vollständig, ■ schnell, ○ Ordner
+scan-perf-point = { $date }, { $kind }: { $seconds } s, { $speed } MB/s, { $files } Dateien
+scan-perf-settings = Profil { $profile }, { $threads } Threads, Priorität { $priority }, Dateien ab { $size } gespeichert
+scan-kind-full = vollständiger Scan
+scan-kind-quick = Schnellscan
+scan-kind-folder = Ordnerscan
+max-file-entries-placeholder = Max. gespeicherte Dateieinträge über alle Laufwerke
+entries-capped = Dateiliste auf { $count } Einträge gekürzt (Obergrenze erreicht); nur die größten Dateien werden aufgeführt
+app-memory = App-Speicher: { $size }
+age-recent = Neu
+age-moderate = Mittel
+age-old = Alt
+age-very-old = Sehr alt
+age-filter-label = Nur anzeigen:
+age-filter-all = Alle Alter
+age-filter-only = Dateien: { $age }
+owner-filter-label = Besitzer:
+owner-filter-all = Beliebiger Besitzer
+world-writable-only = Nur für alle beschreibbar
+setuid-only = Nur mit Setuid
+line-count-placeholder = Zeilen in Quelltextdateien bis zu so vielen MB zählen (leer = aus; verlangsamt Scans)
+lines-of-code = Codezeilen insgesamt: { $count }
+no-capacity-heading = Nicht gescannt (keine Kapazität gemeldet)
+include-virtual = Virtuelle Dateisysteme einbeziehen
+virtual-filesystems-label = Virtuelle Dateisystemtypen:
+artifacts-header = Entwickler-Artefakte: { $size } in { $count } Projekten
+artifacts-project = { $path }: { $size }
+artifacts-dir = { $name }: { $size } ({ $files } Dateien)
+artifacts-more = … und { $count } weitere Projekte
+move-to-trash = In den Papierkorb
+trash-artifact-confirm = { $path } ({ $size }) in den Papierkorb verschieben?
+trash-move-failed = { $path } konnte nicht in den Papierkorb verschoben werden: { $error }
+copy-selected-paths = Pfade kopieren
+trashing-selected = Wird in den Papierkorb verschoben...
+trash-selected-confirm = { $count } ausgewählte Dateien ({ $size }) in den Papierkorb verschieben?
+trash-selected-failed = { $count } Dateien konnten nicht in den Papierkorb verschoben werden, z. B.: { $error }
+trash-move-unsupported = Verschieben in den Papierkorb wird auf dieser Plattform nicht unterstützt
+artifact-rules-label = Ordner für Entwickler-Artefakte (Ordnername und Dateien, von denen eine daneben liegen muss):
+artifact-rule = { $dir } neben { $markers }
+remove-artifact-rule = Entfernen
+artifact-dir-placeholder = Ordnername, z. B. dist
+artifact-markers-placeholder = Markierungsdateien, z. B. package.json, *.py
+add-artifact-rule = Regel hinzufügen
+last-scanned = Zuletzt gescannt: vor { $age }
+duration-under-minute = weniger als einer Minute
+duration-minute = { $count } Minute
+duration-minutes = { $count } Minuten
+duration-hour = { $count } Stunde
+duration-hours = { $count } Stunden
+duration-day = { $count } Tag
+duration-days = { $count } Tagen
+exporting = Exportiere...
+export-progress = Export: { $done } von { $total } Zeilen geschrieben
+export-cancelled = Export abgebrochen
+export-worker-stopped = Der Export wurde vor dem Abschluss beendet
+skipped-mounts = { $count } darin eingehängte Dateisysteme ausgelassen:
+kept-scans-label = Im Speicher behaltene Scans:
+snapshot-older = ◀ Älterer Scan
+snapshot-newer = Neuerer Scan ▶
+snapshot-latest = Neuester Scan ({ $count } behalten)
+snapshot-earlier = Angezeigt wird ein früherer Scan vom { $date } ({ $position } von { $count }), nicht die neuesten Ergebnisse
+palette-older-scan = Älteren Scan anzeigen
+palette-newer-scan = Neueren Scan anzeigen
+growth-baseline-snapshot = Behaltener Scan vom { $date }
+throttle-cpu-placeholder = Scan pausieren, solange die CPU-Last über (%) liegt
+scan-throttled = Wegen hoher CPU-Last pausiert: { $seconds } s
+most-files-summary = { $arrow } Meiste Dateien
+most-files-max-avg-label = Nur mit durchschnittlicher Dateigröße unter (KB):
+most-files-row = { $count } Dateien, { $size }, im Schnitt { $average }
+most-files-none = Keine passenden Ordner
+entry-buffer-placeholder = Einträge, die vor dem Kürzen auf die Obergrenze gesammelt werden (Standard { $default })
+drive-removed = Laufwerk während des Scans entfernt – unvollständige Daten
+checkpoint-interval-placeholder = Alle so viele Dateien einen Checkpoint speichern (leer für nie)
+resume-checkpoint = Vom Checkpoint fortsetzen? (Checkpoint von vor { $age }, { $files } Dateien)
+resume-scan = Fortsetzen
+start-over = Neu beginnen
+scan-threads-placeholder = Scan-Threads pro Laufwerk (leer für automatisch, höchstens { $max })
+scan-threads-used = Gescannt mit bis zu { $count } Threads pro Laufwerk
+growth-extensions-header = Nach Dateiendung
+growth-extension-grew = { $extension }-Dateien um { $size } gewachsen ({ $before } → { $after } Dateien)
+growth-extension-shrank = { $extension }-Dateien um { $size } geschrumpft ({ $before } → { $after } Dateien)
+empty-state-title = Noch nichts gescannt
+empty-state-explanation = Ein Scan durchläuft die Laufwerke unten und zeigt, was ihren Platz belegt: die größten Dateien und Ordner, Dateitypen und wie sich jedes Laufwerk mit der Zeit füllt.
+scan-now = Jetzt scannen
+disks-to-scan = Zu scannende Laufwerke:
+detected-disk = { $name } ({ $mount }) — { $used } von { $total } belegt
+no-disks-detected = Keine Laufwerke zum Scannen gefunden.
+hidden-filter-label = Versteckte Dateien:
+hidden-all = Anzeigen
+hidden-exclude = Ausblenden
+hidden-only = Nur versteckte
+hidden-files-summary = { $count } versteckte Dateien, { $size }
+archive-savings = Einsparung durch Archivkomprimierung: { $size }
+file-row = Datei: { $path }, Größe: { $size }
+symlinks-summary = { $arrow } { $count } Symlinks, { $broken } defekt
+symlinks-more = … und { $count } weitere Symlinks
+archive-members-summary = { $arrow } Archivinhalt: { $count } Dateien
+archive-members-more = … und { $count } weitere Dateien in Archiven
+symlink-broken = (defekt)
+symlink-media-missing = (Laufwerk nicht eingebunden)
+symlink-outside-root = (außerhalb des gescannten Ordners)
+selected-summary = Ausgewählt: { $count } Dateien ({ $size })
+fill-estimate = Voraussichtlich voll in: { $days } Tagen (am { $date })
+
+scan-cancelled = Scan abgebrochen
+scan-stop-timed-out = Der Scan hat nicht rechtzeitig angehalten und läuft im Hintergrund zu Ende; seine Ergebnisse werden verworfen
+scan-running = Ein Scan läuft bereits
+scan-too-soon = Der letzte Scan ist zu kurz her; bitte einige Sekunden warten
+disk-info-failed = Laufwerksinformationen konnten nicht abgerufen werden
+notify-scan-complete = Laufwerksscan abgeschlossen: { $files } Dateien in { $seconds } Sekunden
+network-rescan-blocked = { $disk } ist ein Netzwerk-/Cloud-Dateisystem; aktivieren Sie „Netzwerk- und Cloud-Speicher einbeziehen“, um es zu scannen
+full-scan-running = Ein vollständiger Scan läuft bereits
+rescan-overlap = { $path } überschneidet sich mit einem laufenden Neuscan
+path-missing = { $path } existiert nicht mehr
+rescan-worker-stopped = Der Neuscan wurde unerwartet beendet
+settings-save-failed = Einstellungen konnten nicht gespeichert werden: { $error }
+import-not-an-export = Diese Datei ist kein Export des Disk Usage Visualizer.
+import-unverified = Ohne Prüfsummenprüfung importiert: { $path } fehlt.
+import-newer-schema = Dieser Export verwendet Formatversion { $version }, diese App-Version liest bis Version { $supported }. Bitte aktualisieren Sie die App.
+checksum-mismatch = Prüfsumme von { $path } stimmt nicht: erwartet { $expected }, erhalten { $actual }
@@ -0,0 +1,389 @@
+# English strings. This is the fallback for any key missing from another locale.
+# Syntax is a small subset of Fluent: `key = value`, with `{ $name }` placeholders.
+
+decimal-separator = .
+group-separator = ,
+unit-mb = MB
+unit-gb = GB
+date-format = { $year }-{ $month }-{ $day }
+date-time-format = { $date } { $hour }:{ $minute } UTC
+
+app-title = Disk Usage Visualizer
+language-label = Language:
+language-system = System default
+ui-scale-label = UI scale:
+
+scan-disk = Scan Disk
+quick-scan = Quick Scan
+deep-scan = Deep Scan
+refresh = Refresh Disk Info
+export = Export
+format-json = JSON
+format-csv = CSV
+format-yaml = YAML
+format-toml = TOML (summary only)
+format-xlsx = Excel workbook (XLSX)
+format-markdown = Markdown report
+format-ndjson = NDJSON (one line per file)
+copy-markdown-summary = Copy summary as Markdown
+md-usage = Used { $used } of { $total } ({ $percent }%), { $files } files
+md-path = Path
+md-size = Size
+md-modified = Modified
+md-extension = Extension
+md-files = Files
+md-no-extension = (none)
+import-json = Import JSON
+export-per-disk = One file per disk (choose a folder)
+detect-clone-dirs = Find cloned directories
+detecting-clone-dirs = Comparing directories...
+clone-dirs-summary = { $count } likely cloned directory pairs
+clone-dirs-pair = { $left } ≈ { $right }: { $percent }% alike, { $files } shared files ({ $size })
+growth-log-enabled = Append disk totals to a growth log after each scan
+growth-log-locked = Could not append to { $path } ({ $error }); the lines were kept and will be added next time
+auto-export-enabled = Export automatically after every scan
+auto-export-path-placeholder = Destination, e.g. exports/disk_usage_{date}.{ext}
+auto-export-filtered = Only export files matching the current filters
+auto-export-done = Auto-exported to { $path }
+auto-export-failed = Auto-export failed: { $error }
+auto-export-worker-stopped = Auto-export stopped unexpectedly
+compress-exports = Compress exports (gzip)
+export-per-disk-summary = Wrote { $written } of { $total } files
+export-file-written = Wrote { $file }
+export-file-failed = Failed to write { $file }: { $error }
+export-checksum = Exported, SHA-256 { $checksum }
+done = Done
+cancel-scan = Cancel Scan
+cancelling = Cancelling...
+rescan = Rescan
+rescanning = Rescanning...
+select-all-temp = Select all temp files
+clear-selection = Clear selection
+
+file-type-filter-placeholder = File type filter (e.g., .txt or .jpg, .png)
+file-name-filter-placeholder = File name filter (e.g., report)
+inspect-archives = Inspect .zip/.jar/.apk archives (slower)
+include-network = Include network and cloud storage (may be slow or download files)
+notify-on-complete = Notify when scans longer than { $seconds } seconds finish
+scan-profile-label = Scan profile:
+profile-quick = Quick (4 levels, 100,000 files per disk)
+profile-deep = Deep (unlimited)
+profile-custom = Custom
+max-depth-placeholder = Max depth (blank = unlimited)
+max-files-placeholder = Max files (blank = unlimited)
+time-limit-placeholder = Scan time limit in seconds (blank = unlimited)
+
+scanning-header = Scanning... { $percent }%
+current-dir-slow = { $path } (slow)
+cpu-usage = CPU { $percent }%
+memory-usage = Memory { $size }
+scan-counters = Files scanned: { $files }    Data scanned: { $size }    Time Elapsed: { $seconds } seconds
+scan-duration = Scan Duration: { $seconds } seconds
+currently-scanning = Currently scanning: { $path }
+scan-retries = Entries retried after transient errors: { $count }
+scans-performed = Scans performed: { $count }
+
+disk-label = Disk: { $name }
+total-space = Total Space: { $size }
+used-space = Used Space: { $size }
+skipped = Skipped: { $reason }
+skipped-network-reason = network/cloud filesystem
+skipped-placeholders = Skipped { $count } cloud placeholder files
+scan-truncated = Scan truncated at { $count } files after { $seconds } seconds (time limit reached)
+temp-files-summary = Temp files: { $count } files, { $size } wasted
+projects-header = Projects ({ $count })
+project-row = { $path } [{ $type }]: { $size }
+dedup-ratio = Deduplication ratio: { $ratio }x (saving { $saving })
+combined-totals = { $disks } disks from { $sources } machines: { $used } used of { $total }
+remove-source = Remove
+preset-name-placeholder = Preset name
+save-preset = Save filters as preset
+filter-presets-header = Filter presets
+delete-preset = Delete
+hardlinks-summary = Hardlinks: { $entries } entries share { $inodes } files; listing them separately over-counts { $size }. Deleting one link frees nothing while the others remain.
+hardlink-group = { $count } links, { $size }: { $paths }
+category-image = Images
+category-video = Videos
+category-document = Documents
+category-archive = Archives
+category-code = Code
+category-other = Other
+quit-confirm-scan = A scan is running — stop it and quit?
+quit-confirm-export = An export is being written — finish it and quit?
+cancel = Cancel
+quit = Quit
+quitting = Stopping the scan and finishing exports before quitting…
+sort-label = Sort by:
+sort-size = Size
+sort-name = Name
+sort-path = Path
+sort-modified = Modified
+sort-extension = Extension
+sort-ascending = ▲ Ascending
+sort-descending = ▼ Descending
+scope-size = This folder: { $size } ({ $percent }% of the disk)
+chart-other-dirs = other ({ $count } dirs) — { $size }
+file-column = File
+share-of-disk = % of disk
+share-of-folder = % of folder
+share-of-filtered = % of filtered
+cumulative-column = Cumulative
+show-more-files = Show { $count } more
+collapse-files = Collapse
+files-shown = Showing { $shown } of { $total } files
+scan-priority-label = Scan priority:
+priority-background = Background (low)
+priority-normal = Normal
+priority-high = High
+depth-histogram-header = Files by depth:
+depth-row = Depth { $depth }: { $count } files
+size-histogram-header = Files by size:
+size-histogram-row = { $range }: { $count } files
+size-range-1kb = 0–1 KB
+size-range-100kb = 1–100 KB
+size-range-1mb = 100 KB–1 MB
+size-range-100mb = 1–100 MB
+size-range-1gb = 100 MB–1 GB
+size-range-over-1gb = over 1 GB
+nearly-full-banner = ⚠ { $disk } is { $percent }% full — { $free } free
+nearly-full-threshold = Warn when a disk is this % full:
+notify-nearly-full = Also send a desktop notification
+notify-nearly-full-body = Nearly full (over { $percent }%): { $disks }
+minimize-to-tray = Closing the window minimizes to the tray
+tray-show = Show window
+tray-scan-now = Scan now
+tray-no-scan-yet = No scan yet
+tray-last-scan = Last scan { $date }: { $files } files in { $seconds } s
+use-duignore = Skip paths listed in .duignore files
+btrfs-dedup = Work out the Btrfs deduplication ratio (reads the whole file system)
+duignore-patterns = Skipped by .duignore: { $count } patterns
+drop-folder-hint = Drop a folder here to scan it
+drop-not-folder = Only folders can be dropped to scan: { $path }
+scanning-folder = Scanning folder { $path }
+scan-queue = { $count } more dropped folders queued
+summary-headline = { $disks } disks: { $used } used of { $total }, { $free } free ({ $percent }% full)
+summary-details = { $files } files · { $nearly_full } nearly full · last scan { $last_scan }
+summary-never-scanned = never
+summary-col-disk = Disk
+summary-col-used = Used
+summary-col-free = Free
+summary-col-files = Files
+summary-used-cell = { $used } of { $total } ({ $percent }%)
+binary-filter-label = Content:
+binary-all = Any
+binary-only = Binary only
+binary-text-only = Text only
+binary-check-placeholder = Check files up to this many MB for binary content (blank = off)
+dark-mode = Dark mode
+palette-placeholder = Type a command…
+palette-no-matches = No matching commands
+palette-rescan = Rescan { $disk }
+palette-jump = Go to { $disk }
+palette-export = Export as { $format }
+palette-toggle-dark-mode = Toggle dark mode
+checksum-top-n-placeholder = SHA-256 the N largest files per disk (blank = off)
+file-sha256 = SHA-256 { $hash }
+mime-filter-placeholder = Filter by MIME type from content (e.g. image/png, pdf)
+mime-mismatch-only = Only files whose content doesn't match the extension
+file-mime-mismatch = ⚠ Content looks like { $mime }
+usage-history-point = { $date }: { $used } used
+min-dir-size-label = Chart directories of at least (MB):
+chart-small-dirs = { $count } directories under { $size } not charted
+chart-coloring-label = Color directories by:
+chart-color-size = Share of size
+chart-color-age = Age of newest file
+age-band-day = Past day
+age-band-week = Past week
+age-band-month = Past month
+age-band-quarter = Past 3 months
+age-band-year = Past year
+age-band-three-years = 1–3 years
+age-band-older = Older
+path-memory-saved = Shared file paths saved { $size } of memory
+what-grew = What grew
+hide-what-grew = Hide what grew
+growth-header = What grew
+growth-no-baseline = Nothing to compare with yet: scan again later, or import an export from another scan.
+growth-baseline-label = Compared with:
+growth-baseline-scan = Scan of { $date }
+growth-baseline-import = Import from { $host }
+growth-export = Export CSV
+growth-nothing = No directory grew.
+growth-row = { $path }: +{ $size } (+{ $percent }%)
+growth-row-new = { $path }: +{ $size } (new)
+growth-freed-header = Freed: { $count } directories gone or now under 1 MB, { $size }
+growth-freed-row = { $path } — { $size }
+growth-exported = Saved to { $path }
+io-quota-placeholder = Max I/O per disk (MB; blank or 0 = no limit)
+scan-quota-truncated = Scan stopped at { $count } files (I/O quota reached)
+scan-quota-exceeded = Scan quota exceeded: scanning stopped after reading about { $size } on a disk, results are partial
+storage-order-label = Store scanned files:
+storage-order-size = Largest first
+storage-order-name = By name
+storage-order-modified = Newest first
+storage-order-unsorted = Unsorted (fastest)
+extensions-header = Largest file types (click to list their files):
+extension-row = { $extension } — { $count } files, { $size }
+extension-unlisted-row = Not listed one by one — { $count } files, { $size }
+extension-back = ← Back to overview
+export-extension-list = Export this list
+auto-refresh-label = Rescan every (minutes):
+auto-refresh-off = off
+low-space-threshold = Warn below this % free during auto-refresh:
+notify-low-space-body = Disk { $disk } is almost full: only { $size } remaining
+min-size-filter-placeholder = Minimum size (MB)
+older-than-filter-placeholder = Not modified for (days)
+preset-show-chip = Show above the filters
+preset-large-videos = Videos > 500 MB
+preset-large-archives = Archives > 100 MB
+preset-huge-files = Files > 1 GB
+preset-old-files = Older than 1 year
+preset-downloads = In Downloads
+store-files-above-label = List only files of at least (MB, 0 = all):
+unstored-files = { $count } of { $total } files ({ $size }) are below the listing size or past the entry cap and only counted
+trash-size = Trash: { $size } in { $count } files
+trash-size-partial = Trash: at least { $size } in { $count } files (partially measured; some of it can't be read)
+empty-trash = Empty trash
+emptying-trash = Emptying trash…
+empty-trash-confirm = Permanently delete what you trashed from { $disk }? This frees up to { $size }.
+trash-empty-failed = Emptying the trash stopped unexpectedly
+trash-empty-error = Couldn't empty the trash: { $error }
+trash-empty-unsupported = Emptying the trash isn't supported on this system
+export-sqlite = Export as SQLite
+sqlite-unavailable = This build can't export to SQLite; rebuild with the "sqlite" feature
+show-same-name = Files with the same name
+hide-same-name = Hide files with the same name
+finding-same-name = Finding files with the same name…
+same-name-summary = { $count } file names found in more than one folder
+same-name-group = { $name }: { $count } locations, { $size } if they are copies
+same-name-more = … and { $count } more names
+reveal-in-folder = Show in folder
+reveal-failed = Couldn't open the folder: { $error }
+show-all-disks-treemap = All disks treemap
+hide-all-disks-treemap = Hide all disks treemap
+all-disks-treemap-header = All disks
+all-disks-treemap-empty = No files to show
+treemap-other-files = { $count } smaller files — { $size }
+analysis-duplicates = Export duplicates…
+analysis-reclaimable = Export reclaimable files…
+recurse-archives = List files inside .zip and .tar archives (slower)
+scan-perf-header = Last { $count } scans: duration (blue) and MB/s (orange); ● full, ■ quick, ○ folder
+scan-perf-point = { $date }, { $kind }: { $seconds } s, { $speed } MB/s, { $files } files
+scan-perf-settings = { $profile } profile, { $threads } threads, { $priority } priority, files from { $size } kept
+scan-kind-full = full scan
+scan-kind-quick = quick scan
+scan-kind-folder = folder scan
+max-file-entries-placeholder = Max file entries kept across all disks
+entries-capped = File list truncated to { $count } entries (cap reached); only the largest files are listed
+app-memory = App memory: { $size }
+age-recent = Recent
+age-moderate = Moderate
+age-old = Old
+age-very-old = Very old
+age-filter-label = Show only:
+age-filter-all = All ages
+age-filter-only = { $age } files
+owner-filter-label = Owner:
+owner-filter-all = Any owner
+world-writable-only = World-writable only
+setuid-only = Setuid only
+line-count-placeholder = Count lines in source files up to this many MB (blank = off; makes scans slower)
+lines-of-code = Total lines of code: { $count }
+no-capacity-heading = Not scanned (no capacity reported)
+include-virtual = Include virtual file systems
+virtual-filesystems-label = Virtual file system types:
+artifacts-header = Developer artifacts: { $size } across { $count } projects
+artifacts-project = { $path }: { $size }
+artifacts-dir = { $name }: { $size } ({ $files } files)
+artifacts-more = … and { $count } more projects
+move-to-trash = Move to trash
+trash-artifact-confirm = Move { $path } ({ $size }) to the trash?
+trash-move-failed = Couldn't move { $path } to the trash: { $error }
+copy-selected-paths = Copy paths
+trashing-selected = Moving to trash...
+trash-selected-confirm = Move { $count } selected files ({ $size }) to the trash?
+trash-selected-failed = { $count } files couldn't be moved to the trash, e.g.: { $error }
+trash-move-unsupported = Moving to the trash isn't supported on this platform
+artifact-rules-label = Developer artifact folders (folder name, and files any of which must sit next to it):
+artifact-rule = { $dir } next to { $markers }
+remove-artifact-rule = Remove
+artifact-dir-placeholder = Folder name, e.g. dist
+artifact-markers-placeholder = Marker files, e.g. package.json, *.py
+add-artifact-rule = Add rule
+last-scanned = Last scanned: { $age } ago
+duration-under-minute = less than a minute
+duration-minute = { $count } minute
+duration-minutes = { $count } minutes
+duration-hour = { $count } hour
+duration-hours = { $count } hours
+duration-day = { $count } day
+duration-days = { $count } days
+exporting = Exporting...
+export-progress = Exporting: wrote { $done } of { $total } rows
+export-cancelled = Export cancelled
+export-worker-stopped = The export stopped before finishing
+skipped-mounts = Left out { $count } file systems mounted inside this disk:
+kept-scans-label = Scan results kept in memory:
+snapshot-older = ◀ Older scan
+snapshot-newer = Newer scan ▶
+snapshot-latest = Latest scan ({ $count } kept)
+snapshot-earlier = Showing an earlier scan from { $date } ({ $position } of { $count }), not the latest results
+palette-older-scan = Show older scan
+palette-newer-scan = Show newer scan
+growth-baseline-snapshot = Kept scan of { $date }
+throttle-cpu-placeholder = Pause scanning while CPU usage is above (%)
+scan-throttled = Paused for high CPU usage: { $seconds } s
+most-files-summary = { $arrow } Most files
+most-files-max-avg-label = Only where the average file is under (KB):
+most-files-row = { $count } files, { $size }, { $average } on average
+most-files-none = No directories match
+entry-buffer-placeholder = Entries collected before trimming to the cap (default { $default })
+drive-removed = Drive removed during scan — partial data
+checkpoint-interval-placeholder = Save a checkpoint every this many files (empty for never)
+resume-checkpoint = Resume from checkpoint? (checkpoint from { $age } ago, { $files } files)
+resume-scan = Resume
+start-over = Start over
+scan-threads-placeholder = Scan threads per disk (empty for automatic, at most { $max })
+scan-threads-used = Scanned with up to { $count } threads per disk
+growth-extensions-header = By extension
+growth-extension-grew = { $extension } files grew by { $size } ({ $before } → { $after } files)
+growth-extension-shrank = { $extension } files shrank by { $size } ({ $before } → { $after } files)
+empty-state-title = Nothing scanned yet
+empty-state-explanation = A scan walks the disks below and shows what takes up their space: the largest files and folders, file types, and how each disk fills over time.
+scan-now = Scan now
+disks-to-scan = Disks to scan:
+detected-disk = { $name } ({ $mount }) — { $used } used of { $total }
+no-disks-detected = No disks were found to scan.
+hidden-filter-label = Hidden files:
+hidden-all = Show
+hidden-exclude = Exclude
+hidden-only = Only hidden
+hidden-files-summary = { $count } hidden files, { $size }
+archive-savings = Archive compression savings: { $size }
+file-row = File: { $path }, Size: { $size }
+symlinks-summary = { $arrow } { $count } symlinks, { $broken } broken
+symlinks-more = … and { $count } more symlinks
+archive-members-summary = { $arrow } Archive contents: { $count } files
+archive-members-more = … and { $count } more files in archives
+symlink-broken = (broken)
+symlink-media-missing = (volume not mounted)
+symlink-outside-root = (outside the scanned folder)
+selected-summary = Selected: { $count } files ({ $size })
+fill-estimate = Estimated full in: { $days } days (on { $date })
+
+scan-cancelled = Scan cancelled
+scan-stop-timed-out = The scan did not stop in time and was left to finish in the background; its results will be ignored
+scan-running = A scan is already running
+scan-too-soon = Too soon to re-scan; wait a few seconds
+disk-info-failed = Failed to retrieve disk information
+notify-scan-complete = Disk scan complete: { $files } files in { $seconds } seconds
+network-rescan-blocked = { $disk } is a network/cloud filesystem; enable "Include network and cloud storage" to scan it
+full-scan-running = A full scan is already running
+rescan-overlap = { $path } overlaps a rescan that is already running
+path-missing = { $path } no longer exists
+rescan-worker-stopped = Rescan worker stopped unexpectedly
+settings-save-failed = Failed to save settings: { $error }
+import-not-an-export = This file is not a Disk Usage Visualizer export.
+import-unverified = Imported without a checksum check: { $path } is missing.
+import-newer-schema = This export uses format version { $version }, but this version of the app reads up to { $supported }. Please update the app.
+checksum-mismatch = Checksum mismatch for { $path }: expected { $expected }, got { $actual }
@@ -0,0 +1,129 @@
+//! Duplicate and reclaimable-space reports over the scanned files, exported
+//! on their own rather than as part of the main dump.
+
+use crate::classify::{self, TempRule};
+use crate::dupes::{self, DuplicateSet};
+use crate::i18n::tr;
+use crate::FileInfo;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Mutex;
+
+/// Which analysis an export holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnalysisReport {
+    Duplicates,
+    Reclaimable,
+}
+
+impl AnalysisReport {
+    pub fn default_file_name(self) -> &'static str {
+        match self {
+            AnalysisReport::Duplicates => "duplicates.json",
+            AnalysisReport::Reclaimable => "reclaimable.json",
+        }
+    }
+}
+
+impl std::fmt::Display for AnalysisReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self {
+            AnalysisReport::Duplicates => "analysis-duplicates",
+            AnalysisReport::Reclaimable => "analysis-reclaimable",
+        };
+        write!(f, "{}", tr(key))
+    }
+}
+
+/// Files matched by one cleanup rule.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReclaimableGroup {
+    pub rule: TempRule,
+    /// Path and size in bytes of each file, largest first.
+    pub files: Vec<ReclaimableFile>,
+    pub reclaimable_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReclaimableFile {
+    pub path: String,
+    pub size: u64,
+}
+
+fn size_bytes(file: &FileInfo) -> u64 {
+    (file.size_mb * 1_048_576.0).round() as u64
+}
+
+/// Finds the duplicates among `files` the way `dupes` does: files sharing a
+/// size are hashed, and those whose hashes match form a set, most
+/// reclaimable first. Empty files and files that can no longer be read are
+/// left out.
+pub fn duplicate_sets<'a>(files: impl Iterator<Item = &'a FileInfo>) -> Vec<DuplicateSet> {
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    for file in files {
+        let size = size_bytes(file);
+        if size > 0 {
+            by_size.entry(size).or_default().push(PathBuf::from(&*file.path));
+        }
+    }
+    let candidates: Vec<(u64, Vec<PathBuf>)> = by_size.into_iter().filter(|(_, paths)| paths.len() > 1).collect();
+    let sets = Mutex::new(Vec::new());
+    dupes::find_duplicates(candidates, &AtomicUsize::new(0), |set| sets.lock().unwrap().push(set));
+    let mut sets = sets.into_inner().unwrap();
+    sets.sort_by(|a, b| b.reclaimable_bytes.cmp(&a.reclaimable_bytes).then_with(|| a.hash.cmp(&b.hash)));
+    sets
+}
+
+/// The temp files among `files`, by the rule they matched.
+pub fn reclaimable_groups<'a>(files: impl Iterator<Item = &'a FileInfo>) -> Vec<ReclaimableGroup> {
+    let mut by_rule: BTreeMap<TempRule, Vec<ReclaimableFile>> = BTreeMap::new();
+    for file in files.filter(|file| file.is_temp) {
+        // Imported files may sit in another machine's temp directory, which
+        // no longer matches here.
+        let rule = classify::temp_rule(&file.path).unwrap_or(TempRule::TempDirectory);
+        by_rule.entry(rule).or_default().push(ReclaimableFile { path: file.path.to_string(), size: size_bytes(file) });
+    }
+    by_rule
+        .into_iter()
+        .map(|(rule, mut files)| {
+            files.sort_by(|a, b| b.size.cmp(&a.size).then_with(|| a.path.cmp(&b.path)));
+            ReclaimableGroup { rule, reclaimable_bytes: files.iter().map(|file| file.size).sum(), files }
+        })
+        .collect()
+}
+
+/// One row per member path, with a 1-based set id tying the rows of a set
+/// together.
+pub fn write_duplicates_csv(sets: &[DuplicateSet], out: &mut dyn Write) -> Result<(), String> {
+    let mut wtr = csv::Writer::from_writer(out);
+    wtr.write_record(["set_id", "hash", "size_bytes", "reclaimable_bytes", "path"]).map_err(|e| e.to_string())?;
+    for (id, set) in sets.iter().enumerate() {
+        for path in &set.paths {
+            wtr.write_record([
+                (id + 1).to_string(),
+                set.hash.clone(),
+                set.size.to_string(),
+                set.reclaimable_bytes.to_string(),
+                path.clone(),
+            ])
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    wtr.flush().map_err(|e| e.to_string())
+}
+
+/// One row per file, with the rule it matched.
+pub fn write_reclaimable_csv(groups: &[ReclaimableGroup], out: &mut dyn Write) -> Result<(), String> {
+    let mut wtr = csv::Writer::from_writer(out);
+    wtr.write_record(["rule", "path", "size_bytes"]).map_err(|e| e.to_string())?;
+    for group in groups {
+        for file in &group.files {
+            wtr.write_record([group.rule.id(), file.path.as_str(), file.size.to_string().as_str()]).map_err(|e| e.to_string())?;
+        }
+    }
+    wtr.flush().map_err(|e| e.to_string())
+}
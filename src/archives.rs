@@ -0,0 +1,176 @@
+//! Lists the members of archives found by the scan, so their contents show up
+//! as entries like `backup.zip::photos/img.jpg`. Only built with the
+//! `recurse-archives` feature; without it no members are ever listed and the
+//! option is hidden. Zip and tar (plain, gzip, bzip2 or xz compressed) are
+//! read; a lone `.gz` file yields its one member from the gzip trailer. 7z and
+//! rar archives are flagged but not opened.
+
+use std::path::Path;
+
+/// Whether this build can list archive members.
+pub const AVAILABLE: bool = cfg!(feature = "recurse-archives");
+
+/// Separates an archive's path from a member's path inside it.
+#[cfg(feature = "recurse-archives")]
+pub const MEMBER_SEPARATOR: &str = "::";
+
+/// How deep archives inside archives are opened when the scan has no depth
+/// limit of its own.
+pub const DEFAULT_NESTING: usize = 3;
+
+/// One file inside an archive, at any nesting level.
+#[derive(Debug, Clone)]
+pub struct ArchiveMember {
+    /// The archive's path, then each nested member, joined by `MEMBER_SEPARATOR`.
+    pub path: String,
+    /// Uncompressed size in bytes.
+    pub size: u64,
+    /// Seconds since the Unix epoch, where the format records it.
+    pub modified: Option<u64>,
+}
+
+/// Lists the files in the archive at `path`, descending into archives within
+/// it until `depth` levels have been opened; 0 lists nothing. Unreadable or
+/// corrupt archives list what could be read before the error.
+#[cfg(feature = "recurse-archives")]
+pub fn list_members(path: &Path, depth: usize) -> Vec<ArchiveMember> {
+    let mut members = Vec::new();
+    if depth > 0 {
+        if let Ok(file) = std::fs::File::open(path) {
+            let name = path.display().to_string();
+            imp::list_archive(&name, &name, std::io::BufReader::new(file), depth, &mut members);
+        }
+    }
+    members
+}
+
+#[cfg(not(feature = "recurse-archives"))]
+pub fn list_members(_path: &Path, _depth: usize) -> Vec<ArchiveMember> {
+    Vec::new()
+}
+
+#[cfg(feature = "recurse-archives")]
+mod imp {
+    use super::{ArchiveMember, MEMBER_SEPARATOR};
+    use bzip2::read::BzDecoder;
+    use flate2::read::GzDecoder;
+    use std::io::{Cursor, Read, Seek, SeekFrom};
+    use xz2::read::XzDecoder;
+
+    /// Nested archives are read into memory to be opened; larger ones are
+    /// listed as plain members.
+    const MAX_NESTED_BYTES: u64 = 64 * 1_048_576;
+
+    enum Kind {
+        Zip,
+        Tar,
+        TarGz,
+        TarBz2,
+        TarXz,
+        Gz,
+    }
+
+    fn kind_of(name: &str) -> Option<Kind> {
+        let name = name.to_ascii_lowercase();
+        let kind = if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Kind::TarGz
+        } else if name.ends_with(".tar.bz2") || name.ends_with(".tbz2") {
+            Kind::TarBz2
+        } else if name.ends_with(".tar.xz") || name.ends_with(".txz") {
+            Kind::TarXz
+        } else if name.ends_with(".tar") {
+            Kind::Tar
+        } else if name.ends_with(".zip") || name.ends_with(".jar") || name.ends_with(".apk") {
+            Kind::Zip
+        } else if name.ends_with(".gz") {
+            Kind::Gz
+        } else {
+            return None;
+        };
+        Some(kind)
+    }
+
+    /// Lists the archive in `reader`, whose own name is `name`, with members
+    /// named below `prefix`.
+    pub fn list_archive<R: Read + Seek>(prefix: &str, name: &str, reader: R, depth: usize, out: &mut Vec<ArchiveMember>) {
+        match kind_of(name) {
+            Some(Kind::Zip) => list_zip(prefix, reader, depth, out),
+            Some(Kind::Tar) => list_tar(prefix, reader, depth, out),
+            Some(Kind::TarGz) => list_tar(prefix, GzDecoder::new(reader), depth, out),
+            Some(Kind::TarBz2) => list_tar(prefix, BzDecoder::new(reader), depth, out),
+            Some(Kind::TarXz) => list_tar(prefix, XzDecoder::new(reader), depth, out),
+            Some(Kind::Gz) => {
+                if let Some(size) = gzip_size(reader) {
+                    let file_name = name.rsplit(['/', '\\']).next().unwrap_or(name);
+                    let member = &file_name[..file_name.len() - ".gz".len()];
+                    out.push(ArchiveMember { path: format!("{}{}{}", prefix, MEMBER_SEPARATOR, member), size, modified: None });
+                }
+            }
+            None => {}
+        }
+    }
+
+    /// The uncompressed size from the gzip trailer, which only holds it
+    /// modulo 4 GiB.
+    fn gzip_size<R: Read + Seek>(mut reader: R) -> Option<u64> {
+        reader.seek(SeekFrom::End(-4)).ok()?;
+        let mut trailer = [0; 4];
+        reader.read_exact(&mut trailer).ok()?;
+        Some(u32::from_le_bytes(trailer) as u64)
+    }
+
+    /// Opens `member` as an archive itself when it is one, small enough, and
+    /// nesting depth is left.
+    fn list_nested(path: &str, name: &str, size: u64, member: impl Read, depth: usize, out: &mut Vec<ArchiveMember>) {
+        if depth <= 1 || size > MAX_NESTED_BYTES || kind_of(name).is_none() {
+            return;
+        }
+        let mut bytes = Vec::with_capacity(size as usize);
+        if member.take(MAX_NESTED_BYTES).read_to_end(&mut bytes).is_ok() {
+            list_archive(path, name, Cursor::new(bytes), depth - 1, out);
+        }
+    }
+
+    fn list_zip<R: Read + Seek>(prefix: &str, reader: R, depth: usize, out: &mut Vec<ArchiveMember>) {
+        let Ok(mut archive) = zip::ZipArchive::new(reader) else {
+            return;
+        };
+        for i in 0..archive.len() {
+            let Ok(member) = archive.by_index(i) else {
+                continue;
+            };
+            if member.is_dir() {
+                continue;
+            }
+            let name = member.name().to_string();
+            let size = member.size();
+            let path = format!("{}{}{}", prefix, MEMBER_SEPARATOR, name);
+            list_nested(&path, &name, size, member, depth, out);
+            out.push(ArchiveMember { path, size, modified: None });
+        }
+    }
+
+    fn list_tar<R: Read>(prefix: &str, reader: R, depth: usize, out: &mut Vec<ArchiveMember>) {
+        let mut archive = tar::Archive::new(reader);
+        let Ok(entries) = archive.entries() else {
+            return;
+        };
+        for entry in entries {
+            // A tar is a stream; nothing after a broken entry can be found.
+            let Ok(entry) = entry else {
+                break;
+            };
+            if !entry.header().entry_type().is_file() {
+                continue;
+            }
+            let Ok(name) = entry.path().map(|path| path.display().to_string()) else {
+                continue;
+            };
+            let size = entry.size();
+            let modified = entry.header().mtime().ok();
+            let path = format!("{}{}{}", prefix, MEMBER_SEPARATOR, name);
+            list_nested(&path, &name, size, entry, depth, out);
+            out.push(ArchiveMember { path, size, modified });
+        }
+    }
+}
@@ -0,0 +1,270 @@
+use crate::scan::UnstoredFiles;
+use crate::{util, FileInfo};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::Mutex;
+use std::thread::{self, JoinHandle};
+
+/// What a checkpoint holds, kept in a small file of its own so asking about
+/// it doesn't mean reading every file entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckpointInfo {
+    /// The walked root.
+    pub root: String,
+    /// Everything sorting before this path had been walked.
+    pub resume_from: PathBuf,
+    /// Entries in the data file that belong to this checkpoint.
+    pub files: usize,
+    /// Files the walk had counted, including those not kept as entries.
+    #[serde(default)]
+    pub files_scanned: usize,
+    pub written_at: u64,
+}
+
+/// The info file: a checkpoint's info, and the files tallied per directory
+/// so far, which change as a whole rather than by appending.
+#[derive(Debug, Serialize, Deserialize)]
+struct State {
+    #[serde(flatten)]
+    info: CheckpointInfo,
+    #[serde(default)]
+    unstored: HashMap<String, UnstoredFiles>,
+}
+
+/// A walk's progress: the entries kept and files tallied so far.
+#[derive(Debug)]
+pub struct Checkpoint {
+    pub info: CheckpointInfo,
+    pub files: Vec<FileInfo>,
+    pub unstored: HashMap<String, UnstoredFiles>,
+}
+
+#[cfg(not(test))]
+fn checkpoint_dir() -> PathBuf {
+    util::data_dir().join("checkpoints")
+}
+
+/// Tests keep their checkpoints away from the user's own.
+#[cfg(test)]
+fn checkpoint_dir() -> PathBuf {
+    std::env::temp_dir().join(format!("disk-usage-test-{}-checkpoints", std::process::id()))
+}
+
+/// A readable name for `root`'s files, made unique by a hash of the full
+/// path: sanitizing alone maps "/" and "/root", or "/data/a" and "/data_a",
+/// to the same name.
+fn file_stem(root: &Path) -> String {
+    let root = root.display().to_string();
+    let hash: String = Sha256::digest(root.as_bytes())[..8].iter().map(|byte| format!("{:02x}", byte)).collect();
+    let stem = util::sanitize_file_name(&root);
+    if stem.is_empty() {
+        format!("root-{}", hash)
+    } else {
+        format!("{}-{}", stem, hash)
+    }
+}
+
+/// The entries, one JSON line each, appended to at every checkpoint.
+pub fn data_path(root: &Path) -> PathBuf {
+    checkpoint_dir().join(format!("{}.ndjson", file_stem(root)))
+}
+
+fn info_path(root: &Path) -> PathBuf {
+    checkpoint_dir().join(format!("{}.info.json", file_stem(root)))
+}
+
+/// Replaces the info file in one step, so it never names entries that
+/// aren't all written yet.
+fn write_state(root: &Path, state: &State) -> Result<(), String> {
+    let path = info_path(root);
+    let temporary = path.with_extension("json.tmp");
+    let contents = serde_json::to_string(state).map_err(|e| e.to_string())?;
+    fs::write(&temporary, contents).map_err(|e| e.to_string())?;
+    fs::rename(&temporary, &path).map_err(|e| e.to_string())
+}
+
+fn read_state(root: &Path) -> Option<State> {
+    serde_json::from_str(&fs::read_to_string(info_path(root)).ok()?).ok()
+}
+
+/// The checkpoint of `root`, if a walk of it left one. Entries appended
+/// after the last complete checkpoint are ignored.
+pub fn load(root: &Path) -> Option<Checkpoint> {
+    let State { info, unstored } = read_state(root)?;
+    if info.root != root.display().to_string() {
+        return None;
+    }
+    let data = BufReader::new(File::open(data_path(root)).ok()?);
+    let files = data
+        .lines()
+        .take(info.files)
+        .map(|line| serde_json::from_str(&line.ok()?).ok())
+        .collect::<Option<Vec<FileInfo>>>()?;
+    (files.len() == info.files).then_some(Checkpoint { info, files, unstored })
+}
+
+pub fn remove(root: &Path) {
+    let _ = fs::remove_file(info_path(root));
+    let _ = fs::remove_file(data_path(root));
+}
+
+/// Every checkpoint left by a scan that didn't finish, oldest first.
+pub fn list() -> Vec<CheckpointInfo> {
+    let Ok(entries) = fs::read_dir(checkpoint_dir()) else {
+        return Vec::new();
+    };
+    let mut infos: Vec<CheckpointInfo> = entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_name().to_string_lossy().ends_with(".info.json"))
+        .filter_map(|entry| serde_json::from_str::<State>(&fs::read_to_string(entry.path()).ok()?).ok())
+        .map(|state| state.info)
+        .collect();
+    infos.sort_by_key(|info| info.written_at);
+    infos
+}
+
+/// What changed since the previous checkpoint.
+struct Batch {
+    resume_from: PathBuf,
+    files: Vec<FileInfo>,
+    files_scanned: usize,
+    unstored: HashMap<String, UnstoredFiles>,
+}
+
+/// Writes a walk's checkpoints on a thread of its own, appending only the
+/// entries kept since the previous one, so the walk never waits on the disk
+/// and the total written grows with the walk rather than its square.
+pub struct Writer {
+    batches: Option<Sender<Batch>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Writer {
+    /// Starts writing checkpoints of `root`, after the first `resumed`
+    /// entries of the data file, which a resumed walk already has.
+    pub fn start(root: &Path, resumed: usize) -> Writer {
+        let (batches, received) = mpsc::channel::<Batch>();
+        let root = root.to_path_buf();
+        let thread = thread::spawn(move || {
+            let mut written = resumed;
+            let mut data = match open_data(&root, resumed) {
+                Ok(data) => data,
+                Err(e) => {
+                    eprintln!("Failed to start scan checkpoints for {}: {}", root.display(), e);
+                    return;
+                }
+            };
+            for batch in received {
+                let appended = batch
+                    .files
+                    .iter()
+                    .try_for_each(|file| {
+                        serde_json::to_writer(&mut data, file).map_err(|e| e.to_string())?;
+                        data.write_all(b"\n").map_err(|e| e.to_string())
+                    })
+                    .and_then(|_| data.flush().map_err(|e| e.to_string()));
+                let saved = appended.and_then(|_| {
+                    written += batch.files.len();
+                    let info = CheckpointInfo {
+                        root: root.display().to_string(),
+                        resume_from: batch.resume_from,
+                        files: written,
+                        files_scanned: batch.files_scanned,
+                        written_at: util::unix_now(),
+                    };
+                    write_state(&root, &State { info, unstored: batch.unstored })
+                });
+                if let Err(e) = saved {
+                    // The info file still names what was complete before.
+                    eprintln!("Failed to save scan checkpoint for {}: {}", root.display(), e);
+                    return;
+                }
+            }
+        });
+        Writer { batches: Some(batches), thread: Some(thread) }
+    }
+
+    /// Queues a checkpoint; `files` are the entries kept since the last one.
+    pub fn save(&self, resume_from: PathBuf, files: Vec<FileInfo>, files_scanned: usize, unstored: HashMap<String, UnstoredFiles>) {
+        if let Some(batches) = &self.batches {
+            let _ = batches.send(Batch { resume_from, files, files_scanned, unstored });
+        }
+    }
+
+    /// Waits for queued checkpoints to be written.
+    pub fn finish(mut self) {
+        self.batches = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The data file, cut back to its first `keep` entries, ready to append to.
+/// A fresh walk starts it over.
+fn open_data(root: &Path, keep: usize) -> Result<BufWriter<File>, String> {
+    fs::create_dir_all(checkpoint_dir()).map_err(|e| e.to_string())?;
+    let path = data_path(root);
+    if keep == 0 {
+        let _ = fs::remove_file(info_path(root));
+        return File::create(&path).map(BufWriter::new).map_err(|e| e.to_string());
+    }
+    let mut length = 0;
+    let mut reader = BufReader::new(File::open(&path).map_err(|e| e.to_string())?);
+    let mut line = Vec::new();
+    for _ in 0..keep {
+        line.clear();
+        length += reader.read_until(b'\n', &mut line).map_err(|e| e.to_string())? as u64;
+    }
+    let file = OpenOptions::new().write(true).open(&path).map_err(|e| e.to_string())?;
+    file.set_len(length).map_err(|e| e.to_string())?;
+    let file = OpenOptions::new().append(true).open(&path).map_err(|e| e.to_string())?;
+    Ok(BufWriter::new(file))
+}
+
+/// Follows a walk's entries from the moment the walker hands them out until
+/// they are done with, to know where a checkpoint may resume: the smallest
+/// path still in flight.
+pub struct Tracker {
+    interval: usize,
+    in_flight: Mutex<BTreeSet<PathBuf>>,
+    next_at: AtomicUsize,
+}
+
+impl Tracker {
+    pub fn new(interval: usize, files_so_far: usize) -> Tracker {
+        let interval = interval.max(1);
+        Tracker { interval, in_flight: Mutex::new(BTreeSet::new()), next_at: AtomicUsize::new(files_so_far + interval) }
+    }
+
+    pub fn started(&self, path: &Path) {
+        self.in_flight.lock().unwrap().insert(path.to_path_buf());
+    }
+
+    pub fn finished(&self, path: &Path) {
+        self.in_flight.lock().unwrap().remove(path);
+    }
+
+    /// Whether `files_scanned` has reached the next checkpoint. True for
+    /// only one caller per checkpoint.
+    pub fn due(&self, files_scanned: usize) -> bool {
+        let next_at = self.next_at.load(Ordering::Relaxed);
+        files_scanned >= next_at
+            && self
+                .next_at
+                .compare_exchange(next_at, files_scanned + self.interval, Ordering::Relaxed, Ordering::Relaxed)
+                .is_ok()
+    }
+
+    /// Where a walk resumed from a checkpoint taken now can start. Read it
+    /// before gathering the entries, so none finishing in between is lost.
+    pub fn resume_from(&self) -> Option<PathBuf> {
+        self.in_flight.lock().unwrap().first().cloned()
+    }
+}
@@ -0,0 +1,106 @@
+use sha2::{Digest, Sha256};
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Lowercase hex encoding of a SHA-256 digest.
+pub fn to_hex(digest: &[u8]) -> String {
+    digest.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Passes writes through to `inner` while hashing them, so a checksum of a
+/// large export can be taken without holding it in memory.
+pub struct HashingWriter<W: Write> {
+    inner: W,
+    hasher: Sha256,
+}
+
+impl<W: Write> HashingWriter<W> {
+    pub fn new(inner: W) -> Self {
+        HashingWriter { inner, hasher: Sha256::new() }
+    }
+
+    /// The wrapped writer and the hex SHA-256 of everything written to it.
+    pub fn finish(self) -> (W, String) {
+        (self.inner, to_hex(&self.hasher.finalize()))
+    }
+}
+
+impl<W: Write> Write for HashingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.hasher.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// SHA-256 of a file's contents, as lowercase hex.
+pub fn hash_file(path: &Path) -> io::Result<String> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut hasher = HashingWriter::new(io::sink());
+    io::copy(&mut reader, &mut hasher)?;
+    Ok(hasher.finish().1)
+}
+
+/// Like `hash_file`, but stops between chunks once `cancelled` is set and
+/// returns `Ok(None)`, so hashing a huge file doesn't hold up a cancel.
+pub fn hash_file_cancellable(path: &Path, cancelled: &AtomicBool) -> io::Result<Option<String>> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0u8; 64 * 1024];
+    loop {
+        if cancelled.load(Ordering::Relaxed) {
+            return Ok(None);
+        }
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(Some(to_hex(&hasher.finalize())));
+        }
+        hasher.update(&buffer[..read]);
+    }
+}
+
+/// `disk_usage.json` -> `disk_usage.json.sha256`.
+pub fn sidecar_path(path: &Path) -> PathBuf {
+    let mut sidecar = path.as_os_str().to_owned();
+    sidecar.push(".sha256");
+    PathBuf::from(sidecar)
+}
+
+/// Writes `checksum` for `path` to its sidecar file, in the `sha256sum` format
+/// so it can also be checked with `sha256sum -c`.
+pub fn write_sidecar(path: &Path, checksum: &str) -> Result<(), String> {
+    let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+    fs::write(sidecar_path(path), format!("{}  {}\n", checksum, file_name)).map_err(|e| e.to_string())
+}
+
+/// Checks `path` against its sidecar file, so a truncated or edited export
+/// is never loaded. `Ok(false)` when there is no sidecar to check against,
+/// e.g. a file copied without it.
+pub fn verify(path: &Path) -> Result<bool, String> {
+    let sidecar = sidecar_path(path);
+    let contents = match fs::read_to_string(&sidecar) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(format!("{}: {}", sidecar.display(), e)),
+    };
+    let expected = contents
+        .split_whitespace()
+        .next()
+        .map(str::to_ascii_lowercase)
+        .ok_or_else(|| format!("{}: empty checksum file", sidecar.display()))?;
+    let actual = hash_file(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if actual != expected {
+        return Err(crate::i18n::tr_args("checksum-mismatch", &[
+            ("path", path.display().to_string()),
+            ("expected", expected),
+            ("actual", actual),
+        ]));
+    }
+    Ok(true)
+}
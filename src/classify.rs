@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::SystemTime;
+
+const TEMP_DIRS: [&str; 2] = ["/tmp/", "/var/tmp/"];
+const TEMP_EXTENSIONS: [&str; 4] = ["tmp", "temp", "swp", "bak"];
+
+fn system_temp_dir() -> &'static PathBuf {
+    static TEMP_DIR: OnceLock<PathBuf> = OnceLock::new();
+    TEMP_DIR.get_or_init(std::env::temp_dir)
+}
+
+/// Which of `classify_as_temp`'s rules a file matched.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TempRule {
+    /// Anywhere under a system temp directory.
+    TempDirectory,
+    /// An MS Office `~$` lock file.
+    OfficeLockFile,
+    /// `*.tmp`, `*.temp`, Vim swap files or `*.bak` backups.
+    TempExtension,
+}
+
+impl TempRule {
+    /// Stable name for exports.
+    pub fn id(self) -> &'static str {
+        match self {
+            TempRule::TempDirectory => "temp_directory",
+            TempRule::OfficeLockFile => "office_lock_file",
+            TempRule::TempExtension => "temp_extension",
+        }
+    }
+}
+
+/// True for files that are likely safe to clean up: anything under a system
+/// temp directory, plus `*.tmp`/`*.temp`, Vim swap files, `*.bak` backups and
+/// MS Office `~$` lock files.
+pub fn classify_as_temp(path: &str) -> bool {
+    temp_rule(path).is_some()
+}
+
+/// The first rule of `classify_as_temp` that `path` matches.
+pub fn temp_rule(path: &str) -> Option<TempRule> {
+    let path_ref = Path::new(path);
+    if TEMP_DIRS.iter().any(|dir| path.starts_with(dir)) || path_ref.starts_with(system_temp_dir()) {
+        return Some(TempRule::TempDirectory);
+    }
+
+    let name = path_ref.file_name().and_then(|name| name.to_str()).unwrap_or("");
+    if name.starts_with("~$") {
+        return Some(TempRule::OfficeLockFile);
+    }
+
+    path_ref
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| TEMP_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+        .map(|_| TempRule::TempExtension)
+}
+
+/// Broad kind of file, used to color files consistently wherever they are drawn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FileCategory {
+    Image,
+    Video,
+    Document,
+    Archive,
+    Code,
+    Other,
+}
+
+impl FileCategory {
+    pub const ALL: &[FileCategory] = &[
+        FileCategory::Image,
+        FileCategory::Video,
+        FileCategory::Document,
+        FileCategory::Archive,
+        FileCategory::Code,
+        FileCategory::Other,
+    ];
+}
+
+impl std::fmt::Display for FileCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self {
+            FileCategory::Image => "category-image",
+            FileCategory::Video => "category-video",
+            FileCategory::Document => "category-document",
+            FileCategory::Archive => "category-archive",
+            FileCategory::Code => "category-code",
+            FileCategory::Other => "category-other",
+        };
+        write!(f, "{}", crate::i18n::tr(key))
+    }
+}
+
+const IMAGE_EXTENSIONS: [&str; 11] = ["jpg", "jpeg", "png", "gif", "bmp", "webp", "tif", "tiff", "svg", "heic", "raw"];
+const VIDEO_EXTENSIONS: [&str; 9] = ["mp4", "mkv", "mov", "avi", "wmv", "webm", "m4v", "flv", "mpg"];
+const DOCUMENT_EXTENSIONS: [&str; 12] = ["pdf", "doc", "docx", "xls", "xlsx", "ppt", "pptx", "odt", "ods", "txt", "md", "rtf"];
+const ARCHIVE_EXTENSIONS: [&str; 10] = ["zip", "tar", "gz", "tgz", "bz2", "xz", "7z", "rar", "zst", "iso"];
+const CODE_EXTENSIONS: [&str; 20] = [
+    "rs", "c", "h", "cpp", "hpp", "cs", "java", "kt", "go", "py", "js", "ts", "tsx", "jsx", "rb", "php", "swift", "sh",
+    "html", "css",
+];
+
+/// Categorizes a file by its extension, case-insensitively.
+pub fn categorize(path: &str) -> FileCategory {
+    let extension = match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some(ext) => ext.to_ascii_lowercase(),
+        None => return FileCategory::Other,
+    };
+    let extension = extension.as_str();
+    if IMAGE_EXTENSIONS.contains(&extension) {
+        FileCategory::Image
+    } else if VIDEO_EXTENSIONS.contains(&extension) {
+        FileCategory::Video
+    } else if DOCUMENT_EXTENSIONS.contains(&extension) {
+        FileCategory::Document
+    } else if ARCHIVE_EXTENSIONS.contains(&extension) {
+        FileCategory::Archive
+    } else if CODE_EXTENSIONS.contains(&extension) {
+        FileCategory::Code
+    } else {
+        FileCategory::Other
+    }
+}
+
+/// The category a MIME type found from a file's magic bytes implies, if it
+/// implies one (executables, fonts, audio and the like don't).
+fn mime_category(mime: &str) -> Option<FileCategory> {
+    if mime.starts_with("image/") {
+        return Some(FileCategory::Image);
+    }
+    if mime.starts_with("video/") {
+        return Some(FileCategory::Video);
+    }
+    match mime {
+        "application/pdf"
+        | "application/rtf"
+        | "application/msword"
+        | "application/vnd.ms-excel"
+        | "application/vnd.ms-powerpoint"
+        | "application/x-ole-storage" => Some(FileCategory::Document),
+        _ if mime.starts_with("application/vnd.openxmlformats-officedocument.")
+            || mime.starts_with("application/vnd.oasis.opendocument.") =>
+        {
+            Some(FileCategory::Document)
+        }
+        "application/zip"
+        | "application/x-tar"
+        | "application/gzip"
+        | "application/x-bzip2"
+        | "application/x-xz"
+        | "application/x-7z-compressed"
+        | "application/vnd.rar"
+        | "application/zstd" => Some(FileCategory::Archive),
+        _ => None,
+    }
+}
+
+const EXECUTABLE_MIME_TYPES: [&str; 3] =
+    ["application/vnd.microsoft.portable-executable", "application/x-executable", "application/x-mach-binary"];
+
+/// Whether a file's extension and the MIME type of its content disagree,
+/// e.g. a `.jpg` that is really a zip, or a `.pdf` that is a program. Only
+/// flagged when the extension points to a category; Office and OpenDocument
+/// files are zips inside, so a zip under a document extension is no mismatch.
+pub fn mime_mismatch(path: &str, mime: &str) -> bool {
+    let by_extension = categorize(path);
+    if by_extension == FileCategory::Other {
+        return false;
+    }
+    if EXECUTABLE_MIME_TYPES.contains(&mime) {
+        return by_extension != FileCategory::Code;
+    }
+    match mime_category(mime) {
+        Some(FileCategory::Archive) if mime == "application/zip" && by_extension == FileCategory::Document => false,
+        Some(by_content) => by_content != by_extension,
+        None => false,
+    }
+}
+
+/// A one-character glyph per category for file rows, drawn in the
+/// category's color.
+pub fn category_glyph(category: &FileCategory) -> &'static str {
+    match category {
+        FileCategory::Image => "▣",
+        FileCategory::Video => "▶",
+        FileCategory::Document => "≡",
+        FileCategory::Archive => "▤",
+        FileCategory::Code => "◆",
+        FileCategory::Other => "●",
+    }
+}
+
+/// The one color scheme for file categories; every view that colors files
+/// by type takes its colors from here.
+pub fn file_type_color(category: &FileCategory) -> iced::Color {
+    match category {
+        FileCategory::Image => iced::Color::from_rgb(0.25, 0.5, 0.95),
+        FileCategory::Video => iced::Color::from_rgb(0.9, 0.25, 0.25),
+        FileCategory::Document => iced::Color::from_rgb(0.2, 0.7, 0.3),
+        FileCategory::Archive => iced::Color::from_rgb(0.85, 0.7, 0.1),
+        FileCategory::Code => iced::Color::from_rgb(0.6, 0.3, 0.85),
+        FileCategory::Other => iced::Color::from_rgb(0.55, 0.55, 0.55),
+    }
+}
+
+/// How long ago a file was last modified, in four coarse steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum AgeCategory {
+    /// Under 7 days.
+    Recent,
+    /// 7 to 90 days.
+    Moderate,
+    /// 90 to 365 days.
+    Old,
+    /// Over a year.
+    VeryOld,
+}
+
+impl std::fmt::Display for AgeCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self {
+            AgeCategory::Recent => "age-recent",
+            AgeCategory::Moderate => "age-moderate",
+            AgeCategory::Old => "age-old",
+            AgeCategory::VeryOld => "age-very-old",
+        };
+        write!(f, "{}", crate::i18n::tr(key))
+    }
+}
+
+/// Categorizes a modification time by its age at `now`, taken once by the
+/// caller for all the files it goes through. Times in the future, as left by
+/// a wrong clock, count as recent.
+pub fn classify_age(modified: SystemTime, now: SystemTime) -> AgeCategory {
+    const DAY: u64 = 86_400;
+    let age_days = now.duration_since(modified).map_or(0, |age| age.as_secs() / DAY);
+    match age_days {
+        0..=6 => AgeCategory::Recent,
+        7..=89 => AgeCategory::Moderate,
+        90..=364 => AgeCategory::Old,
+        _ => AgeCategory::VeryOld,
+    }
+}
+
+/// Green for fresh files through red for ones untouched in over a year.
+pub fn age_color(category: AgeCategory) -> iced::Color {
+    match category {
+        AgeCategory::Recent => iced::Color::from_rgb(0.2, 0.7, 0.3),
+        AgeCategory::Moderate => iced::Color::from_rgb(0.85, 0.75, 0.1),
+        AgeCategory::Old => iced::Color::from_rgb(0.95, 0.55, 0.1),
+        AgeCategory::VeryOld => iced::Color::from_rgb(0.9, 0.2, 0.2),
+    }
+}
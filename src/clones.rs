@@ -0,0 +1,92 @@
+use crate::FileInfo;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Directories with fewer direct files than this are too small to call clones.
+const MIN_FILES_PER_DIR: usize = 3;
+/// `(name, size)` entries shared by more directories than this (a `LICENSE`,
+/// an empty `__init__.py`) say nothing about cloning and are skipped to keep
+/// the pair counting from going quadratic.
+const MAX_DIRS_PER_ENTRY: usize = 50;
+/// Minimum Jaccard similarity for two directories to be flagged.
+const SIMILARITY_THRESHOLD: f64 = 0.8;
+
+/// Two directories whose direct files mostly match by name and size.
+#[derive(Debug, Clone)]
+pub struct ClonePair {
+    pub left: String,
+    pub right: String,
+    /// Jaccard similarity of the two fingerprints, 0.0..=1.0.
+    pub similarity: f64,
+    pub shared_files: usize,
+    /// Size of the shared files, which is what deleting one copy would free.
+    pub shared_mb: f64,
+}
+
+/// A directory's `file name -> size in bytes` map, over its direct files only.
+type Fingerprint = BTreeMap<String, u64>;
+
+fn fingerprints<'a>(files: impl Iterator<Item = &'a FileInfo>) -> Vec<(String, Fingerprint)> {
+    let mut by_dir: HashMap<String, Fingerprint> = HashMap::new();
+    for file in files.filter(|file| file.symlink_target.is_none()) {
+        let path = Path::new(&*file.path);
+        if let (Some(parent), Some(name)) = (path.parent(), path.file_name()) {
+            by_dir
+                .entry(parent.display().to_string())
+                .or_default()
+                .insert(name.to_string_lossy().to_string(), (file.size_mb * 1_048_576.0).round() as u64);
+        }
+    }
+    by_dir
+        .into_iter()
+        .filter(|(_, fingerprint)| fingerprint.len() >= MIN_FILES_PER_DIR)
+        .collect()
+}
+
+/// Finds pairs of directories whose fingerprints overlap by more than
+/// `SIMILARITY_THRESHOLD`, largest shared size first. Only directories sharing
+/// at least one entry are ever compared, via an inverted index on entries.
+pub fn find_clone_dirs<'a>(files: impl Iterator<Item = &'a FileInfo>) -> Vec<ClonePair> {
+    let dirs = fingerprints(files);
+
+    let mut index: HashMap<(&str, u64), Vec<usize>> = HashMap::new();
+    for (i, (_, fingerprint)) in dirs.iter().enumerate() {
+        for (name, &size) in fingerprint {
+            index.entry((name.as_str(), size)).or_default().push(i);
+        }
+    }
+
+    let mut shared: HashMap<(usize, usize), (usize, u64)> = HashMap::new();
+    for ((_, size), holders) in &index {
+        if holders.len() < 2 || holders.len() > MAX_DIRS_PER_ENTRY {
+            continue;
+        }
+        for (n, &a) in holders.iter().enumerate() {
+            for &b in &holders[n + 1..] {
+                let entry = shared.entry((a.min(b), a.max(b))).or_default();
+                entry.0 += 1;
+                entry.1 += size;
+            }
+        }
+    }
+
+    let mut pairs: Vec<ClonePair> = shared
+        .into_iter()
+        .filter_map(|((a, b), (shared_files, shared_bytes))| {
+            let union = dirs[a].1.len() + dirs[b].1.len() - shared_files;
+            let similarity = shared_files as f64 / union as f64;
+            (similarity > SIMILARITY_THRESHOLD).then(|| {
+                let (left, right) = if dirs[a].0 <= dirs[b].0 { (a, b) } else { (b, a) };
+                ClonePair {
+                    left: dirs[left].0.clone(),
+                    right: dirs[right].0.clone(),
+                    similarity,
+                    shared_files,
+                    shared_mb: shared_bytes as f64 / 1_048_576.0,
+                }
+            })
+        })
+        .collect();
+    pairs.sort_by(|a, b| b.shared_mb.partial_cmp(&a.shared_mb).unwrap_or(std::cmp::Ordering::Equal));
+    pairs
+}
@@ -0,0 +1,354 @@
+use crate::i18n::{format_count, format_size_mb, tr, tr_args};
+use crate::scan::UnstoredFiles;
+use crate::{util, FileInfo, Message};
+use iced::mouse;
+use iced::widget::canvas::{self, Cache, Canvas, Frame, Geometry};
+use iced::{Color, Element, Length, Point, Rectangle, Renderer, Size, Theme};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Directories charted per disk or scope.
+pub const CHART_DIRS: usize = 15;
+const ROW_HEIGHT: f32 = 22.0;
+const LABEL_WIDTH: f32 = 300.0;
+const LABEL_CHARS: usize = 30;
+/// Bars that would be narrower than this are merged into one "other" bar.
+const MIN_BAR_WIDTH: f32 = 4.0;
+
+/// What the chart's bar colors show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ChartColoring {
+    /// Stronger for directories holding more of the scope.
+    #[default]
+    SizeShare,
+    /// By the newest modification time anywhere beneath the directory.
+    Age,
+}
+
+impl ChartColoring {
+    pub const ALL: &[ChartColoring] = &[ChartColoring::SizeShare, ChartColoring::Age];
+}
+
+impl std::fmt::Display for ChartColoring {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self {
+            ChartColoring::SizeShare => "chart-color-size",
+            ChartColoring::Age => "chart-color-age",
+        };
+        write!(f, "{}", tr(key))
+    }
+}
+
+/// How long ago anything beneath a directory last changed, in steps of days,
+/// weeks, months and years, so data older than a year still spreads over
+/// several colors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgeBand {
+    Day,
+    Week,
+    Month,
+    Quarter,
+    Year,
+    ThreeYears,
+    Older,
+}
+
+impl AgeBand {
+    pub const ALL: &[AgeBand] = &[
+        AgeBand::Day,
+        AgeBand::Week,
+        AgeBand::Month,
+        AgeBand::Quarter,
+        AgeBand::Year,
+        AgeBand::ThreeYears,
+        AgeBand::Older,
+    ];
+
+    fn of(age_secs: u64) -> AgeBand {
+        const DAY: u64 = 86_400;
+        match age_secs / DAY {
+            0 => AgeBand::Day,
+            1..=6 => AgeBand::Week,
+            7..=30 => AgeBand::Month,
+            31..=91 => AgeBand::Quarter,
+            92..=365 => AgeBand::Year,
+            366..=1095 => AgeBand::ThreeYears,
+            _ => AgeBand::Older,
+        }
+    }
+
+    /// Warm for recently active data, cooling off towards the oldest.
+    pub fn color(self) -> Color {
+        match self {
+            AgeBand::Day => Color::from_rgb(0.92, 0.3, 0.2),
+            AgeBand::Week => Color::from_rgb(0.95, 0.55, 0.2),
+            AgeBand::Month => Color::from_rgb(0.9, 0.78, 0.25),
+            AgeBand::Quarter => Color::from_rgb(0.55, 0.78, 0.35),
+            AgeBand::Year => Color::from_rgb(0.3, 0.68, 0.62),
+            AgeBand::ThreeYears => Color::from_rgb(0.3, 0.5, 0.85),
+            AgeBand::Older => Color::from_rgb(0.48, 0.36, 0.78),
+        }
+    }
+}
+
+impl std::fmt::Display for AgeBand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self {
+            AgeBand::Day => "age-band-day",
+            AgeBand::Week => "age-band-week",
+            AgeBand::Month => "age-band-month",
+            AgeBand::Quarter => "age-band-quarter",
+            AgeBand::Year => "age-band-year",
+            AgeBand::ThreeYears => "age-band-three-years",
+            AgeBand::Older => "age-band-older",
+        };
+        write!(f, "{}", tr(key))
+    }
+}
+
+/// One charted directory: its size and the newest modification time of any
+/// file beneath it.
+struct ChartDir {
+    path: PathBuf,
+    size_mb: f64,
+    newest: Option<u64>,
+    label: String,
+}
+
+/// The directories from some rank on, merged into one bar.
+struct OtherDirs {
+    size_mb: f64,
+    newest: Option<u64>,
+    label: String,
+}
+
+/// The largest directories directly below a disk or scope, with the cache of
+/// their drawn bars. Built whenever the files, scope or filters change, so
+/// the geometry is only redrawn then or when the chart is resized.
+pub struct DirChart {
+    disk: usize,
+    dirs: Vec<ChartDir>,
+    /// For each rank, the "other" bar of the directories from it on, so a
+    /// draw at any width only has to lay the bars out.
+    others: Vec<OtherDirs>,
+    /// Directories left out for being under the minimum size.
+    small_dirs: usize,
+    /// Size of all directories below `root`, charted or not.
+    total_mb: f64,
+    coloring: ChartColoring,
+    now: u64,
+    cache: Cache,
+}
+
+struct Bar<'a> {
+    bounds: Rectangle,
+    label: &'a str,
+    size_mb: f64,
+    newest: Option<u64>,
+    /// `None` for the merged "other" bar.
+    dir: Option<&'a PathBuf>,
+}
+
+impl DirChart {
+    /// Sums `files`, and the files only tallied per directory in `unstored`,
+    /// by the subdirectory of `root` they sit in, keeping the `CHART_DIRS`
+    /// largest of at least `min_size_mb`. Files directly in `root` belong to
+    /// no bar.
+    pub fn new<'f>(
+        disk: usize,
+        root: &Path,
+        files: impl Iterator<Item = &'f FileInfo>,
+        unstored: impl Iterator<Item = (&'f String, &'f UnstoredFiles)>,
+        min_size_mb: f64,
+        coloring: ChartColoring,
+    ) -> DirChart {
+        let mut sizes: HashMap<PathBuf, (f64, Option<u64>)> = HashMap::new();
+        for file in files.filter(|file| file.symlink_target.is_none()) {
+            let Ok(relative) = Path::new(&*file.path).strip_prefix(root) else {
+                continue;
+            };
+            let mut components = relative.components();
+            if let (Some(child), Some(_)) = (components.next(), components.next()) {
+                let (size, newest) = sizes.entry(root.join(child)).or_default();
+                *size += file.size_mb;
+                *newest = (*newest).max(file.modified);
+            }
+        }
+        for (dir, tally) in unstored {
+            let Ok(relative) = Path::new(dir.as_str()).strip_prefix(root) else {
+                continue;
+            };
+            if let Some(child) = relative.components().next() {
+                sizes.entry(root.join(child)).or_default().0 += tally.size_mb;
+            }
+        }
+        let total = sizes.len();
+        let total_mb = sizes.values().map(|(size, _)| size).sum();
+        let mut dirs: Vec<ChartDir> = sizes
+            .into_iter()
+            .filter(|(_, (size, _))| *size >= min_size_mb)
+            .map(|(path, (size_mb, newest))| {
+                let name = path.strip_prefix(root).unwrap_or(&path).display().to_string();
+                let label = format!("{} — {}", util::truncate_middle(&name, LABEL_CHARS), format_size_mb(size_mb));
+                ChartDir { path, size_mb, newest, label }
+            })
+            .collect();
+        let small_dirs = total - dirs.len();
+        dirs.sort_by(|a, b| b.size_mb.partial_cmp(&a.size_mb).unwrap_or(std::cmp::Ordering::Equal));
+        dirs.truncate(CHART_DIRS);
+        let others = (0..dirs.len())
+            .map(|rank| {
+                let rest = &dirs[rank..];
+                let size_mb = rest.iter().map(|dir| dir.size_mb).sum();
+                OtherDirs {
+                    size_mb,
+                    newest: rest.iter().filter_map(|dir| dir.newest).max(),
+                    label: tr_args("chart-other-dirs", &[("count", format_count(rest.len())), ("size", format_size_mb(size_mb))]),
+                }
+            })
+            .collect();
+        DirChart {
+            disk,
+            dirs,
+            others,
+            small_dirs,
+            total_mb,
+            coloring,
+            now: util::unix_now(),
+            cache: Cache::new(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.dirs.is_empty()
+    }
+
+    pub fn small_dirs(&self) -> usize {
+        self.small_dirs
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        Canvas::new(self)
+            .width(Length::Fill)
+            .height(Length::Fixed(self.dirs.len() as f32 * ROW_HEIGHT))
+            .into()
+    }
+
+    /// By share, the "other" bar stays grey; by age, it takes the newest of
+    /// its directories. Without any modification time a bar is grey too.
+    fn color(&self, theme: &Theme, bar: &Bar) -> Color {
+        let grey = theme.extended_palette().background.strong.color;
+        match self.coloring {
+            ChartColoring::SizeShare if bar.dir.is_none() => grey,
+            ChartColoring::SizeShare => {
+                let share = if self.total_mb > 0.0 { (bar.size_mb / self.total_mb) as f32 } else { 0.0 };
+                Color { a: 0.35 + 0.65 * share.clamp(0.0, 1.0), ..theme.extended_palette().primary.base.color }
+            }
+            ChartColoring::Age => bar.newest.map_or(grey, |newest| AgeBand::of(self.now.saturating_sub(newest)).color()),
+        }
+    }
+
+    /// One bar per directory, scaled against the largest, until they get too
+    /// narrow; the rest become one "other" bar.
+    fn bars(&self, width: f32) -> Vec<Bar<'_>> {
+        let largest = self.dirs.first().map_or(0.0, |dir| dir.size_mb);
+        let scale = if largest > 0.0 { (width - LABEL_WIDTH).max(0.0) as f64 / largest } else { 0.0 };
+        let row = |index: usize, size: f64| Rectangle::new(
+            Point::new(LABEL_WIDTH, index as f32 * ROW_HEIGHT + 3.0),
+            Size::new(((size * scale) as f32).max(1.0), ROW_HEIGHT - 6.0),
+        );
+
+        let shown = self.dirs.iter().take_while(|dir| (dir.size_mb * scale) as f32 >= MIN_BAR_WIDTH).count();
+        let mut bars: Vec<Bar> = self.dirs[..shown]
+            .iter()
+            .enumerate()
+            .map(|(index, dir)| Bar {
+                bounds: row(index, dir.size_mb),
+                label: &dir.label,
+                size_mb: dir.size_mb,
+                newest: dir.newest,
+                dir: Some(&dir.path),
+            })
+            .collect();
+        if let Some(other) = self.others.get(shown) {
+            bars.push(Bar {
+                bounds: row(shown, other.size_mb),
+                label: &other.label,
+                size_mb: other.size_mb,
+                newest: other.newest,
+                dir: None,
+            });
+        }
+        bars
+    }
+
+    /// The bar row under `point`, whole rows being clickable rather than
+    /// only the painted part.
+    fn bar_at<'a>(bars: &'a [Bar<'a>], point: Point) -> Option<&'a Bar<'a>> {
+        bars.iter().find(|bar| point.y >= bar.bounds.y - 3.0 && point.y < bar.bounds.y - 3.0 + ROW_HEIGHT)
+    }
+}
+
+impl canvas::Program<Message> for DirChart {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut (),
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (canvas::event::Status, Option<Message>) {
+        let canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event else {
+            return (canvas::event::Status::Ignored, None);
+        };
+        let Some(point) = cursor.position_in(bounds) else {
+            return (canvas::event::Status::Ignored, None);
+        };
+        let bars = self.bars(bounds.width);
+        match DirChart::bar_at(&bars, point).and_then(|bar| bar.dir) {
+            Some(dir) => (canvas::event::Status::Captured, Some(Message::SetScope(self.disk, Some(dir.clone())))),
+            None => (canvas::event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(&self, _state: &(), renderer: &Renderer, theme: &Theme, bounds: Rectangle, cursor: mouse::Cursor) -> Vec<Geometry> {
+        let palette = theme.extended_palette();
+        let bars = self.bars(bounds.width);
+        let geometry = self.cache.draw(renderer, bounds.size(), |frame| {
+            for bar in &bars {
+                frame.fill_rectangle(bar.bounds.position(), bar.bounds.size(), self.color(theme, bar));
+                frame.fill_text(canvas::Text {
+                    content: bar.label.to_string(),
+                    position: Point::new(0.0, bar.bounds.y - 1.0),
+                    color: palette.background.base.text,
+                    size: 14.0,
+                    ..canvas::Text::default()
+                });
+            }
+        });
+
+        // Hover is redrawn every frame on top of the cached bars.
+        let mut hover = Frame::new(renderer, bounds.size());
+        if let Some(bar) = cursor.position_in(bounds).and_then(|point| DirChart::bar_at(&bars, point)) {
+            if bar.dir.is_some() {
+                hover.fill_rectangle(
+                    Point::new(0.0, bar.bounds.y - 3.0),
+                    Size::new(bounds.width, ROW_HEIGHT),
+                    Color { a: 0.12, ..palette.primary.strong.color },
+                );
+                hover.fill_rectangle(bar.bounds.position(), bar.bounds.size(), Color { a: 1.0, ..self.color(theme, bar) });
+            }
+        }
+        vec![geometry, hover.into_geometry()]
+    }
+
+    fn mouse_interaction(&self, _state: &(), bounds: Rectangle, cursor: mouse::Cursor) -> mouse::Interaction {
+        let bars = self.bars(bounds.width);
+        match cursor.position_in(bounds).and_then(|point| DirChart::bar_at(&bars, point)) {
+            Some(bar) if bar.dir.is_some() => mouse::Interaction::Pointer,
+            _ => mouse::Interaction::default(),
+        }
+    }
+}
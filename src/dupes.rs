@@ -0,0 +1,369 @@
+use crate::checksum;
+use crate::scan;
+use crate::settings::{AppSettings, FilterPreset};
+use crate::{BinaryFilter, FileFacts};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use rayon::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateSet {
+    pub hash: String,
+    pub size: u64,
+    pub paths: Vec<String>,
+    pub reclaimable_bytes: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Json,
+    Text,
+    Yaml,
+    Toml,
+}
+
+struct DupesArgs {
+    path: PathBuf,
+    min_size: u64,
+    format: OutputFormat,
+    output: Option<PathBuf>,
+    quiet: bool,
+    compress: bool,
+    preset: Option<FilterPreset>,
+}
+
+/// Where results go; gzip output needs an explicit `finish` to write its trailer.
+enum Output {
+    Plain(Box<dyn Write>),
+    Gzip(GzEncoder<Box<dyn Write>>),
+}
+
+impl Output {
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Output::Plain(mut out) => out.flush(),
+            Output::Gzip(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Output::Plain(out) => out.write(buf),
+            Output::Gzip(encoder) => encoder.write(buf),
+        }
+    }
+
+    // Gzip output is only flushed by `finish`, since each flush costs compression ratio.
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Output::Plain(out) => out.flush(),
+            Output::Gzip(_) => Ok(()),
+        }
+    }
+}
+
+const USAGE: &str = "Usage: disk_usage_visualizer dupes --path <dir> [--min-size <size>] [--format json|text|yaml|toml] [--output <file>] [--preset <name>] [--compress] [--quiet]";
+
+/// Walks `root` and groups regular files of at least `min_size` bytes by size.
+/// Only sizes shared by two or more files are returned, since a file with a
+/// unique size can't have a duplicate and never needs hashing. With a
+/// `preset`, only files matching its filters are considered.
+pub fn collect_candidates(
+    root: &Path,
+    min_size: u64,
+    preset: Option<&FilterPreset>,
+    errors: &AtomicUsize,
+) -> Vec<(u64, Vec<PathBuf>)> {
+    let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+    let now = crate::util::unix_now();
+
+    for entry in WalkDir::new(root) {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(_) => {
+                errors.fetch_add(1, Ordering::SeqCst);
+                continue;
+            }
+        };
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        match entry.metadata() {
+            Ok(metadata) if metadata.len() >= min_size => {
+                let matches = preset.is_none_or(|preset| {
+                    // Sniffing every candidate is only worth it when the preset asks.
+                    let is_binary = if preset.binary == BinaryFilter::All { None } else { scan::is_binary(entry.path()) };
+                    let mime_type = if preset.needs_mime_type() { scan::mime_type(entry.path()) } else { None };
+                    let path = entry.path().display().to_string();
+                    let owner_and_mode = scan::owner_and_mode(&metadata);
+                    preset.matches(&FileFacts {
+                        path: &path,
+                        is_hidden: scan::is_hidden(root, entry.path(), &metadata),
+                        is_binary,
+                        mime_type: mime_type.as_deref(),
+                        size_mb: metadata.len() as f64 / 1_048_576.0,
+                        modified: scan::modified_secs(&metadata),
+                        uid: owner_and_mode.map(|(uid, _)| uid),
+                        mode: owner_and_mode.map(|(_, mode)| mode),
+                    }, now)
+                });
+                if !matches {
+                    continue;
+                }
+                by_size.entry(metadata.len()).or_default().push(entry.into_path());
+            }
+            Ok(_) => {}
+            Err(_) => {
+                errors.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+    }
+
+    let mut candidates: Vec<(u64, Vec<PathBuf>)> = by_size
+        .into_iter()
+        .filter(|(_, paths)| paths.len() > 1)
+        .collect();
+    candidates.sort_by_key(|&(size, _)| std::cmp::Reverse(size));
+    candidates
+}
+
+/// Hashes each size group in parallel and calls `on_set` as soon as a group
+/// yields one or more duplicate sets, so callers can stream results.
+pub fn find_duplicates<F>(candidates: Vec<(u64, Vec<PathBuf>)>, errors: &AtomicUsize, on_set: F)
+where
+    F: Fn(DuplicateSet) + Sync,
+{
+    candidates.into_par_iter().for_each(|(size, paths)| {
+        let mut by_hash: HashMap<String, Vec<String>> = HashMap::new();
+        for path in paths {
+            match checksum::hash_file(&path) {
+                Ok(hash) => by_hash.entry(hash).or_default().push(path.display().to_string()),
+                Err(_) => {
+                    errors.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        }
+
+        for (hash, mut paths) in by_hash {
+            if paths.len() > 1 {
+                paths.sort();
+                on_set(DuplicateSet {
+                    hash,
+                    size,
+                    reclaimable_bytes: size * (paths.len() as u64 - 1),
+                    paths,
+                });
+            }
+        }
+    });
+}
+
+/// Parses sizes such as `4096`, `512K`, `1MB` or `2GiB` into bytes (binary units).
+fn parse_size(input: &str) -> Result<u64, String> {
+    let input = input.trim();
+    let split = input
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(input.len());
+    let (number, unit) = input.split_at(split);
+    let number: f64 = number
+        .parse()
+        .map_err(|_| format!("Invalid size: {}", input))?;
+    let multiplier: u64 = match unit.trim().to_ascii_uppercase().as_str() {
+        "" | "B" => 1,
+        "K" | "KB" | "KIB" => 1024,
+        "M" | "MB" | "MIB" => 1_048_576,
+        "G" | "GB" | "GIB" => 1_073_741_824,
+        "T" | "TB" | "TIB" => 1_099_511_627_776,
+        _ => return Err(format!("Invalid size unit: {}", unit)),
+    };
+    Ok((number * multiplier as f64) as u64)
+}
+
+fn parse_args(args: &[String]) -> Result<DupesArgs, String> {
+    let mut path = None;
+    let mut min_size = 1;
+    let mut format = OutputFormat::Json;
+    let mut output = None;
+    let mut quiet = false;
+    let mut compress = false;
+    let mut preset = None;
+
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = || iter.next().cloned().ok_or_else(|| format!("Missing value for {}", arg));
+        match arg.as_str() {
+            "--path" => path = Some(PathBuf::from(value()?)),
+            "--min-size" => min_size = parse_size(&value()?)?,
+            "--format" => {
+                format = match value()?.as_str() {
+                    "json" => OutputFormat::Json,
+                    "text" => OutputFormat::Text,
+                    "yaml" => OutputFormat::Yaml,
+                    "toml" => OutputFormat::Toml,
+                    other => return Err(format!("Unknown format: {}", other)),
+                }
+            }
+            "--output" => output = Some(PathBuf::from(value()?)),
+            "--quiet" | "-q" => quiet = true,
+            "--compress" => compress = true,
+            "--preset" => {
+                let name = value()?;
+                preset = Some(
+                    AppSettings::load()
+                        .preset(&name)
+                        .cloned()
+                        .ok_or_else(|| format!("Unknown preset: {}", name))?,
+                );
+            }
+            other => return Err(format!("Unknown argument: {}", other)),
+        }
+    }
+
+    Ok(DupesArgs {
+        path: path.ok_or("Missing required argument --path")?,
+        min_size,
+        format,
+        // Compressed output files get `.gz` appended unless the name already has it.
+        output: output.map(|output| {
+            if compress && output.extension().is_none_or(|ext| ext != "gz") {
+                let mut name = output.into_os_string();
+                name.push(".gz");
+                PathBuf::from(name)
+            } else {
+                output
+            }
+        }),
+        quiet,
+        compress,
+        preset,
+    })
+}
+
+/// Each set is written as its own document so output still streams: NDJSON
+/// lines, `---`-separated YAML documents, or one `[[duplicate]]` TOML table.
+fn write_set(out: &mut dyn Write, set: &DuplicateSet, format: OutputFormat) -> io::Result<()> {
+    match format {
+        OutputFormat::Json => {
+            serde_json::to_writer(&mut *out, set)?;
+            writeln!(out)?;
+        }
+        OutputFormat::Text => {
+            writeln!(out, "{}  {} bytes  {} reclaimable", set.hash, set.size, set.reclaimable_bytes)?;
+            for path in &set.paths {
+                writeln!(out, "    {}", path)?;
+            }
+        }
+        OutputFormat::Yaml => {
+            writeln!(out, "---")?;
+            serde_yaml::to_writer(&mut *out, set).map_err(io::Error::other)?;
+        }
+        OutputFormat::Toml => {
+            #[derive(Serialize)]
+            struct Table<'a> {
+                duplicate: Vec<&'a DuplicateSet>,
+            }
+            let text = toml::to_string(&Table { duplicate: vec![set] }).map_err(io::Error::other)?;
+            writeln!(out, "{}", text)?;
+        }
+    }
+    Ok(())
+}
+
+/// Entry point for `disk_usage_visualizer dupes ...`. Returns the process exit
+/// code: 0 on success whether or not duplicates were found, 1 on I/O errors and
+/// 2 on invalid arguments.
+pub fn run_cli(args: &[String]) -> i32 {
+    let args = match parse_args(args) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            return 2;
+        }
+    };
+
+    let sink: Box<dyn Write> = match &args.output {
+        Some(path) => match File::create(path) {
+            Ok(file) => Box::new(BufWriter::new(file)),
+            Err(e) => {
+                eprintln!("Failed to create {}: {}", path.display(), e);
+                return 1;
+            }
+        },
+        None => Box::new(io::stdout()),
+    };
+    let mut out = if args.compress {
+        Output::Gzip(GzEncoder::new(sink, Compression::default()))
+    } else {
+        Output::Plain(sink)
+    };
+
+    let errors = Arc::new(AtomicUsize::new(0));
+    if !args.quiet {
+        eprintln!("Scanning {}...", args.path.display());
+    }
+    let candidates = collect_candidates(&args.path, args.min_size, args.preset.as_ref(), &errors);
+    if !args.quiet {
+        let file_count: usize = candidates.iter().map(|(_, paths)| paths.len()).sum();
+        eprintln!("Hashing {} files in {} size groups...", file_count, candidates.len());
+    }
+
+    let (tx, rx) = mpsc::channel();
+    let hash_errors = Arc::clone(&errors);
+    let worker = thread::spawn(move || {
+        let tx = Mutex::new(tx);
+        find_duplicates(candidates, &hash_errors, |set| {
+            let _ = tx.lock().unwrap().send(set);
+        });
+    });
+
+    let mut set_count = 0;
+    let mut reclaimable = 0;
+    let mut write_failed = false;
+    for set in rx {
+        set_count += 1;
+        reclaimable += set.reclaimable_bytes;
+        if !write_failed {
+            if let Err(e) = write_set(&mut out, &set, args.format).and_then(|_| out.flush()) {
+                eprintln!("Failed to write output: {}", e);
+                write_failed = true;
+            }
+        }
+        if !args.quiet {
+            eprint!("\rFound {} duplicate sets", set_count);
+        }
+    }
+    let _ = worker.join();
+    if let Err(e) = out.finish() {
+        if !write_failed {
+            eprintln!("Failed to write output: {}", e);
+            write_failed = true;
+        }
+    }
+
+    let error_count = errors.load(Ordering::SeqCst);
+    if !args.quiet {
+        eprintln!(
+            "\rFound {} duplicate sets, {:.2} MB reclaimable ({} I/O errors)",
+            set_count,
+            reclaimable as f64 / 1_048_576.0,
+            error_count
+        );
+    }
+
+    if write_failed || error_count > 0 {
+        1
+    } else {
+        0
+    }
+}
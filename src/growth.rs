@@ -0,0 +1,223 @@
+use crate::history::ScanRecord;
+use crate::i18n::{format_date_time, tr_args};
+use crate::report;
+use crate::{DiskInfo, FileInfo};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Files are summed into the directory this many levels below their disk's
+/// mount point (or into their own directory when it is shallower), so the
+/// directories compared never contain one another.
+pub const GROWTH_DEPTH: usize = 3;
+/// Directories under this size aren't kept in history records, which keeps
+/// the history file small.
+pub const MIN_RECORDED_MB: f64 = 1.0;
+/// Directories kept per history record at most, the largest ones.
+pub const MAX_RECORDED_DIRS: usize = 500;
+/// Grown directories listed in the panel and the export.
+pub const GROWTH_ROWS: usize = 20;
+/// Extensions listed in the panel, those that grew or shrank most.
+pub const EXTENSION_GROWTH_ROWS: usize = 10;
+pub const GROWTH_EXPORT_PATH: &str = "what_grew.csv";
+
+/// Sizes in MB of the directories `GROWTH_DEPTH` levels below `mount_point`,
+/// keyed by path.
+pub fn dir_sizes<'a>(mount_point: &str, files: impl Iterator<Item = &'a FileInfo>) -> HashMap<String, f64> {
+    let mut sizes: HashMap<String, f64> = HashMap::new();
+    for file in files.filter(|file| file.symlink_target.is_none()) {
+        let Some(parent) = Path::new(&*file.path).parent() else {
+            continue;
+        };
+        let dir = match parent.strip_prefix(mount_point) {
+            Ok(relative) => {
+                let mut dir = PathBuf::from(mount_point);
+                dir.extend(relative.components().take(GROWTH_DEPTH));
+                dir
+            }
+            Err(_) => parent.to_path_buf(),
+        };
+        *sizes.entry(dir.display().to_string()).or_default() += file.size_mb;
+    }
+    sizes
+}
+
+/// The directory sizes kept with a scan record.
+pub fn recorded_dir_sizes(disk: &DiskInfo) -> HashMap<String, f64> {
+    let mut sizes = dir_sizes(&disk.mount_point, disk.files.iter());
+    sizes.retain(|_, size| *size >= MIN_RECORDED_MB);
+    if sizes.len() <= MAX_RECORDED_DIRS {
+        return sizes;
+    }
+    let mut largest: Vec<(String, f64)> = sizes.into_iter().collect();
+    largest.select_nth_unstable_by(MAX_RECORDED_DIRS - 1, |a, b| b.1.total_cmp(&a.1));
+    largest.truncate(MAX_RECORDED_DIRS);
+    largest.into_iter().collect()
+}
+
+/// How much the files of one extension (`report::extension_key`) grew
+/// between two scans.
+#[derive(Debug, Clone)]
+pub struct ExtensionGrowth {
+    pub extension: String,
+    pub delta_mb: f64,
+    pub old_count: usize,
+    pub new_count: usize,
+}
+
+/// Size change per extension from `old` to `new`, largest growth first. Only
+/// the file entries kept by each scan count, and only local disks found in
+/// both, by name or mount point: imported ones are another machine's, and a
+/// disk in just one scan would count as all growth or all shrinkage.
+pub fn compute_extension_growth(old: &[DiskInfo], new: &[DiskInfo]) -> Vec<ExtensionGrowth> {
+    let is_local = |disk: &&DiskInfo| disk.source.is_none();
+    let same_disk = |a: &DiskInfo, b: &DiskInfo| a.name == b.name || a.mount_point == b.mount_point;
+    let totals = |disks: &[DiskInfo], others: &[DiskInfo]| {
+        let files = disks
+            .iter()
+            .filter(is_local)
+            .filter(|disk| others.iter().filter(is_local).any(|other| same_disk(disk, other)))
+            .flat_map(|disk| disk.files.iter());
+        report::by_extension(files)
+            .into_iter()
+            .map(|(extension, count, size_mb)| (extension, (count, size_mb)))
+            .collect::<HashMap<_, _>>()
+    };
+    let (old, mut new) = (totals(old, new), totals(new, old));
+    let mut growth: Vec<ExtensionGrowth> = old
+        .into_iter()
+        .map(|(extension, (old_count, old_mb))| {
+            let (new_count, new_mb) = new.remove(&extension).unwrap_or((0, 0.0));
+            ExtensionGrowth { extension, delta_mb: new_mb - old_mb, old_count, new_count }
+        })
+        .collect();
+    growth.extend(new.into_iter().map(|(extension, (new_count, new_mb))| ExtensionGrowth {
+        extension,
+        delta_mb: new_mb,
+        old_count: 0,
+        new_count,
+    }));
+    growth.sort_by(|a, b| b.delta_mb.partial_cmp(&a.delta_mb).unwrap_or(std::cmp::Ordering::Equal));
+    growth
+}
+
+/// What the current scan is compared against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GrowthBaseline {
+    /// An earlier scan of this machine from the history, by its timestamp.
+    Scan(u64),
+    /// Disks imported from the named host.
+    Import(String),
+    /// Scan results still kept in memory, by the time of their newest scan.
+    Snapshot(u64),
+}
+
+impl std::fmt::Display for GrowthBaseline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            GrowthBaseline::Scan(timestamp) => tr_args("growth-baseline-scan", &[(
+                "date",
+                format_date_time(UNIX_EPOCH + Duration::from_secs(*timestamp)),
+            )]),
+            GrowthBaseline::Import(host) => tr_args("growth-baseline-import", &[("host", host.clone())]),
+            GrowthBaseline::Snapshot(timestamp) => tr_args("growth-baseline-snapshot", &[(
+                "date",
+                format_date_time(UNIX_EPOCH + Duration::from_secs(*timestamp)),
+            )]),
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// One directory's size in the baseline and now.
+#[derive(Debug, Clone)]
+pub struct DirGrowth {
+    pub disk: String,
+    pub path: String,
+    pub before_mb: f64,
+    pub after_mb: f64,
+    /// The directory's size in each recorded scan of its disk, oldest first;
+    /// only filled in when there are more scans than the two compared.
+    pub trend: Vec<f64>,
+}
+
+impl DirGrowth {
+    pub fn growth_mb(&self) -> f64 {
+        self.after_mb - self.before_mb
+    }
+
+    /// `None` for directories that are new since the baseline.
+    pub fn percent(&self) -> Option<f64> {
+        (self.before_mb > 0.0).then(|| self.growth_mb() / self.before_mb * 100.0)
+    }
+}
+
+/// The directories that grew most since the baseline, and those that are
+/// gone from the current scan.
+#[derive(Debug, Clone, Default)]
+pub struct GrowthReport {
+    pub baseline: Option<GrowthBaseline>,
+    pub grown: Vec<DirGrowth>,
+    pub freed: Vec<DirGrowth>,
+    /// Filled in when both scans' file lists are at hand, i.e. against
+    /// scans kept in memory.
+    pub extensions: Vec<ExtensionGrowth>,
+}
+
+impl GrowthReport {
+    pub fn new(baseline: GrowthBaseline) -> GrowthReport {
+        GrowthReport { baseline: Some(baseline), ..GrowthReport::default() }
+    }
+
+    /// Compares one disk. New directories count their whole size as growth.
+    pub fn add_disk(&mut self, disk: &str, current: &HashMap<String, f64>, baseline: &HashMap<String, f64>) {
+        for (path, &after_mb) in current {
+            let before_mb = baseline.get(path).copied().unwrap_or(0.0);
+            if after_mb > before_mb {
+                self.grown.push(DirGrowth { disk: disk.to_string(), path: path.clone(), before_mb, after_mb, trend: Vec::new() });
+            }
+        }
+        for (path, &before_mb) in baseline.iter().filter(|(path, _)| !current.contains_key(*path)) {
+            self.freed.push(DirGrowth { disk: disk.to_string(), path: path.clone(), before_mb, after_mb: 0.0, trend: Vec::new() });
+        }
+    }
+
+    /// Keeps the `GROWTH_ROWS` largest growths and orders what was freed by
+    /// size, then fills in each grown directory's trend from `records`.
+    pub fn finish(mut self, records: &[ScanRecord]) -> GrowthReport {
+        self.grown.sort_by(|a, b| b.growth_mb().partial_cmp(&a.growth_mb()).unwrap_or(std::cmp::Ordering::Equal));
+        self.grown.truncate(GROWTH_ROWS);
+        self.freed.sort_by(|a, b| b.before_mb.partial_cmp(&a.before_mb).unwrap_or(std::cmp::Ordering::Equal));
+        for dir in &mut self.grown {
+            let mut scans: Vec<&ScanRecord> = records.iter().filter(|record| record.disk.name == dir.disk).collect();
+            scans.sort_by_key(|record| record.timestamp);
+            if scans.len() > 2 {
+                dir.trend = scans.iter().map(|record| record.dirs.get(&dir.path).copied().unwrap_or(0.0)).collect();
+            }
+        }
+        self
+    }
+
+    /// Writes the grown and freed directories to `GROWTH_EXPORT_PATH`,
+    /// returning the path written.
+    pub fn export_csv(&self) -> Result<String, String> {
+        let mut wtr = csv::Writer::from_path(GROWTH_EXPORT_PATH).map_err(|e| e.to_string())?;
+        wtr.write_record(["status", "disk", "path", "before_mb", "after_mb", "growth_mb", "growth_percent"])
+            .map_err(|e| e.to_string())?;
+        let rows = self.grown.iter().map(|dir| ("grown", dir)).chain(self.freed.iter().map(|dir| ("freed", dir)));
+        for (status, dir) in rows {
+            wtr.write_record([
+                status.to_string(),
+                dir.disk.clone(),
+                dir.path.clone(),
+                format!("{:.2}", dir.before_mb),
+                format!("{:.2}", dir.after_mb),
+                format!("{:.2}", dir.growth_mb()),
+                dir.percent().map(|percent| format!("{:.1}", percent)).unwrap_or_default(),
+            ])
+            .map_err(|e| e.to_string())?;
+        }
+        wtr.flush().map_err(|e| e.to_string())?;
+        Ok(GROWTH_EXPORT_PATH.to_string())
+    }
+}
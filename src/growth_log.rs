@@ -0,0 +1,110 @@
+use crate::DiskInfo;
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::Duration;
+
+const CSV_HEADER: &str = "timestamp,hostname,disk,total_bytes,used_bytes,file_count";
+const OPEN_ATTEMPTS: u32 = 5;
+
+/// One line per disk per scan, for plotting growth in external tools.
+#[derive(Debug, Clone, Serialize)]
+pub struct GrowthRecord {
+    pub timestamp: u64,
+    pub hostname: String,
+    pub disk: String,
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub file_count: usize,
+}
+
+pub fn default_path() -> PathBuf {
+    crate::util::data_dir().join("growth.csv")
+}
+
+pub fn records_for(disks: &[DiskInfo], timestamp: u64) -> Vec<GrowthRecord> {
+    let hostname = crate::util::hostname();
+    disks
+        .iter()
+        .filter(|disk| disk.source.is_none() && disk.skipped_reason.is_none())
+        .map(|disk| GrowthRecord {
+            timestamp,
+            hostname: hostname.clone(),
+            disk: if disk.mount_point.is_empty() { disk.name.clone() } else { disk.mount_point.clone() },
+            total_bytes: (disk.total_space * 1_073_741_824.0).round() as u64,
+            used_bytes: (disk.used_space * 1_073_741_824.0).round() as u64,
+            file_count: disk.total_files(),
+        })
+        .collect()
+}
+
+/// `.ndjson`/`.jsonl` files get one JSON object per line; anything else is CSV.
+fn is_ndjson(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "ndjson" | "jsonl"))
+}
+
+fn format_lines(path: &Path, records: &[GrowthRecord]) -> Result<String, String> {
+    let mut lines = String::new();
+    for record in records {
+        if is_ndjson(path) {
+            lines.push_str(&serde_json::to_string(record).map_err(|e| e.to_string())?);
+            lines.push('\n');
+        } else {
+            let mut writer = csv::WriterBuilder::new().has_headers(false).from_writer(Vec::new());
+            writer.serialize(record).map_err(|e| e.to_string())?;
+            let row = writer.into_inner().map_err(|e| e.to_string())?;
+            lines.push_str(&String::from_utf8_lossy(&row));
+        }
+    }
+    Ok(lines)
+}
+
+/// Lines that couldn't be appended, e.g. while the log is open in Excel, wait
+/// here and are folded in on the next successful append.
+fn pending_path(path: &Path) -> PathBuf {
+    let mut pending = path.as_os_str().to_owned();
+    pending.push(".pending");
+    PathBuf::from(pending)
+}
+
+/// Appends `records` to `path`, creating it (with a CSV header) if needed.
+/// A file locked by another program is retried with backoff; if it stays
+/// locked the lines are parked in a `.pending` file instead of being lost.
+/// Blocking, so callers run it off the UI thread.
+pub fn append(path: &Path, records: &[GrowthRecord]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let pending = pending_path(path);
+    let mut lines = fs::read_to_string(&pending).unwrap_or_default();
+    lines.push_str(&format_lines(path, records)?);
+
+    let mut last_error = None;
+    for attempt in 0..OPEN_ATTEMPTS {
+        if attempt > 0 {
+            thread::sleep(Duration::from_millis(200 << attempt));
+        }
+        match OpenOptions::new().create(true).append(true).open(path) {
+            Ok(mut file) => {
+                let needs_header = !is_ndjson(path) && file.metadata().map(|m| m.len() == 0).unwrap_or(false);
+                if needs_header {
+                    lines.insert_str(0, &format!("{}\n", CSV_HEADER));
+                }
+                file.write_all(lines.as_bytes()).map_err(|e| e.to_string())?;
+                let _ = fs::remove_file(&pending);
+                return Ok(());
+            }
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    fs::write(&pending, lines).map_err(|e| e.to_string())?;
+    Err(crate::i18n::tr_args("growth-log-locked", &[
+        ("path", path.display().to_string()),
+        ("error", last_error.map(|e| e.to_string()).unwrap_or_default()),
+    ]))
+}
@@ -0,0 +1,46 @@
+use crate::FileInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Directory entries that share one `(device, inode)`, i.e. hardlinks to the
+/// same data. Deleting any one of them frees nothing while the others remain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardlinkGroup {
+    pub device: u64,
+    pub inode: u64,
+    pub paths: Vec<String>,
+    /// Size of the shared data, counted once.
+    pub size_mb: f64,
+}
+
+impl HardlinkGroup {
+    /// What listing every entry separately over-counts: the size once per
+    /// extra link. This is also what the entries appear to use beyond the
+    /// data itself, none of which is freed by deleting a single link.
+    pub fn duplicated_mb(&self) -> f64 {
+        self.size_mb * self.paths.len().saturating_sub(1) as f64
+    }
+}
+
+/// Groups files by `(device, inode)`, keeping only inodes reached through two
+/// or more paths, largest first. Symlinks are skipped since their inode is
+/// the link's own. Files scanned off Unix carry no inode and are never grouped.
+pub fn group_hardlinks(files: &[FileInfo]) -> Vec<HardlinkGroup> {
+    let mut by_inode: HashMap<(u64, u64), HardlinkGroup> = HashMap::new();
+    for file in files.iter().filter(|file| file.symlink_target.is_none()) {
+        if let (Some(device), Some(inode)) = (file.device, file.inode) {
+            by_inode
+                .entry((device, inode))
+                .or_insert_with(|| HardlinkGroup { device, inode, paths: Vec::new(), size_mb: file.size_mb })
+                .paths
+                .push(file.path.to_string());
+        }
+    }
+
+    let mut groups: Vec<HardlinkGroup> = by_inode.into_values().filter(|group| group.paths.len() > 1).collect();
+    for group in &mut groups {
+        group.paths.sort();
+    }
+    groups.sort_by(|a, b| b.size_mb.partial_cmp(&a.size_mb).unwrap_or(std::cmp::Ordering::Equal));
+    groups
+}
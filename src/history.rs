@@ -0,0 +1,191 @@
+use crate::scan::{ScanPriority, ScanProfile};
+use crate::{growth, DiskInfo};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+/// Records kept per disk; older ones are dropped when a scan is recorded.
+const MAX_RECORDS_PER_DISK: usize = 50;
+/// Scans kept for the performance chart.
+const MAX_RUNS: usize = 100;
+
+/// A disk's space figures at the end of a scan. The file list is not kept,
+/// only the sizes of its larger directories for comparing scans.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanRecord {
+    pub timestamp: u64,
+    pub disk: DiskInfo,
+    /// MB per directory, see `growth::recorded_dir_sizes`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub dirs: HashMap<String, f64>,
+}
+
+/// How a scan was started, told apart in the performance chart.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ScanKind {
+    Full,
+    Quick,
+    Folder,
+}
+
+/// How long one scan took and how much it walked, with the settings it ran
+/// with, for seeing whether tuning them made scans faster.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScanRun {
+    pub timestamp: u64,
+    pub kind: ScanKind,
+    pub duration_secs: f64,
+    pub files: usize,
+    pub bytes_walked: u64,
+    pub profile: ScanProfile,
+    pub threads: usize,
+    pub priority: ScanPriority,
+    pub store_files_above_mb: f64,
+}
+
+impl ScanRun {
+    pub fn mb_per_sec(&self) -> f64 {
+        if self.duration_secs > 0.0 {
+            self.bytes_walked as f64 / 1_048_576.0 / self.duration_secs
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Everything in the history file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    #[serde(default)]
+    pub records: Vec<ScanRecord>,
+    #[serde(default)]
+    pub runs: Vec<ScanRun>,
+}
+
+/// The history file as written by any version: a bare array of records
+/// before scan runs were kept.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredHistory {
+    Current(History),
+    Records(Vec<ScanRecord>),
+}
+
+fn history_path() -> PathBuf {
+    crate::util::data_dir().join("history.json")
+}
+
+pub fn load() -> History {
+    let stored = fs::read_to_string(history_path()).ok().and_then(|contents| serde_json::from_str(&contents).ok());
+    match stored {
+        Some(StoredHistory::Current(history)) => history,
+        Some(StoredHistory::Records(records)) => History { records, runs: Vec::new() },
+        None => History::default(),
+    }
+}
+
+fn save(records: &[ScanRecord], runs: &[ScanRun]) -> Result<(), String> {
+    #[derive(Serialize)]
+    struct Stored<'a> {
+        records: &'a [ScanRecord],
+        runs: &'a [ScanRun],
+    }
+    let path = history_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let contents = serde_json::to_string(&Stored { records, runs }).map_err(|e| e.to_string())?;
+    fs::write(path, contents).map_err(|e| e.to_string())
+}
+
+/// Saves on a thread of its own, so writing the file never holds up the UI.
+/// A save that gets to the file after a later one has been written is
+/// dropped rather than putting older contents back.
+pub fn save_in_background(records: Vec<ScanRecord>, runs: Vec<ScanRun>) {
+    static STARTED: AtomicU64 = AtomicU64::new(0);
+    static WRITTEN: Mutex<u64> = Mutex::new(0);
+    let save_number = STARTED.fetch_add(1, Ordering::Relaxed) + 1;
+    thread::spawn(move || {
+        let mut written = WRITTEN.lock().unwrap();
+        if *written > save_number {
+            return;
+        }
+        if let Err(e) = save(&records, &runs) {
+            eprintln!("Failed to save scan history: {}", e);
+        }
+        *written = save_number;
+    });
+}
+
+/// Adds `recorded` after the existing runs, dropping the oldest beyond the
+/// limit.
+pub fn append_runs(runs: &mut Vec<ScanRun>, recorded: Vec<ScanRun>) {
+    runs.extend(recorded);
+    let excess = runs.len().saturating_sub(MAX_RUNS);
+    runs.drain(..excess);
+}
+
+pub fn record_scan<'a>(records: &mut Vec<ScanRecord>, disks: impl Iterator<Item = &'a DiskInfo>, timestamp: u64) {
+    let recorded = disks
+        .map(|disk| ScanRecord { timestamp, disk: disk.without_files(), dirs: growth::recorded_dir_sizes(disk) })
+        .collect();
+    append(records, recorded);
+}
+
+/// Adds `recorded` after the existing records, dropping each disk's oldest
+/// beyond the limit. Also used for scans that finished before the saved
+/// history had been loaded.
+pub fn append(records: &mut Vec<ScanRecord>, recorded: Vec<ScanRecord>) {
+    for record in recorded {
+        let mount_point = record.disk.mount_point.clone();
+        records.push(record);
+
+        let count = records.iter().filter(|r| r.disk.mount_point == mount_point).count();
+        let mut excess = count.saturating_sub(MAX_RECORDS_PER_DISK);
+        records.retain(|r| {
+            if excess > 0 && r.disk.mount_point == mount_point {
+                excess -= 1;
+                false
+            } else {
+                true
+            }
+        });
+    }
+}
+
+/// The last `count` used-space figures recorded for the disk at
+/// `mount_point`, oldest first, as `(timestamp, used GB)`.
+pub fn used_space_series(records: &[ScanRecord], mount_point: &str, count: usize) -> Vec<(u64, f64)> {
+    let mut series: Vec<(u64, f64)> = records
+        .iter()
+        .filter(|r| r.disk.mount_point == mount_point)
+        .map(|r| (r.timestamp, r.disk.used_space))
+        .collect();
+    series.sort_by_key(|&(timestamp, _)| timestamp);
+    series.split_off(series.len().saturating_sub(count))
+}
+
+/// The most recent record of `disk` taken strictly before `before`.
+pub fn previous_record<'a>(records: &'a [ScanRecord], disk: &DiskInfo, before: u64) -> Option<&'a ScanRecord> {
+    records
+        .iter()
+        .filter(|r| r.disk.mount_point == disk.mount_point && r.timestamp < before)
+        .max_by_key(|r| r.timestamp)
+}
+
+/// Linearly extrapolates used-space growth between two scans to the moment the
+/// disk is full. Returns `None` if usage didn't grow.
+pub fn estimate_time_to_fill(current: &DiskInfo, previous: &DiskInfo, elapsed: Duration) -> Option<SystemTime> {
+    let growth = current.used_space - previous.used_space;
+    if growth <= 0.0 || elapsed.is_zero() {
+        return None;
+    }
+    let remaining = (current.total_space - current.used_space).max(0.0);
+    let seconds = remaining / (growth / elapsed.as_secs_f64());
+    SystemTime::now().checked_add(Duration::try_from_secs_f64(seconds).ok()?)
+}
@@ -0,0 +1,164 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{OnceLock, RwLock};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// UI language. `System` follows the OS locale and is resolved when selected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    System,
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: &[Language] = &[Language::System, Language::English, Language::German];
+
+    fn resolve(self) -> Language {
+        match self {
+            Language::System => match sys_locale::get_locale() {
+                Some(locale) if locale.to_ascii_lowercase().starts_with("de") => Language::German,
+                _ => Language::English,
+            },
+            language => language,
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Language::System => write!(f, "{}", tr("language-system")),
+            Language::English => write!(f, "English"),
+            Language::German => write!(f, "Deutsch"),
+        }
+    }
+}
+
+static CURRENT: RwLock<Language> = RwLock::new(Language::English);
+
+fn catalogs() -> &'static HashMap<Language, HashMap<&'static str, &'static str>> {
+    static CATALOGS: OnceLock<HashMap<Language, HashMap<&'static str, &'static str>>> = OnceLock::new();
+    CATALOGS.get_or_init(|| {
+        HashMap::from([
+            (Language::English, parse(include_str!("../locales/en.ftl"))),
+            (Language::German, parse(include_str!("../locales/de.ftl"))),
+        ])
+    })
+}
+
+/// Reads `key = value` lines; blank lines and `#` comments are skipped.
+fn parse(source: &'static str) -> HashMap<&'static str, &'static str> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .map(|(key, value)| (key.trim(), value.trim()))
+        .collect()
+}
+
+pub fn set_language(language: Language) {
+    *CURRENT.write().unwrap() = language.resolve();
+}
+
+fn phrase(key: &str) -> &str {
+    let catalogs = catalogs();
+    let language = *CURRENT.read().unwrap();
+    catalogs
+        .get(&language)
+        .and_then(|phrases| phrases.get(key))
+        .or_else(|| catalogs[&Language::English].get(key))
+        .copied()
+        .unwrap_or(key)
+}
+
+pub fn tr(key: &str) -> String {
+    phrase(key).to_string()
+}
+
+/// Translates `key` and fills in its `{ $name }` placeholders.
+pub fn tr_args(key: &str, args: &[(&str, String)]) -> String {
+    let mut text = phrase(key).to_string();
+    for (name, value) in args {
+        text = text.replace(&format!("{{ ${} }}", name), value);
+    }
+    text
+}
+
+/// Formats `value` with the current locale's decimal and grouping separators.
+pub fn format_number(value: f64, decimals: usize) -> String {
+    let formatted = format!("{:.*}", decimals, value.abs());
+    let (integer, fraction) = match formatted.split_once('.') {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (formatted.as_str(), None),
+    };
+
+    let mut result = String::new();
+    if value < 0.0 {
+        result.push('-');
+    }
+    for (i, digit) in integer.chars().enumerate() {
+        if i > 0 && (integer.len() - i) % 3 == 0 {
+            result.push_str(phrase("group-separator"));
+        }
+        result.push(digit);
+    }
+    if let Some(fraction) = fraction {
+        result.push_str(phrase("decimal-separator"));
+        result.push_str(fraction);
+    }
+    result
+}
+
+pub fn format_count(count: usize) -> String {
+    format_number(count as f64, 0)
+}
+
+/// A file size given in MB, switched to GB from 1000 MB up.
+pub fn format_size_mb(size_mb: f64) -> String {
+    if size_mb >= 1000.0 {
+        format_gb(size_mb / 1024.0)
+    } else {
+        format!("{} {}", format_number(size_mb, 2), phrase("unit-mb"))
+    }
+}
+
+pub fn format_gb(size_gb: f64) -> String {
+    format!("{} {}", format_number(size_gb, 2), phrase("unit-gb"))
+}
+
+/// A span of time in its largest whole unit, e.g. `3 hours`; under a minute
+/// reads as less than one.
+pub fn human_duration(seconds: u64) -> String {
+    let (count, one, many) = match seconds {
+        0..=59 => return phrase("duration-under-minute").to_string(),
+        60..=3_599 => (seconds / 60, "duration-minute", "duration-minutes"),
+        3_600..=86_399 => (seconds / 3_600, "duration-hour", "duration-hours"),
+        _ => (seconds / 86_400, "duration-day", "duration-days"),
+    };
+    tr_args(if count == 1 { one } else { many }, &[("count", format_count(count as usize))])
+}
+
+/// A calendar date (UTC) in the current locale's order.
+pub fn format_date(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let (year, month, day) = crate::util::civil_from_days((secs / 86_400) as i64);
+    tr_args("date-format", &[
+        ("year", year.to_string()),
+        ("month", format!("{:02}", month)),
+        ("day", format!("{:02}", day)),
+    ])
+}
+
+/// A date and time of day (UTC) in the current locale's format.
+pub fn format_date_time(time: SystemTime) -> String {
+    let secs = time.duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    tr_args("date-time-format", &[
+        ("date", format_date(time)),
+        ("hour", format!("{:02}", secs / 3600 % 24)),
+        ("minute", format!("{:02}", secs / 60 % 60)),
+    ])
+}
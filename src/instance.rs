@@ -0,0 +1,108 @@
+use std::fs::{self, OpenOptions};
+use std::io::{self, Read, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use sysinfo::{Pid, PidExt, System, SystemExt};
+
+const FOCUS_REQUEST: &[u8] = b"focus\n";
+
+pub enum Startup {
+    /// This is the only running instance. The flag is set whenever a later
+    /// launch asks this one to bring its window forward.
+    Primary(Arc<AtomicBool>),
+    /// Another instance is running and has been asked to focus its window.
+    AlreadyRunning,
+}
+
+fn lock_path() -> PathBuf {
+    crate::util::data_dir().join("instance.lock")
+}
+
+/// Takes the single-instance lock. The lock file holds `<pid> <port>`, where
+/// `port` is a localhost listener that accepts focus requests.
+pub fn acquire() -> Result<Startup, String> {
+    let path = lock_path();
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    for _ in 0..2 {
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                let listener = TcpListener::bind("127.0.0.1:0").map_err(|e| e.to_string())?;
+                let port = listener.local_addr().map_err(|e| e.to_string())?.port();
+                write!(file, "{} {}", std::process::id(), port).map_err(|e| e.to_string())?;
+
+                let focus_requested = Arc::new(AtomicBool::new(false));
+                listen_for_focus(listener, Arc::clone(&focus_requested));
+                return Ok(Startup::Primary(focus_requested));
+            }
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                if signal_existing(&path) {
+                    return Ok(Startup::AlreadyRunning);
+                }
+                // Left behind by a process that crashed or was killed.
+                fs::remove_file(&path).map_err(|e| e.to_string())?;
+            }
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+
+    Err("Could not acquire the instance lock".to_string())
+}
+
+/// Removes the lock file if it belongs to this process. Needed on exit paths
+/// such as `std::process::exit` that skip destructors.
+pub fn release() {
+    let path = lock_path();
+    if let Ok(contents) = fs::read_to_string(&path) {
+        let owner = contents.split_whitespace().next().and_then(|pid| pid.parse::<u32>().ok());
+        if owner == Some(std::process::id()) {
+            let _ = fs::remove_file(&path);
+        }
+    }
+}
+
+/// Returns true only if the lock owner is alive and accepted the focus request.
+/// Anything else (unreadable file, dead PID, nobody listening) means the lock is stale.
+fn signal_existing(path: &Path) -> bool {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(_) => return false,
+    };
+    let mut parts = contents.split_whitespace();
+    let pid = parts.next().and_then(|pid| pid.parse::<u32>().ok());
+    let port = parts.next().and_then(|port| port.parse::<u16>().ok());
+    let (pid, port) = match (pid, port) {
+        (Some(pid), Some(port)) => (pid, port),
+        _ => return false,
+    };
+
+    if !System::new().refresh_process(Pid::from_u32(pid)) {
+        return false;
+    }
+
+    let addr = SocketAddr::from(([127, 0, 0, 1], port));
+    match TcpStream::connect_timeout(&addr, Duration::from_secs(1)) {
+        Ok(mut stream) => stream.write_all(FOCUS_REQUEST).is_ok(),
+        Err(_) => false,
+    }
+}
+
+fn listen_for_focus(listener: TcpListener, focus_requested: Arc<AtomicBool>) {
+    thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(1)));
+            let mut buffer = [0u8; 16];
+            if let Ok(read) = stream.read(&mut buffer) {
+                if &buffer[..read] == FOCUS_REQUEST {
+                    focus_requested.store(true, Ordering::SeqCst);
+                }
+            }
+        }
+    });
+}
@@ -1,361 +1,6633 @@
 use iced::{
-    Application, Command, Element, Length, Settings, Subscription,
-    widget::{Button, Column, Container, ProgressBar, Text, TextInput, Row, Space},
+    keyboard, theme, Application, Command, Element, Length, Settings, Subscription,
+    widget::{container, text_input, Button, Checkbox, Column, Container, PickList, ProgressBar, Text, TextInput, Row, Space},
+    widget::scrollable::{self, RelativeOffset, Scrollable},
 };
-use sysinfo::{System, SystemExt, DiskExt};
-use rayon::prelude::*;
+use sysinfo::{CpuExt, ProcessExt, System, SystemExt, DiskExt};
 use std::thread;
-use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicUsize, Ordering};
-use walkdir::WalkDir;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, HashSet, VecDeque};
 use std::fs::{self, File};
-use std::time::{Instant, Duration};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Instant, Duration, SystemTime, UNIX_EPOCH};
 use serde::{Serialize, Deserialize};
-use serde_json;
 use csv::Writer;
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+mod analysis;
+mod archives;
+mod checkpoint;
+mod checksum;
+mod classify;
+mod clones;
+mod dir_chart;
+mod dupes;
+mod growth;
+mod growth_log;
+mod hardlinks;
+mod history;
+mod i18n;
+mod instance;
+mod notify;
+mod owners;
+mod report;
+mod same_name;
+mod scan;
+mod settings;
+mod sparkline;
+mod sqlite;
+mod symlinks;
+mod trash;
+mod tray;
+mod treemap;
+mod util;
+
+use analysis::AnalysisReport;
+use checksum::HashingWriter;
+use clones::ClonePair;
+use hardlinks::HardlinkGroup;
+use history::{ScanKind, ScanRecord, ScanRun};
+use i18n::{format_count, format_date, format_date_time, format_gb, format_number, format_size_mb, human_duration, tr, tr_args, Language};
+use same_name::SameNameGroup;
+use scan::{ProjectRoot, ScanConfig, ScanPriority, ScanProfile, ScanProgress, SortMode};
+use settings::{AppSettings, FilterPreset, UiScale, WindowState, DEFAULT_LOW_SPACE_PERCENT};
+use sparkline::{ScanPerfChart, Sparkline, UsageSparkline};
+use symlinks::{LinkStatus, SymlinkEntry};
 
 pub fn main() -> iced::Result {
-    DiskVisualizer::run(Settings::default())
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("dupes") {
+        std::process::exit(dupes::run_cli(&args[1..]));
+    }
+
+    let focus_requested = if args.iter().any(|arg| arg == "--new-instance") {
+        Arc::new(AtomicBool::new(false))
+    } else {
+        match instance::acquire() {
+            Ok(instance::Startup::Primary(focus_requested)) => focus_requested,
+            Ok(instance::Startup::AlreadyRunning) => {
+                eprintln!("Disk Usage Visualizer is already running; focusing the existing window.");
+                return Ok(());
+            }
+            Err(e) => {
+                eprintln!("Single-instance check failed, continuing anyway: {}", e);
+                Arc::new(AtomicBool::new(false))
+            }
+        }
+    };
+
+    // `--csv-no-header` leaves the header row out of CSV exports, for tools
+    // that expect bare records.
+    if args.iter().any(|arg| arg == "--csv-no-header") {
+        CSV_NO_HEADER.store(true, Ordering::Relaxed);
+    }
+
+    // `--growth-log FILE` appends to FILE after every scan in this session,
+    // whatever the saved setting says.
+    let growth_log = args
+        .iter()
+        .position(|arg| arg == "--growth-log")
+        .and_then(|i| args.get(i + 1))
+        .map(PathBuf::from);
+
+    let settings = AppSettings::load();
+    i18n::set_language(settings.language);
+    let mut window = iced::window::Settings {
+        min_size: Some(settings::MIN_WINDOW_SIZE),
+        ..Default::default()
+    };
+    if let Some(state) = settings.window.map(WindowState::clamped_to_displays) {
+        window.size = (state.width, state.height);
+        if let (Some(x), Some(y)) = (state.x, state.y) {
+            window.position = iced::window::Position::Specific(x, y);
+        }
+    }
+
+    DiskVisualizer::run(Settings {
+        window,
+        // Closing mid-scan asks first; see `Message::CloseRequested`.
+        exit_on_close_request: false,
+        ..Settings::with_flags(Flags { focus_requested, settings, growth_log })
+    })
+}
+
+struct Flags {
+    focus_requested: Arc<AtomicBool>,
+    settings: AppSettings,
+    growth_log: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-struct DiskInfo {
+pub struct DiskInfo {
     name: String,
+    #[serde(default)]
+    mount_point: String,
     total_space: f64,
     used_space: f64,
+    #[serde(serialize_with = "serialize_files")]
     files: Vec<FileInfo>,
+    #[serde(default)]
+    archive_overhead_mb: f64,
+    #[serde(default)]
+    skipped_reason: Option<String>,
+    #[serde(default)]
+    skipped_placeholders: usize,
+    #[serde(default)]
+    truncated: bool,
+    /// The scan stopped at the I/O quota; `truncated` is set as well.
+    #[serde(default)]
+    quota_exceeded: bool,
+    /// The I/O quota the scan stopped at, in bytes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    io_quota_bytes: Option<u64>,
+    /// Smaller entries were dropped to stay within `ScanConfig::max_file_entries`.
+    #[serde(default)]
+    entries_capped: bool,
+    /// File systems mounted inside this disk, left to their own disks.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    skipped_mounts: Vec<String>,
+    /// The drive went away during the scan; the numbers are partial.
+    #[serde(default)]
+    disconnected: bool,
+    /// Sum of the files' `line_count`s.
+    #[serde(default)]
+    lines_of_code: u64,
+    #[serde(default)]
+    projects: Vec<ProjectRoot>,
+    /// Build output and dependency directories, largest first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    artifacts: Vec<scan::ArtifactDir>,
+    #[serde(default)]
+    hardlink_groups: Vec<HardlinkGroup>,
+    /// Files too small to keep as entries, by directory; see
+    /// `ScanConfig::store_files_above_mb`.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    unstored: HashMap<String, scan::UnstoredFiles>,
+    /// Every symlink the walk found, broken ones first.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    symlinks: Vec<SymlinkEntry>,
+    /// Files inside the disk's archives, by path. Only listed in the archive
+    /// drill-down: their bytes are already counted in the archives' own
+    /// entries.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    archive_members: Vec<FileInfo>,
+    /// Number of files at each path depth.
+    #[serde(default)]
+    depth_histogram: Vec<usize>,
+    /// Directories holding the most files, at any depth.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    most_files: Vec<scan::FileCountDir>,
+    /// Number of files in each `scan::SizeRange`.
+    #[serde(default)]
+    size_histogram: [usize; 6],
+    #[serde(default)]
+    dedup_ratio: Option<f64>,
+    /// Patterns from `.duignore` files that applied to the scan.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    ignore_patterns: Vec<scan::IgnorePattern>,
+    /// A dropped folder scanned on its own rather than a whole disk; its
+    /// space is that of the disk it sits on.
+    #[serde(default)]
+    folder: bool,
+    /// Host name of the machine an imported disk came from; `None` for disks
+    /// scanned here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    source: Option<String>,
+    /// When the disk was last fully scanned, in seconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scanned_at: Option<u64>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-struct FileInfo {
-    path: String,
+/// Trims `disks` to the `cap` largest entries across all of them, flagging
+/// and re-summarizing the disks that lost entries. The dropped entries are
+/// tallied in `unstored`, so the disks' file counts stay whole.
+fn cap_file_entries<'a>(disks: impl Iterator<Item = &'a mut DiskInfo>, cap: usize) {
+    let mut disks: Vec<&mut DiskInfo> = disks.collect();
+    let mut lists: Vec<&mut Vec<FileInfo>> = disks.iter_mut().map(|disk| &mut disk.files).collect();
+    let dropped = scan::keep_largest(&mut lists, cap);
+    for (disk, dropped) in disks.into_iter().zip(dropped) {
+        if !dropped.is_empty() {
+            disk.entries_capped = true;
+            scan::add_unstored(&mut disk.unstored, dropped);
+            disk.summarize_files();
+        }
+    }
+}
+
+/// Time the scan spent paused for high CPU usage, if any.
+fn throttled_seconds(progress: &ScanProgress) -> Option<f64> {
+    let ms = progress.throttled_ms.load(Ordering::Relaxed);
+    (ms > 0).then(|| ms as f64 / 1000.0)
+}
+
+/// When the newest local disk in `disks` was scanned.
+fn snapshot_time(disks: &[DiskInfo]) -> Option<u64> {
+    disks.iter().filter(|disk| disk.source.is_none()).filter_map(|disk| disk.scanned_at).max()
+}
+
+fn lines_of_code(files: &[FileInfo]) -> u64 {
+    files.iter().filter_map(|file| file.line_count).sum()
+}
+
+impl DiskInfo {
+    /// A freshly scanned local disk, or folder when `mount_point` is a folder
+    /// on one, with everything derived from its files filled in.
+    fn from_walk(
+        name: String,
+        mount_point: String,
+        (total_space, used_space): (f64, f64),
+        walk: scan::WalkOutput,
+        skipped_reason: Option<String>,
+        dedup_ratio: Option<f64>,
+    ) -> DiskInfo {
+        DiskInfo {
+            archive_overhead_mb: scan::archive_overhead_mb(&walk.files),
+            hardlink_groups: hardlinks::group_hardlinks(&walk.files),
+            symlinks: symlinks::symlink_report(&mount_point, &walk.files),
+            most_files: scan::most_files_dirs(&mount_point, &walk.files, &walk.unstored),
+            unstored: walk.unstored,
+            depth_histogram: scan::depth_histogram(&mount_point, &walk.files),
+            size_histogram: scan::compute_size_histogram(&walk.files),
+            lines_of_code: lines_of_code(&walk.files),
+            name,
+            mount_point,
+            total_space,
+            used_space,
+            files: walk.files,
+            archive_members: walk.archive_members,
+            skipped_reason,
+            skipped_placeholders: walk.skipped_placeholders,
+            truncated: walk.truncated,
+            quota_exceeded: walk.quota_exceeded.is_some(),
+            io_quota_bytes: walk.quota_exceeded,
+            entries_capped: walk.capped,
+            skipped_mounts: walk.skipped_mounts,
+            disconnected: walk.disconnected,
+            projects: walk.projects,
+            artifacts: walk.artifacts,
+            ignore_patterns: walk.ignore_patterns,
+            dedup_ratio,
+            folder: false,
+            source: None,
+            scanned_at: Some(util::unix_now()),
+        }
+    }
+
+    /// Recomputes everything derived from `files` after the list changed.
+    fn summarize_files(&mut self) {
+        self.archive_overhead_mb = scan::archive_overhead_mb(&self.files);
+        self.hardlink_groups = hardlinks::group_hardlinks(&self.files);
+        self.symlinks = symlinks::symlink_report(&self.mount_point, &self.files);
+        self.depth_histogram = scan::depth_histogram(&self.mount_point, &self.files);
+        self.most_files = scan::most_files_dirs(&self.mount_point, &self.files, &self.unstored);
+        self.size_histogram = scan::compute_size_histogram(&self.files);
+        self.lines_of_code = lines_of_code(&self.files);
+    }
+
+    /// A copy with the file list left out, for keeping small per-scan records.
+    fn without_files(&self) -> DiskInfo {
+        DiskInfo {
+            name: self.name.clone(),
+            mount_point: self.mount_point.clone(),
+            total_space: self.total_space,
+            used_space: self.used_space,
+            files: Vec::new(),
+            archive_overhead_mb: self.archive_overhead_mb,
+            skipped_reason: self.skipped_reason.clone(),
+            skipped_placeholders: self.skipped_placeholders,
+            ignore_patterns: self.ignore_patterns.clone(),
+            truncated: self.truncated,
+            quota_exceeded: self.quota_exceeded,
+            io_quota_bytes: self.io_quota_bytes,
+            entries_capped: self.entries_capped,
+            skipped_mounts: self.skipped_mounts.clone(),
+            disconnected: self.disconnected,
+            lines_of_code: self.lines_of_code,
+            projects: Vec::new(),
+            artifacts: Vec::new(),
+            hardlink_groups: Vec::new(),
+            symlinks: Vec::new(),
+            archive_members: Vec::new(),
+            unstored: HashMap::new(),
+            depth_histogram: self.depth_histogram.clone(),
+            most_files: Vec::new(),
+            size_histogram: self.size_histogram,
+            dedup_ratio: self.dedup_ratio,
+            folder: self.folder,
+            source: self.source.clone(),
+            scanned_at: self.scanned_at,
+        }
+    }
+
+    /// `server1 — /dev/sda1` for imported disks, so equal names on different
+    /// machines stay apart.
+    fn display_name(&self) -> String {
+        match &self.source {
+            Some(source) => format!("{} — {}", source, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Every file the scan found, including those not kept as entries.
+    fn total_files(&self) -> usize {
+        self.files.len() + self.unstored.values().map(|dir| dir.count).sum::<usize>()
+    }
+
+    /// Used share of the disk in percent; 0 for disks that report no size.
+    fn used_percent(&self) -> f64 {
+        if self.total_space > 0.0 { (self.used_space / self.total_space * 100.0).clamp(0.0, 100.0) } else { 0.0 }
+    }
+
+    /// Makes sizes from an import safe to draw and sort: `total_space`
+    /// finite and not negative, `used_space` within `[0, total_space]`.
+    fn clamp_space(&mut self) {
+        if !self.total_space.is_finite() || self.total_space < 0.0 {
+            self.total_space = 0.0;
+        }
+        self.used_space = if self.used_space.is_finite() { self.used_space.clamp(0.0, self.total_space) } else { 0.0 };
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FileInfo {
+    /// Shared with any other file entry of the same scan reaching the same
+    /// path, and with copies of the entry.
+    path: Arc<str>,
     size_mb: f64, 
+    #[serde(default)]
+    compressed_size_bytes: Option<u64>,
+    #[serde(default)]
+    uncompressed_size_bytes: Option<u64>,
+    #[serde(default)]
+    symlink_target: Option<String>,
+    #[serde(default)]
+    broken_symlink: bool,
+    #[serde(default)]
+    is_temp: bool,
+    #[serde(default)]
+    is_hidden: bool,
+    /// Seconds since the Unix epoch.
+    #[serde(default)]
+    modified: Option<u64>,
+    /// Unix only; two files with the same device and inode are hardlinks.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    inode: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    device: Option<u64>,
+    /// Only set in filtered exports: this file's share of the disk or of the
+    /// filtered set, and the running total down the list, in percent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    share_percent: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cumulative_percent: Option<f64>,
+    /// Whether the start of the file holds a NUL byte; `None` for files over
+    /// the size limit of the check, symlinks and unreadable files.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    is_binary: Option<bool>,
+    /// MIME type from the file's magic bytes; `None` for symlinks, cloud
+    /// placeholders, unreadable files and content no signature matches.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    mime_type: Option<String>,
+    /// Lowercase hex SHA-256, only for the largest files when enabled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    sha256: Option<String>,
+    /// Zip, tar, gzip, bzip2, xz, 7z or rar, by extension.
+    #[serde(default)]
+    is_archive: bool,
+    /// Newlines in a source file, when line counting was on and the file
+    /// was under its size limit.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    line_count: Option<u64>,
+    /// Owner's user id and permission bits (`st_mode`), Unix only.
+    #[serde(default)]
+    uid: Option<u32>,
+    #[serde(default)]
+    mode: Option<u32>,
 }
 
 struct DiskVisualizer {
-    disks: Vec<DiskInfo>,
+    /// Shared with export and analysis workers rather than copied for them;
+    /// changes go through `Arc::make_mut`, which copies only while a worker
+    /// still holds the list.
+    disks: Arc<Vec<DiskInfo>>,
+    /// Earlier scan results, oldest first, up to `AppSettings::kept_scans`.
+    snapshots: Vec<Arc<Vec<DiskInfo>>>,
+    /// The snapshot shown instead of the latest results. Its slot in
+    /// `snapshots` holds the latest results meanwhile.
+    shown_snapshot: Option<usize>,
     scanning: bool,
+    /// The running scan is an auto-refresh: the results shown stay usable
+    /// meanwhile and keep their selection, scopes and scroll positions.
+    background_scan: bool,
+    /// When the running scan was asked to stop.
+    stop_requested: Option<Instant>,
+    /// Counts scans started, so the result of one given up on is dropped.
+    scan_id: u64,
     error_message: Option<String>,
     scan_duration: Option<f64>,
     scan_count: Arc<AtomicUsize>,
+    // Guards against a second scan racing the first, e.g. Scan then Refresh.
+    last_scan_started: Option<Instant>,
     file_type_filter: String,
     file_name_filter: String,
+    /// Minimum size in MB, as typed.
+    min_size_filter: String,
+    /// Minimum age in days, as typed.
+    older_than_filter: String,
+    hidden_filter: HiddenFilter,
+    binary_filter: BinaryFilter,
+    age_filter: AgeFilter,
+    mime_filter: String,
+    mime_mismatch_only: bool,
+    owner_filter: OwnerFilter,
+    world_writable_only: bool,
+    setuid_only: bool,
+    /// `OwnerFilter::All` and every owner found in the scanned files, for
+    /// the owner dropdown.
+    owner_choices: Vec<OwnerFilter>,
+    preset_name: String,
     elapsed_time: Duration,
+    scan_config: ScanConfig,
+    focus_requested: Arc<AtomicBool>,
+    settings: AppSettings,
+    /// Text typed into number fields of the settings, by field.
+    setting_inputs: HashMap<SettingInput, String>,
+    growth_log_override: Option<PathBuf>,
+    // Outcome of the last auto-export, shown under the scan duration.
+    auto_export_status: Option<Result<String, String>>,
+    active_rescans: Vec<PathBuf>,
+    /// Folder the current or last scan was limited to; `None` scans every disk.
+    scan_path: Option<PathBuf>,
+    /// Dropped folders waiting to be scanned, one after another.
+    scan_queue: VecDeque<PathBuf>,
+    /// The summary card shows its per-disk table.
+    summary_expanded: bool,
+    /// The Ctrl+P command palette, while open.
+    palette: Option<Palette>,
+    /// Disks, by display name, whose symlink section is expanded.
+    expanded_symlinks: HashSet<String>,
+    /// Disks, by display name, whose "Most files" list is expanded.
+    expanded_most_files: HashSet<String>,
+    /// Disks, by display name, whose archive contents are expanded.
+    expanded_archives: HashSet<String>,
+    /// Average file size, in KB, the "Most files" lists are limited to;
+    /// empty or unparsable lists every directory.
+    most_files_max_avg_kb: String,
+    /// `(name, mount point)` of the disks the last full scan skipped for
+    /// reporting no capacity.
+    no_capacity_disks: Vec<(String, String)>,
+    /// The virtual file system types as typed, so separators survive editing.
+    virtual_filesystems_input: String,
+    /// The disks listed at startup, to pick from before and between scans.
+    detected_disks: Vec<scan::DetectedDisk>,
+    detecting_clones: bool,
+    clone_pairs: Option<Vec<ClonePair>>,
+    /// Files sharing a name across directories; `Some` while the panel is open.
+    same_name_groups: Option<Vec<SameNameGroup>>,
+    finding_same_names: bool,
+    /// The artifact directory the "move to the trash?" prompt is showing for.
+    confirm_trash_artifact: Option<String>,
+    /// Artifact directories being moved to the trash.
+    trashing_artifacts: HashSet<String>,
+    /// Whether the "move the selected files to the trash?" prompt is showing.
+    confirm_trash_selected: bool,
+    trashing_selected: bool,
+    /// The artifact rule being added in the settings.
+    new_artifact_dir: String,
+    new_artifact_markers: String,
+    /// Every disk's files in one treemap; `Some` while the view is open.
+    all_disks_treemap: Option<treemap::Treemap>,
+    /// The "What grew" panel, while open; its baseline is unset until one is picked.
+    growth_report: Option<growth::GrowthReport>,
+    freed_dirs_expanded: bool,
+    growth_exported: Option<String>,
+    disk_scroll_offsets: Vec<RelativeOffset>,
+    scan_progress: Arc<ScanProgress>,
+    // Directory shown in the scanning panel and the elapsed time it first appeared.
+    current_dir_seen: Option<(String, Duration)>,
+    /// Directories the walker threads were in at the last tick.
+    active_paths: Vec<String>,
+    /// Disks (by display name) already notified about being nearly full, so
+    /// a refresh doesn't notify again until they drop below the threshold.
+    notified_full: HashSet<String>,
+    /// When each disk (by name) was last warned about low free space, so
+    /// auto-refresh warns at most once per `LOW_SPACE_NOTIFY_INTERVAL`.
+    last_notified: HashMap<String, Instant>,
+    // Sampled once per tick while scanning, to tell CPU-bound from I/O-bound scans.
+    resource_monitor: System,
+    /// This process's resident memory in bytes, refreshed every
+    /// `APP_MEMORY_INTERVAL`.
+    app_memory: Option<u64>,
+    cpu_history: VecDeque<f32>,
+    mem_history: VecDeque<f64>,
+    exporting: bool,
+    /// The export running on its worker thread, if it reports rows.
+    export_progress: Option<Arc<ExportProgress>>,
+    auto_exporting: bool,
+    /// The "stop it and quit?" prompt is showing.
+    confirm_quit: bool,
+    /// Checkpoints left by unfinished scans, while the "resume?" prompt
+    /// is showing.
+    resume_prompt: Option<Vec<checkpoint::CheckpointInfo>>,
+    /// The user answered the prompt for the scan about to start.
+    resume_decided: bool,
+    /// Per local disk, by mount point, what the user's trash on it holds.
+    trash_usage: HashMap<String, trash::TrashUsage>,
+    /// The mount point the "empty the trash?" prompt is showing for.
+    confirm_empty_trash: Option<String>,
+    emptying_trash: bool,
+    /// Quit was confirmed; the app exits once the scan has stopped and
+    /// exports have been written.
+    quitting: bool,
+    /// `None` without the `tray` feature or where the desktop has no tray.
+    tray: Option<tray::Tray>,
+    last_export_checksum: Option<String>,
+    export_per_disk: bool,
+    per_disk_export_report: Vec<(String, Result<String, String>)>,
+    /// Shown after an import that couldn't be checked against a sidecar.
+    import_warning: Option<String>,
+    selected_files: HashSet<String>,
+    /// `(disk index, row)` of the file list row focused from the keyboard.
+    focused_file: Option<(usize, usize)>,
+    /// Per disk, indices into its `files` of the rows shown in the file list,
+    /// filtered and sorted. Rebuilt by `refresh_visible_files` whenever the
+    /// disks, filters or sort change, so `view` doesn't re-sort every frame.
+    visible_files: Vec<Vec<usize>>,
+    /// How many rows of each disk's list are shown, keyed by display name.
+    /// Disks not in here show `INITIAL_FILE_ROWS`.
+    row_limits: HashMap<String, usize>,
+    /// Per disk and row, `(share, cumulative share)` in percent, against the
+    /// denominator `share_basis` picks.
+    visible_shares: Vec<Vec<(f64, f64)>>,
+    /// Per disk, the directory the view is narrowed to, or `None` for the whole disk.
+    scopes: Vec<Option<PathBuf>>,
+    /// Per disk, the extension (`report::extension_key`) the file list is
+    /// drilled down to, or `None` for the overview.
+    drilldowns: Vec<Option<String>>,
+    /// Per disk, `(extension, files, MB)` of the files in scope matching the
+    /// filters, largest first, rebuilt along with `visible_files`.
+    extension_groups: Vec<Vec<(String, usize, f64)>>,
+    /// Per disk, `(files, MB)` in scope only tallied rather than kept as
+    /// entries, which `extension_groups` can't place; none while filtering.
+    unlisted_totals: Vec<Option<(usize, f64)>>,
+    /// Per disk, the size of the current scope, rebuilt along with `visible_files`.
+    scope_summaries: Vec<ScopeSummary>,
+    /// Per disk, the chart of the largest directories in scope among the
+    /// files matching the filters, rebuilt along with `visible_files`.
+    dir_charts: Vec<dir_chart::DirChart>,
+    window_state_dirty: bool,
+    scan_history: Vec<ScanRecord>,
+    /// Duration and throughput of past scans, oldest first.
+    scan_runs: Vec<ScanRun>,
+    /// The saved history is read on a worker at startup; until it arrives,
+    /// scans are only recorded in memory so the file isn't overwritten.
+    history_loaded: bool,
+    fill_estimates: HashMap<String, SystemTime>,
 }
 
+/// What the app is busy with, which decides the controls that are usable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Activity {
+    Idle,
+    Scanning,
+    /// An auto-refresh scan is running.
+    Refreshing,
+    Exporting,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Controls {
+    scan: bool,
+    cancel: bool,
+    refresh: bool,
+    export: bool,
+    filters: bool,
+    rescan: bool,
+}
+
+fn controls_for(activity: Activity) -> Controls {
+    match activity {
+        Activity::Idle => Controls { scan: true, cancel: false, refresh: true, export: true, filters: true, rescan: true },
+        Activity::Scanning => Controls { scan: false, cancel: true, refresh: false, export: false, filters: false, rescan: false },
+        // The old results stay up while they refresh, and can be filtered.
+        Activity::Refreshing => Controls { scan: false, cancel: true, refresh: false, export: false, filters: true, rescan: false },
+        // Exports work on a snapshot of the results, so filtering stays usable.
+        Activity::Exporting => Controls { scan: false, cancel: false, refresh: false, export: false, filters: true, rescan: false },
+    }
+}
+
+/// Settings typed in as numbers. Their fields show the text as typed, since
+/// text on its way to a number, like "0." or an emptied field, doesn't parse
+/// and would otherwise be replaced by the setting on every keystroke.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum SettingInput {
+    CustomMaxDepth,
+    CustomMaxFiles,
+    MaxScanDuration,
+    BinaryCheckLimit,
+    ScanThreads,
+    CheckpointInterval,
+    MaxFileEntries,
+    ChecksumTopN,
+    StoreFilesAbove,
+    KeptScans,
+    MinDirSize,
+    NearlyFullPercent,
+    AutoRefreshMinutes,
+    ThrottleCpu,
+    IoQuota,
+    EntryBufferSize,
+    LineCountLimit,
+    /// A disk's low-space warning level, by disk name.
+    LowSpace(String),
+}
+
+/// The number typed for an optional setting: none for an empty field, and
+/// `current` while the text doesn't parse.
+fn parse_optional<T: std::str::FromStr>(value: &str, current: Option<T>) -> Option<T> {
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else {
+        value.parse().ok().or(current)
+    }
+}
+
+/// The scanned disks, the `(name, mount point)` of disks skipped for
+/// reporting no capacity, and the scan's duration in seconds.
+type ScanOutcome = (Vec<DiskInfo>, Vec<(String, String)>, f64);
+
 #[derive(Debug, Clone)]
-pub enum Message {
+enum Message {
     Scan,
+    QuickScan,
+    DeepScan,
     StopScan,
-    Scanned(Result<(Vec<DiskInfo>, f64), String>),
+    Scanned(u64, Result<ScanOutcome, String>),
     Refresh,
+    RescanDisk(String),
+    Rescanned(String, PathBuf, Result<Box<scan::SubtreeScan>, String>),
     FileTypeFilterChanged(String),
+    HiddenFilterSelected(HiddenFilter),
+    BinaryFilterSelected(BinaryFilter),
+    AgeFilterSelected(AgeFilter),
+    OwnerFilterSelected(OwnerFilter),
+    WorldWritableToggled(bool),
+    SetuidToggled(bool),
+    MimeFilterChanged(String),
+    MimeMismatchToggled(bool),
+    BinaryCheckLimitChanged(String),
+    LineCountLimitChanged(String),
+    ChecksumTopNChanged(String),
+    MinDirSizeChanged(String),
+    ChartColoringSelected(dir_chart::ChartColoring),
     FileNameFilterChanged(String),
-    ExportAsJson,
-    ExportAsCsv,
-    ExportCompleted(Result<(), String>),
+    MinSizeFilterChanged(String),
+    OlderThanFilterChanged(String),
+    PresetNameChanged(String),
+    SavePreset,
+    ApplyPreset(usize),
+    PresetRenamed(usize, String),
+    DeletePreset(usize),
+    PresetChipToggled(usize, bool),
+    MovePresetUp(usize),
+    InspectArchivesToggled(bool),
+    RecurseArchivesToggled(bool),
+    IncludeNetworkToggled(bool),
+    IncludeVirtualToggled(bool),
+    VirtualFilesystemsChanged(String),
+    DiskSelected(String, bool),
+    UseDuignoreToggled(bool),
+    BtrfsDedupToggled(bool),
+    ScanProfileSelected(ScanProfile),
+    ScanPrioritySelected(ScanPriority),
+    StorageOrderSelected(SortMode),
+    LanguageSelected(Language),
+    UiScaleSelected(UiScale),
+    CustomMaxDepthChanged(String),
+    CustomMaxFilesChanged(String),
+    MaxScanDurationChanged(String),
+    IoQuotaChanged(String),
+    ThrottleCpuChanged(String),
+    ScanThreadsChanged(String),
+    MaxFileEntriesChanged(String),
+    EntryBufferSizeChanged(String),
+    CheckpointIntervalChanged(String),
+    ResumeCheckpoints(bool),
+    KeptScansChanged(String),
+    ShowOlderScan,
+    ShowNewerScan,
+    StoreFilesAboveChanged(String),
+    NotifyOnCompleteToggled(bool),
+    NearlyFullPercentChanged(String),
+    NotifyNearlyFullToggled(bool),
+    AutoRefreshMinutesChanged(String),
+    AutoRefresh,
+    LowSpaceThresholdChanged(String, String),
+    MinimizeToTrayToggled(bool),
+    CreateTray,
+    TrayPoll,
+    JumpToDisk(usize),
+    GrowthLogToggled(bool),
+    GrowthLogPathChanged(String),
+    GrowthLogAppended(Result<(), String>),
+    AutoExportToggled(bool),
+    AutoExportFormatSelected(ExportFormat),
+    AutoExportPathChanged(String),
+    AutoExportFilteredToggled(bool),
+    AutoExportCompleted(Result<String, String>),
+    ToggleSymlinks(String),
+    ToggleMostFiles(String),
+    ToggleArchiveMembers(String),
+    MostFilesMaxAvgChanged(String),
+    ToggleSameNamePanel,
+    SameNamesFound(Vec<SameNameGroup>),
+    ToggleAllDisksTreemap,
+    RevealInFolder(String),
+    TrashArtifact(String),
+    CancelTrashArtifact,
+    ConfirmTrashArtifact,
+    ArtifactTrashed(String, Result<(), String>),
+    CopySelectedPaths,
+    TrashSelected,
+    CancelTrashSelected,
+    ConfirmTrashSelected,
+    /// The files moved to the trash, and the errors of those that weren't.
+    SelectedTrashed(Vec<String>, Vec<String>),
+    NewArtifactDirChanged(String),
+    NewArtifactMarkersChanged(String),
+    AddArtifactRule,
+    RemoveArtifactRule(usize),
+    DetectCloneDirs,
+    ToggleGrowthPanel,
+    GrowthBaselineSelected(growth::GrowthBaseline),
+    ToggleFreedDirs,
+    ExportGrowth,
+    GrowthExported(Result<String, String>),
+    CloneDirsFound(Vec<ClonePair>),
+    HistoryLoaded(history::History),
+    SelectAllTempFiles,
+    ClearSelection,
+    FocusNext(bool),
+    MoveFileFocus(isize),
+    MoveDiskFocus(isize),
+    ActivateFocusedFile,
+    ClearFocus,
+    SortKeySelected(SortKey),
+    ShowMoreFiles(String),
+    CollapseFiles(String),
+    SetScope(usize, Option<PathBuf>),
+    ScopeUp,
+    SetExtensionDrilldown(usize, Option<String>),
+    ExportExtensionList(usize),
+    ToggleSortDirection,
+    DiskScrolled(usize, RelativeOffset),
+    Export,
+    ExportFormatSelected(ExportFormat),
+    CopyMarkdownSummary,
+    ToggleSummaryTable,
+    ExportAs(ExportFormat),
+    ExportAsSqlite,
+    ExportAnalysis(AnalysisReport),
+    AnalysisExportPicked(AnalysisReport, Option<PathBuf>),
+    ToggleDarkMode(bool),
+    OpenPalette,
+    PaletteQueryChanged(String),
+    PaletteSubmit,
+    PaletteRun(usize),
+    ExportPerDiskToggled(bool),
+    CompressExportsToggled(bool),
+    ExportFolderPicked(ExportFormat, Option<PathBuf>),
+    PerDiskExportCompleted(Vec<(String, Result<String, String>)>),
+    ExportCompleted(Result<String, String>),
+    ExportTick,
+    CancelExport,
+    ImportJson,
+    ImportFilePicked(Option<PathBuf>),
+    RemoveSource(String),
+    Imported(Result<(Vec<DiskInfo>, Option<String>), String>),
     Done,
+    FileDropped(PathBuf),
+    CloseRequested,
+    CancelQuit,
+    ConfirmQuit,
+    TrashMeasured(HashMap<String, trash::TrashUsage>),
+    EmptyTrash(String),
+    CancelEmptyTrash,
+    ConfirmEmptyTrash,
+    TrashEmptied(Result<(), String>),
+    QuitWhenIdle,
     Tick,
+    SampleAppMemory,
+    CheckFocusRequest,
+    WindowResized(u32, u32),
+    WindowMoved(i32, i32),
+    SaveWindowState,
+}
+
+impl Message {
+    /// Results that change the disks shown, which only the latest scan takes.
+    fn changes_disks(&self) -> bool {
+        matches!(
+            self,
+            Message::Scanned(..)
+                | Message::Rescanned(..)
+                | Message::Imported(_)
+                | Message::RemoveSource(_)
+                | Message::TrashEmptied(_)
+                | Message::ArtifactTrashed(..)
+                | Message::SelectedTrashed(..)
+        )
+    }
 }
 
 impl Application for DiskVisualizer {
     type Executor = iced::executor::Default;
     type Message = Message;
-    type Flags = ();
+    type Flags = Flags;
     type Theme = iced::theme::Theme;
 
-    fn new(_: Self::Flags) -> (Self, Command<Self::Message>) {
+    fn new(flags: Self::Flags) -> (Self, Command<Self::Message>) {
+        let virtual_filesystems_input = flags.settings.virtual_filesystems.join(", ");
+        let detected_disks = scan::detect_disks(&ScanConfig {
+            virtual_filesystems: flags.settings.virtual_filesystems.clone(),
+            ..ScanConfig::default()
+        });
         (
             DiskVisualizer {
-                disks: Vec::new(),
+                disks: Arc::default(),
+                snapshots: Vec::new(),
+                shown_snapshot: None,
                 scanning: false,
+                stop_requested: None,
+                scan_id: 0,
+                background_scan: false,
                 error_message: None,
                 scan_duration: None,
                 scan_count: Arc::new(AtomicUsize::new(0)),
+                last_scan_started: None,
                 file_type_filter: String::new(),
                 file_name_filter: String::new(),
+                min_size_filter: String::new(),
+                older_than_filter: String::new(),
+                hidden_filter: HiddenFilter::All,
+                binary_filter: BinaryFilter::All,
+                age_filter: AgeFilter::All,
+                mime_filter: String::new(),
+                mime_mismatch_only: false,
+                owner_filter: OwnerFilter::All,
+                world_writable_only: false,
+                setuid_only: false,
+                owner_choices: vec![OwnerFilter::All],
+                preset_name: String::new(),
                 elapsed_time: Duration::from_secs(0),
+                scan_config: ScanConfig::default(),
+                focus_requested: flags.focus_requested,
+                settings: flags.settings,
+                setting_inputs: HashMap::new(),
+                growth_log_override: flags.growth_log,
+                auto_export_status: None,
+                active_rescans: Vec::new(),
+                scan_path: None,
+                scan_queue: VecDeque::new(),
+                summary_expanded: false,
+                palette: None,
+                expanded_symlinks: HashSet::new(),
+                expanded_most_files: HashSet::new(),
+                expanded_archives: HashSet::new(),
+                most_files_max_avg_kb: String::new(),
+                no_capacity_disks: Vec::new(),
+                virtual_filesystems_input,
+                detected_disks,
+                detecting_clones: false,
+                clone_pairs: None,
+                same_name_groups: None,
+                finding_same_names: false,
+                confirm_trash_artifact: None,
+                confirm_trash_selected: false,
+                trashing_selected: false,
+                trashing_artifacts: HashSet::new(),
+                new_artifact_dir: String::new(),
+                new_artifact_markers: String::new(),
+                all_disks_treemap: None,
+                growth_report: None,
+                freed_dirs_expanded: false,
+                growth_exported: None,
+                disk_scroll_offsets: Vec::new(),
+                scan_progress: Arc::new(ScanProgress::default()),
+                current_dir_seen: None,
+                active_paths: Vec::new(),
+                notified_full: HashSet::new(),
+                last_notified: HashMap::new(),
+                resource_monitor: System::new(),
+                app_memory: None,
+                cpu_history: VecDeque::with_capacity(sparkline::SAMPLES),
+                mem_history: VecDeque::with_capacity(sparkline::SAMPLES),
+                exporting: false,
+                export_progress: None,
+                auto_exporting: false,
+                confirm_quit: false,
+                resume_prompt: None,
+                resume_decided: false,
+                trash_usage: HashMap::new(),
+                confirm_empty_trash: None,
+                emptying_trash: false,
+                quitting: false,
+                tray: None,
+                last_export_checksum: None,
+                export_per_disk: false,
+                per_disk_export_report: Vec::new(),
+                import_warning: None,
+                selected_files: HashSet::new(),
+                focused_file: None,
+                visible_files: Vec::new(),
+                row_limits: HashMap::new(),
+                visible_shares: Vec::new(),
+                scopes: Vec::new(),
+                drilldowns: Vec::new(),
+                extension_groups: Vec::new(),
+                unlisted_totals: Vec::new(),
+                scope_summaries: Vec::new(),
+                dir_charts: Vec::new(),
+                window_state_dirty: false,
+                scan_history: Vec::new(),
+                scan_runs: Vec::new(),
+                history_loaded: false,
+                fill_estimates: HashMap::new(),
             },
-            Command::none(),
+            Command::batch([
+                DiskVisualizer::load_history(),
+                // Commands run once the event loop does, which macOS needs
+                // before a tray icon can be made.
+                Command::perform(async {}, |_| Message::CreateTray),
+            ]),
         )
     }
 
     fn title(&self) -> String {
-        String::from("Disk Usage Visualizer")
+        tr("app-title")
     }
 
     fn update(&mut self, message: Self::Message) -> Command<Self::Message> {
+        // Only the latest results change; an earlier snapshot is left first.
+        if self.shown_snapshot.is_some() && message.changes_disks() {
+            self.show_snapshot(None);
+        }
         match message {
+            // The palette is modal: keys meant for the view behind it stay
+            // with the palette or do nothing.
+            Message::FocusNext(_) if self.palette.is_some() => text_input::focus(palette_input_id()),
+            Message::MoveDiskFocus(_) | Message::ActivateFocusedFile | Message::ScopeUp if self.palette.is_some() => Command::none(),
             Message::Scan => {
+                if self.scanning {
+                    self.error_message = Some(tr("scan-running"));
+                    return Command::none();
+                }
+                let too_soon = self
+                    .last_scan_started
+                    .is_some_and(|started| started.elapsed() < Duration::from_secs(self.settings.scan_cooldown_secs));
+                if too_soon {
+                    self.error_message = Some(tr("scan-too-soon"));
+                    return Command::none();
+                }
+                if !self.resume_decided {
+                    let roots = self.scan_roots();
+                    let checkpoints: Vec<checkpoint::CheckpointInfo> = checkpoint::list()
+                        .into_iter()
+                        .filter(|info| roots.iter().any(|root| Path::new(&info.root) == root))
+                        .collect();
+                    if !checkpoints.is_empty() {
+                        self.resume_prompt = Some(checkpoints);
+                        return Command::none();
+                    }
+                }
+                self.last_scan_started = Some(Instant::now());
+                self.scan_path = self.scan_queue.pop_front();
                 self.scanning = true;
+                self.scan_id += 1;
+                let scan_id = self.scan_id;
                 self.elapsed_time = Duration::from_secs(0);
                 self.error_message = None;
                 self.scan_duration = None;
 
                 let scan_count_clone = Arc::clone(&self.scan_count);
+                self.apply_scan_profile();
+                let mut config = self.scan_config.clone();
+                if self.scan_path.is_none() {
+                    config.skip_mounts = scan::mount_points();
+                }
+                // Only this scan resumes; the next one asks again.
+                self.scan_config.resume_checkpoints = false;
+                self.resume_decided = false;
+                let progress = Arc::new(ScanProgress::default());
+                self.scan_progress = Arc::clone(&progress);
+                self.current_dir_seen = None;
+                self.active_paths.clear();
+                self.cpu_history.clear();
+                self.mem_history.clear();
+                let scan_path = self.scan_path.clone();
                 let (tx, rx) = std::sync::mpsc::channel();
 
                 thread::spawn(move || {
+                    config.priority.apply_to_current_thread();
                     let start_time = Instant::now();
                     let system = System::new_all();
                     let mut disks: Vec<DiskInfo> = Vec::new();
+                    let mut no_capacity = Vec::new();
+
+                    // A dropped folder is scanned on its own, named by its path and
+                    // sized against the disk it sits on.
+                    if let Some(root) = scan_path {
+                        let walk = scan::walk_files(&root, &config, &progress, start_time);
+                        let path = root.display().to_string();
+                        let space = scan::disk_space(&root).unwrap_or((0.0, 0.0));
+                        let mut disk = DiskInfo::from_walk(path.clone(), path, space, walk, None, None);
+                        disk.folder = true;
+                        disks.push(disk);
+                    } else {
+                        let is_virtual = |disk: &sysinfo::Disk| {
+                            !config.include_virtual_filesystems
+                                && scan::is_virtual_filesystem(disk.file_system(), &config.virtual_filesystems)
+                        };
+                        let is_excluded =
+                            |disk: &sysinfo::Disk| config.excluded_disks.iter().any(|mount_point| Path::new(mount_point) == disk.mount_point());
+                        let expected_bytes: u64 = system
+                            .disks()
+                            .iter()
+                            .filter(|disk| scan::is_walked(disk, &config) && !is_excluded(disk))
+                            .map(|disk| disk.total_space().saturating_sub(disk.available_space()))
+                            .sum();
+                        progress.expected_bytes.store(expected_bytes, Ordering::Relaxed);
+
+                        for disk in system.disks().iter().filter(|disk| !is_virtual(disk) && !is_excluded(disk)) {
+                            if progress.is_cancelled() {
+                                break;
+                            }
+                            let (total_space, used_space) = scan::space_gb(disk);
 
-                    for disk in system.disks() {
-                        let total_space = disk.total_space() as f64 / 1_073_741_824.0;
-                        let used_space = (disk.total_space() - disk.available_space()) as f64 / 1_073_741_824.0;
-
-                        let files = Arc::new(Mutex::new(Vec::new()));
-
-                        if total_space > 0.0 {
-                            WalkDir::new(disk.mount_point())
-                                .into_iter()
-                                .par_bridge()
-                                .filter_map(|e| e.ok())
-                                .for_each(|entry| {
-                                    let path = entry.path();
-                                    if let Ok(metadata) = fs::metadata(path) {
-                                        if metadata.is_file() {
-                                            let file_info = FileInfo {
-                                                path: path.display().to_string(),
-                                                size_mb: metadata.len() as f64 / 1_048_576.0,
-                                            };
-                                            files.lock().unwrap().push(file_info);
-                                        }
-                                    }
-                                });
-
-                            let mut files = Arc::try_unwrap(files).unwrap().into_inner().unwrap();
-                            files.sort_by(|a, b| b.size_mb.partial_cmp(&a.size_mb).unwrap());
-
-                            disks.push(DiskInfo {
-                                name: disk.name().to_string_lossy().to_string(),
-                                total_space,
-                                used_space,
-                                files,
-                            });
+                            // Card readers without a card and unformatted volumes
+                            // report no size; there is nothing to walk on them.
+                            if total_space <= 0.0 {
+                                no_capacity.push((disk.name().to_string_lossy().to_string(), disk.mount_point().display().to_string()));
+                            } else {
+                                let network = scan::is_network_disk(disk.file_system(), disk.mount_point());
+                                let (walk, skipped_reason) = if network && !config.include_network {
+                                    let walk = scan::WalkOutput::default();
+                                    (walk, Some(tr("skipped-network-reason")))
+                                } else {
+                                    (scan::walk_files(disk.mount_point(), &config, &progress, start_time), None)
+                                };
+                                disks.push(DiskInfo::from_walk(
+                                    disk.name().to_string_lossy().to_string(),
+                                    disk.mount_point().display().to_string(),
+                                    (total_space, used_space),
+                                    walk,
+                                    skipped_reason,
+                                    scan::dedup_ratio(disk.file_system(), &disk.name().to_string_lossy(), disk.mount_point(), &config),
+                                ));
+                            }
                         }
                     }
 
+                    // Each walk kept at most the cap; together they may still hold more.
+                    if let Some(cap) = config.max_file_entries {
+                        cap_file_entries(disks.iter_mut(), cap);
+                    }
+
                     let duration = start_time.elapsed().as_secs_f64();
                     scan_count_clone.fetch_add(1, Ordering::SeqCst);
+                    progress.share_paths(disks.iter_mut().flat_map(|disk| &mut disk.files));
 
-                    if disks.is_empty() {
-                        let _ = tx.send(Err("Failed to retrieve disk information".to_string()));
+                    if progress.is_cancelled() {
+                        let _ = tx.send(Err(tr("scan-cancelled")));
+                    } else if disks.is_empty() {
+                        let _ = tx.send(Err(tr("disk-info-failed")));
                     } else {
-                        let _ = tx.send(Ok((disks, duration)));
+                        let _ = tx.send(Ok((disks, no_capacity, duration)));
                     }
                 });
 
-                return Command::perform(async move {
-                    let result = rx.recv().unwrap();
-                    result
-                }, |result: Result<(Vec<DiskInfo>, f64), String>| Message::Scanned(result));
+                Command::perform(async move { rx.recv().unwrap() }, move |result| Message::Scanned(scan_id, result))
+            }
+            Message::QuickScan => {
+                self.select_scan_profile(ScanProfile::Quick);
+                self.update(Message::Scan)
+            }
+            Message::DeepScan => {
+                self.select_scan_profile(ScanProfile::Deep);
+                self.update(Message::Scan)
             }
             Message::StopScan => {
-                self.scanning = false;
+                // The worker notices the flag and reports back via `Scanned`,
+                // which is when the UI leaves the scanning state, unless it
+                // takes longer than `STOP_TIMEOUT`.
+                self.scan_progress.cancelled.store(true, Ordering::Relaxed);
+                self.stop_requested.get_or_insert_with(Instant::now);
+                self.last_scan_started = None;
                 Command::none()
             }
-            Message::Scanned(result) => {
+            // A scan given up on while stopping reports too late to count.
+            Message::Scanned(id, _) if id != self.scan_id => Command::none(),
+            Message::Scanned(_, result) => {
                 self.scanning = false;
+                self.stop_requested = None;
+                let background = std::mem::take(&mut self.background_scan);
+                let shown_before: Vec<String> = self.disks.iter().map(DiskInfo::display_name).collect();
+                self.refresh_detected_disks();
                 match result {
-                    Ok((disks, duration)) => {
-                        self.disks = disks;
+                    Ok((disks, no_capacity, duration)) => {
+                        if self.settings.notify_on_complete && duration >= self.settings.notify_threshold_secs as f64 {
+                            let file_count: usize = disks.iter().map(|disk| disk.total_files()).sum();
+                            notify::send(
+                                &tr("app-title"),
+                                &tr_args("notify-scan-complete", &[
+                                    ("files", format_count(file_count)),
+                                    ("seconds", format_number(duration, 0)),
+                                ]),
+                            );
+                        }
+                        if self.scan_path.is_some() {
+                            // A folder scan is added to what's shown, replacing an
+                            // earlier scan of the same folder.
+                            self.keep_snapshot(Arc::clone(&self.disks));
+                            let shown_disks = Arc::make_mut(&mut self.disks);
+                            for disk in disks {
+                                match shown_disks.iter_mut().find(|shown| shown.source.is_none() && shown.name == disk.name) {
+                                    Some(shown) => *shown = disk,
+                                    None => shown_disks.push(disk),
+                                }
+                            }
+                        } else {
+                            // A scan replaces this machine's disks but keeps imported ones.
+                            let previous = std::mem::replace(&mut self.disks, Arc::new(disks));
+                            Arc::make_mut(&mut self.disks).extend(previous.iter().filter(|disk| disk.source.is_some()).cloned());
+                            self.keep_snapshot(previous);
+                            self.no_capacity_disks = no_capacity;
+                        }
                         self.scan_duration = Some(duration);
+                        self.record_scan_run(duration);
+                        self.clone_pairs = None;
+                        self.same_name_groups = None;
+                        self.refresh_treemap();
+                        self.refresh_owner_choices();
+                        self.focused_file = None;
+                        // An auto-refresh of the same disks leaves the view as the
+                        // user had it.
+                        let keep_view = background && self.disks.iter().map(DiskInfo::display_name).eq(shown_before);
+                        if keep_view {
+                            let paths: HashSet<&str> = self.disks.iter().flat_map(|disk| &disk.files).map(|file| &*file.path).collect();
+                            self.selected_files.retain(|path| paths.contains(path.as_str()));
+                        } else {
+                            self.selected_files.clear();
+                            self.scopes = vec![None; self.disks.len()];
+                            self.drilldowns = vec![None; self.disks.len()];
+                            self.row_limits.clear();
+                        }
+                        self.refresh_visible_files();
+                        self.update_fill_estimates();
+                        self.refresh_growth_report();
+                        self.notify_nearly_full();
+                        self.check_low_space();
+                        if let Some(tray) = &self.tray {
+                            let file_count: usize = self.disks.iter().filter(|disk| disk.source.is_none()).map(|disk| disk.total_files()).sum();
+                            tray.set_summary(tr_args("tray-last-scan", &[
+                                ("date", format_date(SystemTime::now())),
+                                ("files", format_count(file_count)),
+                                ("seconds", format_number(duration, 0)),
+                            ]));
+                        }
+                        let snaps: Vec<Command<Message>> = if keep_view {
+                            Vec::new()
+                        } else {
+                            self.disk_scroll_offsets = vec![RelativeOffset::START; self.disks.len()];
+                            (0..self.disks.len())
+                                .map(|index| scrollable::snap_to(disk_scroll_id(index), RelativeOffset::START))
+                                .collect()
+                        };
+                        let auto_export = self.auto_export();
+                        self.auto_exporting = auto_export.is_some();
+                        let growth_log = self.append_growth_log();
+                        let trash = self.measure_trash();
+                        let next = self.scan_next_queued();
+                        return Command::batch(snaps.into_iter().chain(growth_log).chain(auto_export).chain([trash, next]));
                     }
                     Err(e) => {
+                        if self.scan_progress.is_cancelled() {
+                            self.scan_queue.clear();
+                        }
                         self.error_message = Some(e);
                     }
                 }
+                self.scan_next_queued()
+            }
+            Message::FileDropped(path) => {
+                if !path.is_dir() {
+                    self.error_message = Some(tr_args("drop-not-folder", &[("path", path.display().to_string())]));
+                    return Command::none();
+                }
+                self.scan_queue.push_back(path);
+                if self.scanning {
+                    Command::none()
+                } else {
+                    self.scan_next_queued()
+                }
+            }
+            Message::Tick if self.stop_requested.is_some_and(|since| since.elapsed() >= STOP_TIMEOUT) => {
+                // The worker is stuck, say on a hung network mount. It goes on
+                // in the background and its result is dropped when it comes.
+                self.scanning = false;
+                self.background_scan = false;
+                self.stop_requested = None;
+                self.scan_id += 1;
+                self.scan_queue.clear();
+                self.error_message = Some(tr("scan-stop-timed-out"));
                 Command::none()
             }
             Message::Tick => {
                 if self.scanning {
                     self.elapsed_time += Duration::from_secs(1);
+                    let current_dir = self.scan_progress.current_dir();
+                    if self.current_dir_seen.as_ref().is_none_or(|(dir, _)| *dir != current_dir) {
+                        self.current_dir_seen = Some((current_dir, self.elapsed_time));
+                    }
+                    self.active_paths = self.scan_progress.active_paths(ACTIVE_PATHS_SHOWN);
+                    self.sample_resources();
+                }
+                Command::none()
+            }
+            Message::SampleAppMemory => {
+                self.sample_app_memory();
+                Command::none()
+            }
+            Message::Export => self.export(self.settings.export_format),
+            Message::CopyMarkdownSummary => {
+                iced::clipboard::write(report::markdown_report(&self.disks, self.settings.nearly_full_percent))
+            }
+            Message::ToggleSummaryTable => {
+                self.summary_expanded = !self.summary_expanded;
+                Command::none()
+            }
+            Message::ExportAsSqlite => {
+                self.exporting = true;
+                let disks = Arc::clone(&self.disks);
+                let progress = self.start_export_progress(disks.iter().map(|disk| disk.files.len()).sum());
+                export_on_worker(move || {
+                    let path = Path::new(sqlite::EXPORT_PATH);
+                    let result = sqlite::export(&disks, path, &progress);
+                    if result.is_err() {
+                        let _ = fs::remove_file(path);
+                    }
+                    result
+                })
+            }
+            Message::ExportAnalysis(report) => {
+                self.exporting = true;
+                pick_analysis_file(report)
+            }
+            Message::AnalysisExportPicked(report, path) => match path {
+                Some(path) => {
+                    let disks = Arc::clone(&self.disks);
+                    let compress = self.settings.compress_exports;
+                    export_on_worker(move || export_analysis(report, &disks, path, compress))
+                }
+                None => {
+                    self.exporting = false;
+                    Command::none()
+                }
+            },
+            Message::ExportAs(format) => self.export(format),
+            Message::ToggleDarkMode(enabled) => {
+                self.settings.dark_mode = enabled;
+                self.save_settings();
+                Command::none()
+            }
+            Message::OpenPalette => {
+                self.palette = Some(Palette::default());
+                text_input::focus(palette_input_id())
+            }
+            Message::PaletteQueryChanged(query) => {
+                self.palette = Some(Palette { query, selected: 0 });
+                Command::none()
+            }
+            Message::PaletteSubmit => {
+                let selected = self.palette.as_ref().map_or(0, |palette| palette.selected);
+                self.update(Message::PaletteRun(selected))
+            }
+            Message::PaletteRun(index) => match self.palette_matches().into_iter().nth(index) {
+                Some((_, message)) => {
+                    self.palette = None;
+                    self.update(message)
                 }
+                None => Command::none(),
+            },
+            Message::ExportFormatSelected(format) => {
+                self.settings.export_format = format;
+                self.save_settings();
+                Command::none()
+            }
+            Message::ExportPerDiskToggled(enabled) => {
+                self.export_per_disk = enabled;
                 Command::none()
             }
-            Message::ExportAsJson => {
-                let disks = self.disks.clone();
-                Command::perform(async move { export_to_json(disks) }, Message::ExportCompleted)
+            Message::CompressExportsToggled(enabled) => {
+                self.settings.compress_exports = enabled;
+                self.save_settings();
+                Command::none()
             }
-            Message::ExportAsCsv => {
-                let disks = self.disks.clone();
-                Command::perform(async move { export_to_csv(disks) }, Message::ExportCompleted)
+            Message::ExportFolderPicked(format, folder) => match folder {
+                Some(dir) => {
+                    let disks = Arc::clone(&self.disks);
+                    let compress = self.settings.compress_exports;
+                    let nearly_full_percent = self.settings.nearly_full_percent;
+                    let progress = self.start_export_progress(format.row_count(&disks));
+                    let (tx, rx) = std::sync::mpsc::channel();
+                    thread::spawn(move || {
+                        let _ = tx.send(export_per_disk(&disks, format, dir, compress, nearly_full_percent, &progress));
+                    });
+                    Command::perform(async move { rx.recv().unwrap_or_default() }, Message::PerDiskExportCompleted)
+                }
+                None => {
+                    self.exporting = false;
+                    Command::none()
+                }
+            },
+            Message::PerDiskExportCompleted(report) => {
+                self.exporting = false;
+                self.export_progress = None;
+                self.error_message = None;
+                self.last_export_checksum = None;
+                self.per_disk_export_report = report;
+                Command::none()
             }
             Message::ExportCompleted(result) => {
-                self.error_message = result.err();
+                self.exporting = false;
+                let cancelled = self.export_progress.take().is_some_and(|progress| progress.is_cancelled());
+                match result {
+                    Ok(checksum) => {
+                        self.error_message = None;
+                        self.last_export_checksum = Some(checksum);
+                        self.per_disk_export_report.clear();
+                    }
+                    // Asked for; the partial file is already gone.
+                    Err(_) if cancelled => self.error_message = None,
+                    Err(e) => self.error_message = Some(e),
+                }
+                Command::none()
+            }
+            // Only redraws the status bar's row count.
+            Message::ExportTick => Command::none(),
+            Message::CancelExport => {
+                if let Some(progress) = &self.export_progress {
+                    progress.cancel();
+                }
+                Command::none()
+            }
+            Message::ImportJson => Command::perform(
+                async {
+                    rfd::AsyncFileDialog::new()
+                        .add_filter("JSON", &["json", "gz"])
+                        .pick_file()
+                        .await
+                        .map(|file| file.path().to_path_buf())
+                },
+                Message::ImportFilePicked,
+            ),
+            Message::ImportFilePicked(Some(path)) => {
+                Command::perform(async move { import_from_json(&path) }, Message::Imported)
+            }
+            Message::ImportFilePicked(None) => Command::none(),
+            Message::RemoveSource(source) => {
+                Arc::make_mut(&mut self.disks).retain(|disk| disk.source.as_deref() != Some(source.as_str()));
+                self.selected_files.clear();
+                self.clone_pairs = None;
+                self.same_name_groups = None;
+                self.refresh_treemap();
+                self.refresh_owner_choices();
+                self.focused_file = None;
+                self.scopes = vec![None; self.disks.len()];
+                self.drilldowns = vec![None; self.disks.len()];
+                self.refresh_visible_files();
+                self.disk_scroll_offsets = vec![RelativeOffset::START; self.disks.len()];
+                Command::none()
+            }
+            Message::Imported(result) => {
+                match result {
+                    Ok((disks, warning)) => {
+                        // Imports add to the view; re-importing a source replaces its old disks.
+                        self.error_message = None;
+                        self.import_warning = warning;
+                        let sources: HashSet<Option<String>> = disks.iter().map(|disk| disk.source.clone()).collect();
+                        let shown_disks = Arc::make_mut(&mut self.disks);
+                        shown_disks.retain(|disk| !sources.contains(&disk.source));
+                        shown_disks.extend(disks);
+                        self.selected_files.clear();
+                        self.clone_pairs = None;
+                        self.same_name_groups = None;
+                        self.refresh_treemap();
+                        self.refresh_owner_choices();
+                        self.focused_file = None;
+                        self.scopes = vec![None; self.disks.len()];
+                        self.drilldowns = vec![None; self.disks.len()];
+                        self.refresh_visible_files();
+                        self.refresh_growth_report();
+                        self.disk_scroll_offsets = vec![RelativeOffset::START; self.disks.len()];
+                        return Command::batch(
+                            (0..self.disks.len()).map(|index| scrollable::snap_to(disk_scroll_id(index), RelativeOffset::START)),
+                        );
+                    }
+                    Err(e) => {
+                        self.import_warning = None;
+                        self.error_message = Some(e);
+                    }
+                }
+                Command::none()
+            }
+            Message::CheckFocusRequest => {
+                if self.focus_requested.swap(false, Ordering::SeqCst) {
+                    // The window may also be hidden in the tray.
+                    Command::batch(vec![
+                        iced::window::change_mode(iced::window::Mode::Windowed),
+                        iced::window::minimize(false),
+                        iced::window::gain_focus(),
+                    ])
+                } else {
+                    Command::none()
+                }
+            }
+            // Minimizing reports a zero size on some platforms; keep the last real one.
+            Message::WindowResized(0, _) | Message::WindowResized(_, 0) => Command::none(),
+            Message::WindowResized(width, height) => {
+                let state = self.window_state();
+                self.settings.window = Some(WindowState { width, height, ..state });
+                self.window_state_dirty = true;
+                Command::none()
+            }
+            Message::WindowMoved(x, y) => {
+                let state = self.window_state();
+                self.settings.window = Some(WindowState { x: Some(x), y: Some(y), ..state });
+                self.window_state_dirty = true;
+                Command::none()
+            }
+            Message::SaveWindowState => {
+                self.window_state_dirty = false;
+                self.save_settings();
+                Command::none()
+            }
+            Message::CloseRequested if self.settings.minimize_to_tray => {
+                self.confirm_quit = false;
+                // The tray may be gone this session; then the window is only
+                // minimized, since a hidden one couldn't be brought back.
+                if self.tray.is_some() {
+                    iced::window::change_mode(iced::window::Mode::Hidden)
+                } else {
+                    iced::window::minimize(true)
+                }
+            }
+            Message::Done | Message::CloseRequested => {
+                if self.is_busy() {
+                    self.confirm_quit = true;
+                    Command::none()
+                } else {
+                    self.quit()
+                }
+            }
+            Message::CancelQuit => {
+                self.confirm_quit = false;
                 Command::none()
             }
-            Message::Done => {
-                std::process::exit(0);
+            Message::TrashMeasured(usage) => {
+                self.trash_usage = usage;
+                Command::none()
+            }
+            Message::EmptyTrash(mount_point) => {
+                self.confirm_empty_trash = Some(mount_point);
+                Command::none()
+            }
+            Message::CancelEmptyTrash => {
+                self.confirm_empty_trash = None;
+                Command::none()
+            }
+            Message::ConfirmEmptyTrash => {
+                let Some(mount_point) = self.confirm_empty_trash.take() else {
+                    return Command::none();
+                };
+                self.emptying_trash = true;
+                let mount_points = self.local_mount_points();
+                let (tx, rx) = std::sync::mpsc::channel();
+                thread::spawn(move || {
+                    let _ = tx.send(trash::empty(&mount_point, &mount_points));
+                });
+                Command::perform(
+                    async move { rx.recv().unwrap_or_else(|_| Err(tr("trash-empty-failed"))) },
+                    Message::TrashEmptied,
+                )
+            }
+            Message::TrashEmptied(result) => {
+                self.emptying_trash = false;
+                if let Err(e) = result {
+                    self.error_message = Some(tr_args("trash-empty-error", &[("error", e)]));
+                }
+                // Whatever got deleted shows up as free space right away.
+                for disk in Arc::make_mut(&mut self.disks).iter_mut().filter(|disk| disk.source.is_none()) {
+                    if let Some((total_space, used_space)) = scan::disk_space(Path::new(&disk.mount_point)) {
+                        disk.total_space = total_space;
+                        disk.used_space = used_space;
+                    }
+                }
+                self.refresh_visible_files();
+                self.measure_trash()
+            }
+            Message::ConfirmQuit => {
+                self.confirm_quit = false;
+                self.quitting = true;
+                self.scan_progress.cancelled.store(true, Ordering::Relaxed);
+                Command::none()
+            }
+            Message::QuitWhenIdle => {
+                if self.is_busy() {
+                    Command::none()
+                } else {
+                    self.quit()
+                }
             }
             Message::Refresh => {
                 self.scan_duration = None;
+                self.refresh_detected_disks();
                 Command::perform(async { Ok(()) }, |_: Result<(), ()>| Message::Scan)
             }
+            Message::RescanDisk(name) => {
+                match self.disks.iter().find(|disk| disk.source.is_none() && disk.name == name) {
+                    Some(disk) if disk.skipped_reason.is_some() && !self.scan_config.include_network => {
+                        self.error_message = Some(tr_args("network-rescan-blocked", &[("disk", disk.name.clone())]));
+                        Command::none()
+                    }
+                    Some(disk) => {
+                        let mount_point = PathBuf::from(&disk.mount_point);
+                        self.start_rescan(name, mount_point.clone(), mount_point)
+                    }
+                    None => Command::none(),
+                }
+            }
+            Message::Rescanned(disk_name, root, result) => {
+                self.active_rescans.retain(|active| active != &root);
+                match result {
+                    Ok(subtree) => {
+                        if let Some(disk) = Arc::make_mut(&mut self.disks).iter_mut().find(|disk| disk.source.is_none() && disk.name == disk_name) {
+                            if root == Path::new(&disk.mount_point) {
+                                self.row_limits.remove(&disk.display_name());
+                                disk.skipped_reason = None;
+                                disk.skipped_placeholders = subtree.walk.skipped_placeholders;
+                                disk.truncated = subtree.walk.truncated;
+                                disk.quota_exceeded = subtree.walk.quota_exceeded.is_some();
+                                disk.io_quota_bytes = subtree.walk.quota_exceeded;
+                                disk.entries_capped = false;
+                                disk.skipped_mounts.clear();
+                                disk.disconnected = false;
+                                disk.ignore_patterns = subtree.walk.ignore_patterns;
+                                disk.scanned_at = Some(util::unix_now());
+                            }
+                            disk.files.retain(|file| !Path::new(&*file.path).starts_with(&root));
+                            disk.files.extend(subtree.walk.files);
+                            disk.archive_members.retain(|file| !Path::new(&*file.path).starts_with(&root));
+                            disk.archive_members.extend(subtree.walk.archive_members);
+                            disk.archive_members.sort_by(|a, b| a.path.cmp(&b.path));
+                            disk.unstored.retain(|dir, _| !Path::new(dir).starts_with(&root));
+                            disk.unstored.extend(subtree.walk.unstored);
+                            disk.projects.retain(|project| !Path::new(&project.path).starts_with(&root));
+                            disk.projects.extend(subtree.walk.projects);
+                            disk.projects.sort_by(|a, b| b.total_size_mb.partial_cmp(&a.total_size_mb).unwrap_or(std::cmp::Ordering::Equal));
+                            disk.artifacts.retain(|artifact| !Path::new(&artifact.path).starts_with(&root));
+                            disk.artifacts.extend(subtree.walk.artifacts);
+                            disk.artifacts.sort_by(|a, b| b.size_mb.total_cmp(&a.size_mb));
+                            disk.entries_capped |= subtree.walk.capped;
+                            disk.skipped_mounts.retain(|mount| !Path::new(mount).starts_with(&root));
+                            disk.skipped_mounts.extend(subtree.walk.skipped_mounts);
+                            disk.skipped_mounts.sort();
+                            disk.disconnected |= subtree.walk.disconnected;
+                            self.scan_config.storage_order.apply(&mut disk.files);
+                            disk.summarize_files();
+                            if let Some((total_space, used_space)) = subtree.space {
+                                disk.total_space = total_space;
+                                disk.used_space = used_space;
+                            }
+                        }
+                        if let Some(cap) = self.scan_config.max_file_entries {
+                            cap_file_entries(Arc::make_mut(&mut self.disks).iter_mut().filter(|disk| disk.source.is_none()), cap);
+                        }
+                        // Both were found among the files the rescan replaced.
+                        self.clone_pairs = None;
+                        self.same_name_groups = None;
+                        self.refresh_treemap();
+                        self.refresh_owner_choices();
+                        self.refresh_visible_files();
+                        self.notify_nearly_full();
+                        self.check_low_space();
+                    }
+                    Err(e) => {
+                        self.error_message = Some(e);
+                    }
+                }
+                Command::none()
+            }
+            Message::BinaryFilterSelected(filter) => {
+                self.binary_filter = filter;
+                self.refresh_visible_files();
+                Command::none()
+            }
+            Message::AgeFilterSelected(filter) => {
+                self.age_filter = filter;
+                self.refresh_visible_files();
+                Command::none()
+            }
+            Message::OwnerFilterSelected(filter) => {
+                self.owner_filter = filter;
+                self.refresh_visible_files();
+                Command::none()
+            }
+            Message::WorldWritableToggled(only) => {
+                self.world_writable_only = only;
+                self.refresh_visible_files();
+                Command::none()
+            }
+            Message::SetuidToggled(only) => {
+                self.setuid_only = only;
+                self.refresh_visible_files();
+                Command::none()
+            }
+            Message::ChecksumTopNChanged(value) => {
+                self.settings.checksum_top_n = parse_optional(&value, Some(self.settings.checksum_top_n)).unwrap_or(0);
+                self.setting_inputs.insert(SettingInput::ChecksumTopN, value);
+                self.save_settings();
+                Command::none()
+            }
+            Message::MinDirSizeChanged(value) => {
+                if let Some(mb) = value.trim().replace(',', ".").parse::<f64>().ok().filter(|mb| mb.is_finite()) {
+                    self.settings.min_dir_size_mb = mb.max(0.0);
+                    self.save_settings();
+                    self.refresh_visible_files();
+                }
+                self.setting_inputs.insert(SettingInput::MinDirSize, value);
+                Command::none()
+            }
+            Message::ChartColoringSelected(coloring) => {
+                self.settings.chart_coloring = coloring;
+                self.save_settings();
+                self.refresh_visible_files();
+                Command::none()
+            }
+            Message::MimeFilterChanged(filter) => {
+                self.mime_filter = filter;
+                self.refresh_visible_files();
+                Command::none()
+            }
+            Message::MimeMismatchToggled(only) => {
+                self.mime_mismatch_only = only;
+                self.refresh_visible_files();
+                Command::none()
+            }
+            Message::BinaryCheckLimitChanged(value) => {
+                self.settings.binary_check_max_mb = parse_optional(&value, self.settings.binary_check_max_mb);
+                self.setting_inputs.insert(SettingInput::BinaryCheckLimit, value);
+                self.save_settings();
+                Command::none()
+            }
+            Message::LineCountLimitChanged(value) => {
+                self.settings.line_count_max_mb = parse_optional(&value, self.settings.line_count_max_mb);
+                self.setting_inputs.insert(SettingInput::LineCountLimit, value);
+                self.save_settings();
+                Command::none()
+            }
+            Message::HiddenFilterSelected(filter) => {
+                self.hidden_filter = filter;
+                self.refresh_visible_files();
+                Command::none()
+            }
             Message::FileTypeFilterChanged(new_filter) => {
                 self.file_type_filter = new_filter;
+                self.refresh_visible_files();
                 Command::none()
             }
             Message::FileNameFilterChanged(new_filter) => {
                 self.file_name_filter = new_filter;
+                self.refresh_visible_files();
                 Command::none()
             }
-        }
-    }
-
-    fn view(&self) -> Element<Self::Message> {
-    let mut content = Column::new()
-        .spacing(10)
-        .padding(10)
-        .max_width(800);
-
-    
-    if self.scanning {
-        content = content.push(Text::new("Scanning... Please wait..."));
-    } else {
-        // Show error message if any
-        if let Some(ref error_message) = self.error_message {
-            content = content.push(Text::new(error_message).style(iced::Color::from_rgb(1.0, 0.0, 0.0)));
-        }
-
-        // File filters
-        content = content.push(
-            TextInput::new("File type filter (e.g., .txt, .jpg)", &self.file_type_filter)
-                .on_input(Message::FileTypeFilterChanged)
-                .padding(5),
-        );
-
-        content = content.push(
-            TextInput::new("File name filter (e.g., report)", &self.file_name_filter)
-                .on_input(Message::FileNameFilterChanged)
-                .padding(5),
-        );
-
-        
-        for disk in &self.disks {
-            let usage_percentage = (disk.used_space / disk.total_space) * 100.0;
-            content = content
-                .push(Text::new(format!("Disk: {}", disk.name)))
-                .push(Text::new(format!("Total Space: {:.2} GB", disk.total_space)))
-                .push(Text::new(format!("Used Space: {:.2} GB", disk.used_space)))
-                .push(ProgressBar::new(0.0..=100.0, usage_percentage as f32).height(10));
-
-            
-            let mut matching_files: Vec<FileInfo> = disk
-                .files
-                .iter()
-                .filter(|file| {
-                    (self.file_type_filter.is_empty() || file.path.ends_with(&self.file_type_filter)) &&
-                    (self.file_name_filter.is_empty() || file.path.contains(&self.file_name_filter))
-                })
-                .cloned()
-                .collect();
-
-            
-            matching_files.sort_by(|a, b| b.size_mb.partial_cmp(&a.size_mb).unwrap_or(std::cmp::Ordering::Equal));
-            let top_files = matching_files.iter().take(5);
-
-            
-            for file in top_files {
-                let (size, unit) = if file.size_mb >= 1000.0 {
-                    (file.size_mb / 1024.0, "GB")
-                } else {
-                    (file.size_mb, "MB")
-                };
-                content = content.push(Text::new(format!("File: {}, Size: {:.2} {}", file.path, size, unit)));
+            Message::MinSizeFilterChanged(new_filter) => {
+                self.min_size_filter = new_filter;
+                self.refresh_visible_files();
+                Command::none()
             }
-        }
-    }
-
-    
-    if self.scanning {
-        content = content.push(Text::new(format!(
-            "Time Elapsed: {:.0} seconds",
-            self.elapsed_time.as_secs()
-        )));
-    } else if let Some(duration) = self.scan_duration {
-        content = content.push(Text::new(format!("Scan Duration: {:.2} seconds", duration)));
+            Message::OlderThanFilterChanged(new_filter) => {
+                self.older_than_filter = new_filter;
+                self.refresh_visible_files();
+                Command::none()
+            }
+            Message::SetScope(disk, scope) => {
+                if let Some(entry) = self.scopes.get_mut(disk) {
+                    *entry = scope;
+                    if let Some(drilldown) = self.drilldowns.get_mut(disk) {
+                        *drilldown = None;
+                    }
+                    self.focused_file = None;
+                    self.refresh_visible_files();
+                    return scrollable::snap_to(disk_scroll_id(disk), RelativeOffset::START);
+                }
+                Command::none()
+            }
+            Message::ScopeUp => {
+                // The disk with keyboard focus, else the first one drilled or
+                // narrowed down. A drill-down is left before the folder.
+                let disk = self
+                    .focused_file
+                    .map(|(disk, _)| disk)
+                    .or_else(|| self.drilldowns.iter().position(Option::is_some))
+                    .or_else(|| self.scopes.iter().position(Option::is_some));
+                if let Some(disk) = disk.filter(|&disk| self.drilldowns.get(disk).is_some_and(Option::is_some)) {
+                    return self.update(Message::SetExtensionDrilldown(disk, None));
+                }
+                let parent = disk.and_then(|disk| {
+                    let scope = self.scopes.get(disk)?.as_ref()?;
+                    let parent = scope.parent().filter(|parent| *parent != Path::new(&self.disks[disk].mount_point));
+                    Some((disk, parent.map(Path::to_path_buf)))
+                });
+                match parent {
+                    Some((disk, parent)) => self.update(Message::SetScope(disk, parent)),
+                    None => Command::none(),
+                }
+            }
+            Message::SetExtensionDrilldown(disk, extension) => {
+                if let Some(entry) = self.drilldowns.get_mut(disk) {
+                    *entry = extension;
+                    self.focused_file = None;
+                    self.row_limits.remove(&self.disks[disk].display_name());
+                    self.refresh_visible_files();
+                    return scrollable::snap_to(disk_scroll_id(disk), RelativeOffset::START);
+                }
+                Command::none()
+            }
+            // Everything the drill-down lists, not just the rows shown so far.
+            Message::ExportExtensionList(disk) => {
+                let (Some(info), Some(rows)) = (self.disks.get(disk), self.visible_files.get(disk)) else {
+                    return Command::none();
+                };
+                let shares = self.visible_shares.get(disk).cloned().unwrap_or_default();
+                let mut list = info.without_files();
+                list.files = rows
+                    .iter()
+                    .zip(shares)
+                    .map(|(&i, (share, cumulative))| FileInfo {
+                        share_percent: Some(share),
+                        cumulative_percent: Some(cumulative),
+                        ..info.files[i].clone()
+                    })
+                    .collect();
+                let extension = self.drilldowns.get(disk).cloned().flatten().unwrap_or_default();
+                let format = self.settings.export_format;
+                let compress = self.settings.compress_exports;
+                let nearly_full_percent = self.settings.nearly_full_percent;
+                let path = PathBuf::from(format!(
+                    "files_{}_{}.{}",
+                    util::sanitize_file_name(&info.name),
+                    util::sanitize_file_name(&report::extension_label(&extension)),
+                    format.extension(),
+                ));
+                self.exporting = true;
+                let progress = self.start_export_progress(format.row_count(std::slice::from_ref(&list)));
+                export_on_worker(move || {
+                    write_export(&export_path(path, compress), format, &[list], compress, nearly_full_percent, &progress)
+                })
+            }
+            // Only the slice of the cached list grows; nothing is re-filtered.
+            Message::ShowMoreFiles(disk) => {
+                let limit = self.row_limits.entry(disk).or_insert(INITIAL_FILE_ROWS);
+                *limit += MORE_FILE_ROWS;
+                Command::none()
+            }
+            Message::CollapseFiles(disk) => {
+                self.row_limits.remove(&disk);
+                if let Some((focused_disk, row)) = self.focused_file {
+                    if row >= self.shown_rows(focused_disk) {
+                        self.focused_file = None;
+                    }
+                }
+                Command::none()
+            }
+            Message::SortKeySelected(key) => {
+                self.settings.sort_key = key;
+                self.save_settings();
+                self.refresh_visible_files();
+                Command::none()
+            }
+            Message::ToggleSortDirection => {
+                self.settings.sort_descending = !self.settings.sort_descending;
+                self.save_settings();
+                self.refresh_visible_files();
+                Command::none()
+            }
+            Message::PresetNameChanged(name) => {
+                self.preset_name = name;
+                Command::none()
+            }
+            Message::SavePreset => {
+                let mut preset = self.current_filters();
+                preset.name = self.preset_name.trim().to_string();
+                if preset.name.is_empty() {
+                    return Command::none();
+                }
+                match self.settings.filter_presets.iter_mut().find(|existing| existing.name == preset.name) {
+                    Some(existing) => *existing = preset,
+                    None => self.settings.filter_presets.push(preset),
+                }
+                self.preset_name.clear();
+                self.save_settings();
+                Command::none()
+            }
+            Message::ApplyPreset(index) => {
+                // Clicking the chip that is already applied clears the filters.
+                let preset = match self.settings.filter_presets.get(index) {
+                    Some(preset) if self.is_active_preset(preset) => FilterPreset::default(),
+                    Some(preset) => preset.clone(),
+                    None => return Command::none(),
+                };
+                self.file_type_filter = preset.file_type;
+                self.file_name_filter = preset.file_name;
+                self.hidden_filter = preset.hidden;
+                self.binary_filter = preset.binary;
+                self.age_filter = preset.age;
+                self.mime_filter = preset.mime_type;
+                self.mime_mismatch_only = preset.mime_mismatch;
+                self.owner_filter = preset.owner.map_or(OwnerFilter::All, OwnerFilter::Uid);
+                self.world_writable_only = preset.world_writable;
+                self.setuid_only = preset.setuid;
+                self.min_size_filter = preset.min_size_mb.map(|mb| mb.to_string()).unwrap_or_default();
+                self.older_than_filter = preset.older_than_days.map(|days| days.to_string()).unwrap_or_default();
+                self.refresh_visible_files();
+                Command::none()
+            }
+            Message::PresetRenamed(index, name) => {
+                if let Some(preset) = self.settings.filter_presets.get_mut(index).filter(|preset| !preset.builtin) {
+                    preset.name = name;
+                    self.save_settings();
+                }
+                Command::none()
+            }
+            Message::DeletePreset(index) => {
+                if self.settings.filter_presets.get(index).is_some_and(|preset| !preset.builtin) {
+                    self.settings.filter_presets.remove(index);
+                    self.save_settings();
+                }
+                Command::none()
+            }
+            Message::PresetChipToggled(index, shown) => {
+                if let Some(preset) = self.settings.filter_presets.get_mut(index) {
+                    preset.hide_chip = !shown;
+                    self.save_settings();
+                }
+                Command::none()
+            }
+            Message::MovePresetUp(index) => {
+                if index > 0 && index < self.settings.filter_presets.len() {
+                    self.settings.filter_presets.swap(index - 1, index);
+                    self.save_settings();
+                }
+                Command::none()
+            }
+            Message::InspectArchivesToggled(enabled) => {
+                self.scan_config.inspect_archives = enabled;
+                Command::none()
+            }
+            Message::RecurseArchivesToggled(enabled) => {
+                self.scan_config.recurse_archives = enabled;
+                Command::none()
+            }
+            Message::DiskScrolled(index, offset) => {
+                if let Some(entry) = self.disk_scroll_offsets.get_mut(index) {
+                    *entry = offset;
+                }
+                Command::none()
+            }
+            Message::SelectAllTempFiles => {
+                let temp_files = self.disks.iter().flat_map(|disk| &disk.files).filter(|file| file.is_temp);
+                self.selected_files.extend(temp_files.map(|file| file.path.to_string()));
+                Command::none()
+            }
+            Message::ClearSelection => {
+                self.selected_files.clear();
+                Command::none()
+            }
+            Message::FocusNext(backwards) => {
+                self.focused_file = None;
+                if backwards {
+                    iced::widget::focus_previous()
+                } else {
+                    iced::widget::focus_next()
+                }
+            }
+            Message::MoveFileFocus(delta) if self.palette.is_some() => {
+                let matches = self.palette_matches().len();
+                if let Some(palette) = &mut self.palette {
+                    palette.selected = palette.selected.saturating_add_signed(delta).min(matches.saturating_sub(1));
+                }
+                Command::none()
+            }
+            Message::MoveFileFocus(delta) => {
+                let (disk, row) = match self.focused_file {
+                    Some((disk, row)) => (disk, row.saturating_add_signed(delta)),
+                    None => (0, 0),
+                };
+                self.focus_file_row(disk, row)
+            }
+            Message::MoveDiskFocus(delta) => {
+                let disk = match self.focused_file {
+                    Some((disk, _)) => disk.saturating_add_signed(delta).min(self.disks.len().saturating_sub(1)),
+                    None => 0,
+                };
+                self.focus_file_row(disk, 0)
+            }
+            Message::ActivateFocusedFile => {
+                if let Some(path) = self.focused_file_path() {
+                    if !self.selected_files.remove(&path) {
+                        self.selected_files.insert(path);
+                    }
+                }
+                Command::none()
+            }
+            Message::ClearFocus => {
+                self.palette = None;
+                self.focused_file = None;
+                self.confirm_quit = false;
+                Command::none()
+            }
+            Message::ToggleMostFiles(disk_name) => {
+                if !self.expanded_most_files.remove(&disk_name) {
+                    self.expanded_most_files.insert(disk_name);
+                }
+                Command::none()
+            }
+            Message::MostFilesMaxAvgChanged(value) => {
+                self.most_files_max_avg_kb = value;
+                Command::none()
+            }
+            Message::ToggleSymlinks(disk_name) => {
+                if !self.expanded_symlinks.remove(&disk_name) {
+                    self.expanded_symlinks.insert(disk_name);
+                }
+                Command::none()
+            }
+            Message::ToggleArchiveMembers(disk_name) => {
+                if !self.expanded_archives.remove(&disk_name) {
+                    self.expanded_archives.insert(disk_name);
+                }
+                Command::none()
+            }
+            Message::ToggleGrowthPanel => {
+                self.growth_report = match self.growth_report {
+                    Some(_) => None,
+                    None => Some(growth::GrowthReport::default()),
+                };
+                self.growth_exported = None;
+                Command::none()
+            }
+            Message::GrowthBaselineSelected(baseline) => {
+                self.growth_report = Some(self.compare_growth(baseline));
+                self.growth_exported = None;
+                Command::none()
+            }
+            Message::ToggleFreedDirs => {
+                self.freed_dirs_expanded = !self.freed_dirs_expanded;
+                Command::none()
+            }
+            Message::ExportGrowth => match self.growth_report.clone() {
+                Some(report) => Command::perform(async move { report.export_csv() }, Message::GrowthExported),
+                None => Command::none(),
+            },
+            Message::GrowthExported(result) => {
+                match result {
+                    Ok(path) => self.growth_exported = Some(path),
+                    Err(e) => self.error_message = Some(e),
+                }
+                Command::none()
+            }
+            Message::DetectCloneDirs => {
+                self.detecting_clones = true;
+                let disks = Arc::clone(&self.disks);
+                let (tx, rx) = std::sync::mpsc::channel();
+                thread::spawn(move || {
+                    let _ = tx.send(clones::find_clone_dirs(disks.iter().flat_map(|disk| &disk.files)));
+                });
+                Command::perform(async move { rx.recv().unwrap_or_default() }, Message::CloneDirsFound)
+            }
+            Message::HistoryLoaded(loaded) => {
+                // Scans that finished before the saved history was read come after it.
+                let recorded = std::mem::replace(&mut self.scan_history, loaded.records);
+                let runs = std::mem::replace(&mut self.scan_runs, loaded.runs);
+                history::append_runs(&mut self.scan_runs, runs);
+                self.history_loaded = true;
+                if let Some(latest) = recorded.iter().map(|record| record.timestamp).max() {
+                    history::append(&mut self.scan_history, recorded);
+                    self.save_history();
+                    self.refresh_fill_estimates(latest);
+                }
+                self.refresh_growth_report();
+                Command::none()
+            }
+            Message::ToggleSameNamePanel => {
+                if self.same_name_groups.take().is_some() || self.finding_same_names {
+                    return Command::none();
+                }
+                self.finding_same_names = true;
+                let disks = Arc::clone(&self.disks);
+                let (tx, rx) = std::sync::mpsc::channel();
+                thread::spawn(move || {
+                    let _ = tx.send(same_name::same_name_groups(disks.iter().flat_map(|disk| &disk.files)));
+                });
+                Command::perform(async move { rx.recv().unwrap_or_default() }, Message::SameNamesFound)
+            }
+            Message::SameNamesFound(groups) => {
+                self.finding_same_names = false;
+                self.same_name_groups = Some(groups);
+                Command::none()
+            }
+            Message::ToggleAllDisksTreemap => {
+                self.all_disks_treemap = match self.all_disks_treemap {
+                    Some(_) => None,
+                    None => Some(treemap::Treemap::new(treemap::merged_files(&self.disks), treemap::merged_unstored(&self.disks))),
+                };
+                Command::none()
+            }
+            Message::RevealInFolder(path) => {
+                if let Err(e) = util::reveal_in_folder(Path::new(&path)) {
+                    self.error_message = Some(tr_args("reveal-failed", &[("error", e)]));
+                }
+                Command::none()
+            }
+            Message::TrashArtifact(path) => {
+                self.confirm_trash_artifact = Some(path);
+                Command::none()
+            }
+            Message::CancelTrashArtifact => {
+                self.confirm_trash_artifact = None;
+                Command::none()
+            }
+            Message::ConfirmTrashArtifact => {
+                let Some(path) = self.confirm_trash_artifact.take() else {
+                    return Command::none();
+                };
+                if !self.trashing_artifacts.insert(path.clone()) {
+                    return Command::none();
+                }
+                let (tx, rx) = std::sync::mpsc::channel();
+                let target = path.clone();
+                thread::spawn(move || {
+                    let _ = tx.send(trash::move_to_trash(Path::new(&target)));
+                });
+                Command::perform(async move { rx.recv().unwrap_or_else(|e| Err(e.to_string())) }, move |result| {
+                    Message::ArtifactTrashed(path, result)
+                })
+            }
+            Message::ArtifactTrashed(path, result) => {
+                self.trashing_artifacts.remove(&path);
+                match result {
+                    Ok(()) => {
+                        let root = Path::new(&path);
+                        for disk in Arc::make_mut(&mut self.disks).iter_mut().filter(|disk| disk.source.is_none()) {
+                            if !disk.artifacts.iter().any(|artifact| artifact.path == path) {
+                                continue;
+                            }
+                            disk.artifacts.retain(|artifact| !Path::new(&artifact.path).starts_with(root));
+                            disk.files.retain(|file| !Path::new(&*file.path).starts_with(root));
+                            disk.unstored.retain(|dir, _| !Path::new(dir).starts_with(root));
+                            disk.summarize_files();
+                            // The trash may sit on another disk, so the freed
+                            // space is whatever the disk now reports.
+                            if let Some((total_space, used_space)) = scan::disk_space(Path::new(&disk.mount_point)) {
+                                disk.total_space = total_space;
+                                disk.used_space = used_space;
+                            }
+                        }
+                        self.refresh_owner_choices();
+                        self.refresh_visible_files();
+                        self.measure_trash()
+                    }
+                    Err(e) => {
+                        self.error_message = Some(tr_args("trash-move-failed", &[("path", path), ("error", e)]));
+                        Command::none()
+                    }
+                }
+            }
+            Message::CopySelectedPaths => {
+                let mut paths: Vec<&str> = self.selected_files.iter().map(String::as_str).collect();
+                paths.sort_unstable();
+                iced::clipboard::write(paths.join("\n"))
+            }
+            Message::TrashSelected => {
+                self.confirm_trash_selected = true;
+                Command::none()
+            }
+            Message::CancelTrashSelected => {
+                self.confirm_trash_selected = false;
+                Command::none()
+            }
+            Message::ConfirmTrashSelected => {
+                self.confirm_trash_selected = false;
+                let paths = self.selected_local_files();
+                if paths.is_empty() || self.trashing_selected {
+                    return Command::none();
+                }
+                self.trashing_selected = true;
+                let (tx, rx) = std::sync::mpsc::channel();
+                thread::spawn(move || {
+                    let (mut moved, mut errors) = (Vec::new(), Vec::new());
+                    for path in paths {
+                        match trash::move_to_trash(Path::new(&path)) {
+                            Ok(()) => moved.push(path),
+                            Err(e) => errors.push(tr_args("trash-move-failed", &[("path", path), ("error", e)])),
+                        }
+                    }
+                    let _ = tx.send((moved, errors));
+                });
+                Command::perform(async move { rx.recv().unwrap_or_default() }, |(moved, errors)| Message::SelectedTrashed(moved, errors))
+            }
+            Message::SelectedTrashed(moved, errors) => {
+                self.trashing_selected = false;
+                let moved: HashSet<String> = moved.into_iter().collect();
+                for disk in Arc::make_mut(&mut self.disks).iter_mut().filter(|disk| disk.source.is_none()) {
+                    let before = disk.files.len();
+                    disk.files.retain(|file| !moved.contains(&*file.path));
+                    if disk.files.len() == before {
+                        continue;
+                    }
+                    disk.summarize_files();
+                    if let Some((total_space, used_space)) = scan::disk_space(Path::new(&disk.mount_point)) {
+                        disk.total_space = total_space;
+                        disk.used_space = used_space;
+                    }
+                }
+                self.selected_files.retain(|path| !moved.contains(path));
+                if let Some(first) = errors.first() {
+                    self.error_message = Some(match errors.len() {
+                        1 => first.clone(),
+                        count => tr_args("trash-selected-failed", &[("count", format_count(count)), ("error", first.clone())]),
+                    });
+                }
+                self.clone_pairs = None;
+                self.same_name_groups = None;
+                self.refresh_treemap();
+                self.refresh_owner_choices();
+                self.refresh_visible_files();
+                self.measure_trash()
+            }
+            Message::NewArtifactDirChanged(value) => {
+                self.new_artifact_dir = value;
+                Command::none()
+            }
+            Message::NewArtifactMarkersChanged(value) => {
+                self.new_artifact_markers = value;
+                Command::none()
+            }
+            Message::AddArtifactRule => {
+                let dir_name = self.new_artifact_dir.trim().to_string();
+                let markers: Vec<String> =
+                    self.new_artifact_markers.split(',').map(|marker| marker.trim().to_string()).filter(|marker| !marker.is_empty()).collect();
+                if !dir_name.is_empty() && !markers.is_empty() {
+                    self.settings.artifact_rules.push(scan::ArtifactRule { dir_name, markers });
+                    self.new_artifact_dir.clear();
+                    self.new_artifact_markers.clear();
+                    self.save_settings();
+                }
+                Command::none()
+            }
+            Message::RemoveArtifactRule(index) => {
+                if index < self.settings.artifact_rules.len() {
+                    self.settings.artifact_rules.remove(index);
+                    self.save_settings();
+                }
+                Command::none()
+            }
+            Message::CloneDirsFound(pairs) => {
+                self.detecting_clones = false;
+                self.clone_pairs = Some(pairs);
+                Command::none()
+            }
+            Message::IncludeNetworkToggled(enabled) => {
+                self.scan_config.include_network = enabled;
+                self.refresh_detected_disks();
+                Command::none()
+            }
+            Message::IncludeVirtualToggled(enabled) => {
+                self.scan_config.include_virtual_filesystems = enabled;
+                self.refresh_detected_disks();
+                Command::none()
+            }
+            Message::VirtualFilesystemsChanged(value) => {
+                self.settings.virtual_filesystems =
+                    value.split(',').map(|kind| kind.trim().to_string()).filter(|kind| !kind.is_empty()).collect();
+                self.virtual_filesystems_input = value;
+                self.save_settings();
+                self.refresh_detected_disks();
+                Command::none()
+            }
+            Message::DiskSelected(mount_point, selected) => {
+                self.settings.excluded_disks.retain(|excluded| *excluded != mount_point);
+                if !selected {
+                    self.settings.excluded_disks.push(mount_point);
+                }
+                self.save_settings();
+                Command::none()
+            }
+            Message::UseDuignoreToggled(enabled) => {
+                self.scan_config.use_duignore = enabled;
+                Command::none()
+            }
+            Message::BtrfsDedupToggled(enabled) => {
+                self.scan_config.btrfs_dedup = enabled;
+                Command::none()
+            }
+            Message::LanguageSelected(language) => {
+                self.settings.language = language;
+                i18n::set_language(language);
+                self.save_settings();
+                Command::none()
+            }
+            Message::UiScaleSelected(scale) => {
+                self.settings.ui_scale = scale;
+                self.save_settings();
+                Command::none()
+            }
+            Message::ScanProfileSelected(profile) => {
+                self.select_scan_profile(profile);
+                Command::none()
+            }
+            Message::ScanPrioritySelected(priority) => {
+                self.settings.scan_priority = priority;
+                self.scan_config.priority = priority;
+                self.save_settings();
+                Command::none()
+            }
+            Message::StorageOrderSelected(order) => {
+                self.settings.storage_order = order;
+                self.scan_config.storage_order = order;
+                self.save_settings();
+                Command::none()
+            }
+            Message::CustomMaxDepthChanged(value) => {
+                self.settings.custom_max_depth = parse_optional(&value, self.settings.custom_max_depth);
+                self.setting_inputs.insert(SettingInput::CustomMaxDepth, value);
+                self.save_settings();
+                Command::none()
+            }
+            Message::CustomMaxFilesChanged(value) => {
+                self.settings.custom_max_files = parse_optional(&value, self.settings.custom_max_files);
+                self.setting_inputs.insert(SettingInput::CustomMaxFiles, value);
+                self.save_settings();
+                Command::none()
+            }
+            Message::MaxScanDurationChanged(value) => {
+                self.settings.max_scan_duration_secs = parse_optional(&value, self.settings.max_scan_duration_secs);
+                self.setting_inputs.insert(SettingInput::MaxScanDuration, value);
+                self.save_settings();
+                Command::none()
+            }
+            Message::StoreFilesAboveChanged(value) => {
+                let typed = value.trim().replace(',', ".");
+                let mb = if typed.is_empty() { Some(0.0) } else { typed.parse::<f64>().ok() };
+                if let Some(mb) = mb.filter(|mb| mb.is_finite()) {
+                    self.settings.store_files_above_mb = mb.max(0.0);
+                    self.scan_config.store_files_above_mb = self.settings.store_files_above_mb;
+                    self.save_settings();
+                }
+                self.setting_inputs.insert(SettingInput::StoreFilesAbove, value);
+                Command::none()
+            }
+            Message::MaxFileEntriesChanged(value) => {
+                self.settings.max_file_entries = parse_optional(&value, self.settings.max_file_entries);
+                self.setting_inputs.insert(SettingInput::MaxFileEntries, value);
+                self.scan_config.max_file_entries = self.settings.max_file_entries;
+                self.save_settings();
+                Command::none()
+            }
+            Message::EntryBufferSizeChanged(value) => {
+                self.settings.entry_buffer_size = parse_optional(&value, self.settings.entry_buffer_size);
+                self.setting_inputs.insert(SettingInput::EntryBufferSize, value);
+                self.scan_config.buffer_size = self.settings.entry_buffer_size;
+                self.save_settings();
+                Command::none()
+            }
+            Message::CheckpointIntervalChanged(value) => {
+                self.settings.checkpoint_interval = parse_optional(&value, self.settings.checkpoint_interval);
+                self.setting_inputs.insert(SettingInput::CheckpointInterval, value);
+                self.scan_config.checkpoint_interval = self.settings.checkpoint_interval;
+                self.save_settings();
+                Command::none()
+            }
+            Message::ResumeCheckpoints(resume) => {
+                let checkpoints = self.resume_prompt.take().unwrap_or_default();
+                if !resume {
+                    for info in checkpoints {
+                        checkpoint::remove(Path::new(&info.root));
+                    }
+                }
+                self.scan_config.resume_checkpoints = resume;
+                self.resume_decided = true;
+                self.update(Message::Scan)
+            }
+            Message::KeptScansChanged(value) => {
+                if let Ok(count) = value.trim().parse::<usize>() {
+                    self.settings.kept_scans = count.max(1);
+                    self.show_snapshot(None);
+                    self.trim_snapshots();
+                    self.save_settings();
+                }
+                self.setting_inputs.insert(SettingInput::KeptScans, value);
+                Command::none()
+            }
+            Message::ShowOlderScan => {
+                let index = self.shown_snapshot.unwrap_or(self.snapshots.len());
+                if index > 0 {
+                    self.show_snapshot(Some(index - 1));
+                }
+                Command::none()
+            }
+            Message::ShowNewerScan => {
+                let newer = self.shown_snapshot.map(|index| index + 1).filter(|&index| index < self.snapshots.len());
+                self.show_snapshot(newer);
+                Command::none()
+            }
+            Message::IoQuotaChanged(value) => {
+                // 0 means no limit, like a blank field, not a quota that
+                // stops every disk at once.
+                self.settings.io_quota_mb = parse_optional(&value, self.settings.io_quota_mb).filter(|&mb| mb > 0);
+                self.setting_inputs.insert(SettingInput::IoQuota, value);
+                self.save_settings();
+                Command::none()
+            }
+            Message::ThrottleCpuChanged(value) => {
+                self.settings.throttle_at_cpu_pct = parse_optional(&value.replace(',', "."), self.settings.throttle_at_cpu_pct)
+                    .filter(|pct| pct.is_finite())
+                    .map(|pct| pct.clamp(1.0, 100.0));
+                self.setting_inputs.insert(SettingInput::ThrottleCpu, value);
+                self.save_settings();
+                Command::none()
+            }
+            Message::ScanThreadsChanged(value) => {
+                self.settings.scan_threads =
+                    parse_optional(&value, self.settings.scan_threads).filter(|&threads| threads > 0).map(|threads| threads.min(scan::max_threads()));
+                self.setting_inputs.insert(SettingInput::ScanThreads, value);
+                self.save_settings();
+                Command::none()
+            }
+            Message::GrowthLogToggled(enabled) => {
+                self.settings.growth_log_enabled = enabled;
+                self.save_settings();
+                Command::none()
+            }
+            Message::GrowthLogPathChanged(value) => {
+                let value = value.trim();
+                self.settings.growth_log_path = (!value.is_empty()).then(|| PathBuf::from(value));
+                self.save_settings();
+                Command::none()
+            }
+            Message::AutoExportToggled(enabled) => {
+                self.settings.auto_export_enabled = enabled;
+                self.save_settings();
+                Command::none()
+            }
+            Message::AutoExportFormatSelected(format) => {
+                self.settings.auto_export_format = format;
+                self.save_settings();
+                Command::none()
+            }
+            Message::AutoExportPathChanged(path) => {
+                self.settings.auto_export_path = path;
+                self.save_settings();
+                Command::none()
+            }
+            Message::AutoExportFilteredToggled(filtered) => {
+                self.settings.auto_export_filtered = filtered;
+                self.save_settings();
+                Command::none()
+            }
+            Message::AutoExportCompleted(result) => {
+                self.auto_exporting = false;
+                self.auto_export_status = Some(match result {
+                    Ok(path) => Ok(tr_args("auto-export-done", &[("path", path)])),
+                    Err(e) => Err(tr_args("auto-export-failed", &[("error", e)])),
+                });
+                Command::none()
+            }
+            Message::GrowthLogAppended(result) => {
+                if let Err(e) = result {
+                    self.error_message = Some(e);
+                }
+                Command::none()
+            }
+            Message::NotifyOnCompleteToggled(enabled) => {
+                self.settings.notify_on_complete = enabled;
+                self.save_settings();
+                Command::none()
+            }
+            Message::NearlyFullPercentChanged(value) => {
+                if let Ok(percent) = value.trim().parse::<u8>() {
+                    self.settings.nearly_full_percent = percent.min(100);
+                    self.save_settings();
+                }
+                self.setting_inputs.insert(SettingInput::NearlyFullPercent, value);
+                Command::none()
+            }
+            Message::NotifyNearlyFullToggled(enabled) => {
+                self.settings.notify_nearly_full = enabled;
+                self.save_settings();
+                Command::none()
+            }
+            Message::AutoRefreshMinutesChanged(value) => {
+                self.settings.auto_refresh_minutes =
+                    parse_optional(&value, self.settings.auto_refresh_minutes).filter(|&minutes| minutes > 0);
+                self.setting_inputs.insert(SettingInput::AutoRefreshMinutes, value);
+                self.save_settings();
+                Command::none()
+            }
+            // A refresh still running when the next one is due just skips it.
+            Message::AutoRefresh if !self.scanning && self.active_rescans.is_empty() => {
+                self.background_scan = true;
+                self.refresh_detected_disks();
+                let scan = self.update(Message::Scan);
+                // The scan may not have started, e.g. to ask about resuming.
+                self.background_scan = self.scanning;
+                scan
+            }
+            Message::AutoRefresh => Command::none(),
+            Message::LowSpaceThresholdChanged(disk, value) => {
+                let percent = parse_optional(&value, self.settings.low_space_thresholds.get(&disk).copied());
+                self.setting_inputs.insert(SettingInput::LowSpace(disk.clone()), value);
+                match percent {
+                    Some(percent) => {
+                        self.settings.low_space_thresholds.insert(disk, percent.min(100));
+                    }
+                    None => {
+                        self.settings.low_space_thresholds.remove(&disk);
+                    }
+                }
+                self.save_settings();
+                Command::none()
+            }
+            Message::MinimizeToTrayToggled(enabled) => {
+                self.settings.minimize_to_tray = enabled;
+                self.save_settings();
+                Command::none()
+            }
+            Message::CreateTray => {
+                self.tray = tray::Tray::new();
+                Command::none()
+            }
+            Message::TrayPoll => {
+                let show = Command::batch(vec![
+                    iced::window::change_mode(iced::window::Mode::Windowed),
+                    iced::window::gain_focus(),
+                ]);
+                match self.tray.as_ref().and_then(tray::Tray::poll) {
+                    Some(tray::TrayEvent::Show) => show,
+                    Some(tray::TrayEvent::ScanNow) => self.update(Message::Scan),
+                    // Quitting mid-scan asks first, which needs the window.
+                    Some(tray::TrayEvent::Quit) => Command::batch([show, self.update(Message::Done)]),
+                    None => Command::none(),
+                }
+            }
+            Message::JumpToDisk(disk) => {
+                // The page has no per-disk anchors, so scroll to the disk's share
+                // of it and highlight its first file.
+                let offset = if self.disks.len() > 1 { disk as f32 / (self.disks.len() - 1) as f32 } else { 0.0 };
+                Command::batch([
+                    scrollable::snap_to(main_scroll_id(), RelativeOffset { x: 0.0, y: offset }),
+                    self.focus_file_row(disk, 0),
+                ])
+            }
+        }
+    }
+
+    fn view(&self) -> Element<'_, Self::Message> {
+    // While open, the palette is all there is to click or type into.
+    if let Some(palette) = &self.palette {
+        return Container::new(Column::new().max_width(800).push(self.palette_view(palette)))
+            .padding(10)
+            .width(Length::Fill)
+            .height(Length::Fill)
+            .center_x()
+            .into();
+    }
+
+    let mut content = Column::new()
+        .spacing(10)
+        .padding(10)
+        .max_width(800);
+
+    let controls = controls_for(self.activity());
+
+    if self.confirm_quit {
+        let prompt = Column::new()
+            .spacing(10)
+            .push(Text::new(tr(if self.scanning { "quit-confirm-scan" } else { "quit-confirm-export" })).size(18))
+            .push(Row::new()
+                .spacing(10)
+                .push(Button::new(Text::new(tr("cancel"))).on_press(Message::CancelQuit))
+                .push(Button::new(Text::new(tr("quit"))).style(theme::Button::Destructive).on_press(Message::ConfirmQuit)));
+        content = content.push(
+            Container::new(prompt)
+                .padding(10)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(PanelStyle))),
+        );
+    } else if self.quitting {
+        content = content.push(Text::new(tr("quitting")));
+    }
+
+    if let Some(checkpoints) = &self.resume_prompt {
+        let oldest = checkpoints.first().map_or(0, |info| info.written_at);
+        let files: usize = checkpoints.iter().map(|info| info.files_scanned).sum();
+        let age = human_duration(util::unix_now().saturating_sub(oldest));
+        let prompt = Column::new()
+            .spacing(10)
+            .push(Text::new(tr_args("resume-checkpoint", &[("age", age), ("files", format_count(files))])).size(18))
+            .push(Row::new()
+                .spacing(10)
+                .push(Button::new(Text::new(tr("resume-scan"))).on_press(Message::ResumeCheckpoints(true)))
+                .push(Button::new(Text::new(tr("start-over"))).on_press(Message::ResumeCheckpoints(false))));
+        content = content.push(
+            Container::new(prompt)
+                .padding(10)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(PanelStyle))),
+        );
+    }
+
+    if let Some(path) = &self.confirm_trash_artifact {
+        let size_mb = self
+            .disks
+            .iter()
+            .flat_map(|disk| &disk.artifacts)
+            .find(|artifact| &artifact.path == path)
+            .map_or(0.0, |artifact| artifact.size_mb);
+        let prompt = Column::new()
+            .spacing(10)
+            .push(Text::new(tr_args("trash-artifact-confirm", &[
+                ("path", util::truncate_middle(path, MAX_PATH_CHARS)),
+                ("size", format_size_mb(size_mb)),
+            ])).size(18))
+            .push(Row::new()
+                .spacing(10)
+                .push(Button::new(Text::new(tr("cancel"))).on_press(Message::CancelTrashArtifact))
+                .push(Button::new(Text::new(tr("move-to-trash"))).style(theme::Button::Destructive).on_press(Message::ConfirmTrashArtifact)));
+        content = content.push(
+            Container::new(prompt)
+                .padding(10)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(PanelStyle))),
+        );
+    }
+
+    if self.confirm_trash_selected {
+        let paths: HashSet<String> = self.selected_local_files().into_iter().collect();
+        let size_mb: f64 = self.disks.iter().flat_map(|disk| &disk.files).filter(|file| paths.contains(&*file.path)).map(|file| file.size_mb).sum();
+        let prompt = Column::new()
+            .spacing(10)
+            .push(Text::new(tr_args("trash-selected-confirm", &[
+                ("count", format_count(paths.len())),
+                ("size", format_size_mb(size_mb)),
+            ])).size(18))
+            .push(Row::new()
+                .spacing(10)
+                .push(Button::new(Text::new(tr("cancel"))).on_press(Message::CancelTrashSelected))
+                .push(Button::new(Text::new(tr("move-to-trash"))).style(theme::Button::Destructive).on_press(Message::ConfirmTrashSelected)));
+        content = content.push(
+            Container::new(prompt)
+                .padding(10)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(PanelStyle))),
+        );
+    }
+
+    if let Some(mount_point) = &self.confirm_empty_trash {
+        let size_mb = self.trash_usage.get(mount_point).map_or(0.0, |usage| usage.size_mb);
+        let disk = self
+            .disks
+            .iter()
+            .find(|disk| disk.source.is_none() && &disk.mount_point == mount_point)
+            .map_or_else(|| mount_point.clone(), |disk| disk.display_name());
+        let prompt = Column::new()
+            .spacing(10)
+            .push(Text::new(tr_args("empty-trash-confirm", &[("disk", disk), ("size", format_size_mb(size_mb))])).size(18))
+            .push(Row::new()
+                .spacing(10)
+                .push(Button::new(Text::new(tr("cancel"))).on_press(Message::CancelEmptyTrash))
+                .push(Button::new(Text::new(tr("empty-trash"))).style(theme::Button::Destructive).on_press(Message::ConfirmEmptyTrash)));
+        content = content.push(
+            Container::new(prompt)
+                .padding(10)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(PanelStyle))),
+        );
+    }
+
+    let nearly_full = self.nearly_full_disks();
+    if !nearly_full.is_empty() {
+        let banner = nearly_full.iter().fold(Column::new().spacing(2), |banner, &index| {
+            let disk = &self.disks[index];
+            banner.push(
+                Button::new(Text::new(tr_args("nearly-full-banner", &[
+                    ("disk", disk.display_name()),
+                    ("percent", format_number(disk.used_percent(), 0)),
+                    ("free", format_gb(disk.total_space - disk.used_space)),
+                ])).size(18))
+                .style(theme::Button::Text)
+                .on_press(Message::JumpToDisk(index)),
+            )
+        });
+        content = content.push(
+            Container::new(banner)
+                .padding(5)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(BannerStyle))),
+        );
+    }
+
+    if !self.snapshots.is_empty() {
+        let count = self.snapshots.len() + 1;
+        let position = self.shown_snapshot.map_or(count, |index| index + 1);
+        let label = match self.shown_snapshot {
+            Some(_) => tr_args("snapshot-earlier", &[
+                ("date", snapshot_time(&self.disks).map(|secs| format_date_time(UNIX_EPOCH + Duration::from_secs(secs))).unwrap_or_default()),
+                ("position", position.to_string()),
+                ("count", count.to_string()),
+            ]),
+            None => tr_args("snapshot-latest", &[("count", count.to_string())]),
+        };
+        let nav = Row::new()
+            .spacing(10)
+            .push(Button::new(Text::new(tr("snapshot-older"))).on_press_maybe((position > 1).then_some(Message::ShowOlderScan)))
+            .push(Text::new(label).size(if self.shown_snapshot.is_some() { 18 } else { 14 }))
+            .push(Button::new(Text::new(tr("snapshot-newer"))).on_press_maybe(self.shown_snapshot.is_some().then_some(Message::ShowNewerScan)));
+        let mut nav = Container::new(nav).padding(5).width(Length::Fill);
+        if self.shown_snapshot.is_some() {
+            nav = nav.style(theme::Container::Custom(Box::new(BannerStyle)));
+        }
+        content = content.push(nav);
+    }
+
+    if self.disks.iter().any(|disk| disk.source.is_none() && disk.entries_capped) {
+        let entries: usize = self.disks.iter().filter(|disk| disk.source.is_none()).map(|disk| disk.files.len()).sum();
+        content = content.push(
+            Container::new(Text::new(tr_args("entries-capped", &[("count", format_count(entries))])).size(18))
+                .padding(5)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(BannerStyle))),
+        );
+    }
+
+    // The quota the scan ran with, not the setting, which may have changed since.
+    let applied_quota = self
+        .disks
+        .iter()
+        .filter(|disk| disk.source.is_none() && disk.quota_exceeded)
+        .map(|disk| disk.io_quota_bytes)
+        .max();
+    if let Some(quota) = applied_quota {
+        content = content.push(
+            Container::new(Text::new(tr_args("scan-quota-exceeded", &[
+                ("size", format_size_mb(quota.unwrap_or_default() as f64 / 1_048_576.0)),
+            ])).size(18))
+            .padding(5)
+            .width(Length::Fill)
+            .style(theme::Container::Custom(Box::new(BannerStyle))),
+        );
+    }
+
+    // Nothing scanned yet: what the app does, the disks it found, and a way in.
+    if self.disks.is_empty() && !self.scanning {
+        let any_selected = self.detected_disks.iter().any(|disk| !self.settings.excluded_disks.contains(&disk.mount_point));
+        let empty_state = Column::new()
+            .spacing(15)
+            .push(Text::new(tr("empty-state-title")).size(24))
+            .push(Text::new(tr("empty-state-explanation")))
+            .push(self.disk_selection())
+            .push(
+                Button::new(Text::new(tr("scan-now")).size(24))
+                    .padding(15)
+                    .style(theme::Button::Primary)
+                    .on_press_maybe((controls.scan && any_selected).then_some(Message::Scan)),
+            )
+            .push(Text::new(tr("drop-folder-hint")).size(18));
+        content = content.push(
+            Container::new(empty_state)
+                .padding(30)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(PanelStyle))),
+        );
+    }
+
+    if self.scanning {
+        content = content.push(self.scanning_panel());
+    } else if let Some(ref error_message) = self.error_message {
+        // Show error message if any
+        content = content.push(Text::new(error_message).style(iced::Color::from_rgb(1.0, 0.0, 0.0)));
+    }
+    if let Some(ref warning) = self.import_warning {
+        content = content.push(Text::new(warning).size(14).style(iced::Color::from_rgb(0.9, 0.4, 0.1)));
+    }
+
+    // Previous results stay visible during a scan, dimmed and read-only.
+    let mut results = Column::new().spacing(10);
+
+    // Built-in and saved filter presets, as chips above the filters
+    if self.settings.filter_presets.iter().any(|preset| !preset.hide_chip) {
+        let current = self.current_filters();
+        let chips = self.settings.filter_presets.iter().enumerate().filter(|(_, preset)| !preset.hide_chip).fold(Row::new().spacing(5), |row, (index, preset)| {
+            let style = if preset.same_filters(&current) { theme::Button::Primary } else { theme::Button::Secondary };
+            row.push(
+                Button::new(Text::new(preset.label()).size(14))
+                    .style(style)
+                    .on_press_maybe(controls.filters.then_some(Message::ApplyPreset(index))),
+            )
+        });
+        results = results.push(chips);
+    }
+
+    // File filters
+    let mut file_type_input = TextInput::new(&tr("file-type-filter-placeholder"), &self.file_type_filter).padding(5);
+    let mut file_name_input = TextInput::new(&tr("file-name-filter-placeholder"), &self.file_name_filter).padding(5);
+    let mut mime_input = TextInput::new(&tr("mime-filter-placeholder"), &self.mime_filter).padding(5);
+    let mut min_size_input = TextInput::new(&tr("min-size-filter-placeholder"), &self.min_size_filter).padding(5);
+    let mut older_than_input = TextInput::new(&tr("older-than-filter-placeholder"), &self.older_than_filter).padding(5);
+    if controls.filters {
+        file_type_input = file_type_input.on_input(Message::FileTypeFilterChanged);
+        file_name_input = file_name_input.on_input(Message::FileNameFilterChanged);
+        mime_input = mime_input.on_input(Message::MimeFilterChanged);
+        min_size_input = min_size_input.on_input(Message::MinSizeFilterChanged);
+        older_than_input = older_than_input.on_input(Message::OlderThanFilterChanged);
+    }
+    results = results
+        .push(file_type_input)
+        .push(file_name_input)
+        .push(mime_input)
+        .push(Row::new().spacing(10).push(min_size_input).push(older_than_input));
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(Text::new(tr("hidden-filter-label")))
+            .push(PickList::new(HiddenFilter::ALL, Some(self.hidden_filter), Message::HiddenFilterSelected))
+            .push(Text::new(tr("binary-filter-label")))
+            .push(PickList::new(BinaryFilter::ALL, Some(self.binary_filter), Message::BinaryFilterSelected))
+            .push(Text::new(tr("age-filter-label")))
+            .push(PickList::new(AgeFilter::ALL, Some(self.age_filter), Message::AgeFilterSelected))
+            .push(Checkbox::new(tr("mime-mismatch-only"), self.mime_mismatch_only, Message::MimeMismatchToggled)),
+    );
+    // Owners and permission bits are only recorded on Unix.
+    if cfg!(unix) {
+        results = results.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(tr("owner-filter-label")))
+                .push(PickList::new(&self.owner_choices[..], Some(self.owner_filter), Message::OwnerFilterSelected))
+                .push(Checkbox::new(tr("world-writable-only"), self.world_writable_only, Message::WorldWritableToggled))
+                .push(Checkbox::new(tr("setuid-only"), self.setuid_only, Message::SetuidToggled)),
+        );
+    }
+    let mut preset_name_input = TextInput::new(&tr("preset-name-placeholder"), &self.preset_name).padding(5);
+    if controls.filters {
+        preset_name_input = preset_name_input.on_input(Message::PresetNameChanged).on_submit(Message::SavePreset);
+    }
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(preset_name_input)
+            .push(Button::new(Text::new(tr("save-preset"))).on_press_maybe(
+                (controls.filters && !self.preset_name.trim().is_empty()).then_some(Message::SavePreset),
+            )),
+    );
+
+    results = results.push(Checkbox::new(
+        tr("inspect-archives"),
+        self.scan_config.inspect_archives,
+        Message::InspectArchivesToggled,
+    ));
+    if archives::AVAILABLE {
+        results = results.push(Checkbox::new(
+            tr("recurse-archives"),
+            self.scan_config.recurse_archives,
+            Message::RecurseArchivesToggled,
+        ));
+    }
+
+    results = results.push(Checkbox::new(
+        tr("include-network"),
+        self.scan_config.include_network,
+        Message::IncludeNetworkToggled,
+    ));
+
+    results = results.push(Text::new(tr("artifact-rules-label")));
+    for (index, rule) in self.settings.artifact_rules.iter().enumerate() {
+        results = results.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(tr_args("artifact-rule", &[
+                    ("dir", rule.dir_name.clone()),
+                    ("markers", rule.markers.join(", ")),
+                ])).width(Length::Fill))
+                .push(Button::new(Text::new(tr("remove-artifact-rule"))).style(theme::Button::Text).on_press(Message::RemoveArtifactRule(index))),
+        );
+    }
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(TextInput::new(&tr("artifact-dir-placeholder"), &self.new_artifact_dir).on_input(Message::NewArtifactDirChanged).padding(5))
+            .push(TextInput::new(&tr("artifact-markers-placeholder"), &self.new_artifact_markers)
+                .on_input(Message::NewArtifactMarkersChanged)
+                .on_submit(Message::AddArtifactRule)
+                .padding(5))
+            .push(Button::new(Text::new(tr("add-artifact-rule"))).on_press(Message::AddArtifactRule)),
+    );
+
+    results = results.push(Checkbox::new(
+        tr("include-virtual"),
+        self.scan_config.include_virtual_filesystems,
+        Message::IncludeVirtualToggled,
+    ));
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(Text::new(tr("virtual-filesystems-label")))
+            .push(TextInput::new("proc, tmpfs", &self.virtual_filesystems_input)
+                .on_input(Message::VirtualFilesystemsChanged)
+                .padding(5)),
+    );
+
+    if !self.disks.is_empty() {
+        results = results.push(self.disk_selection());
+    }
+
+    results = results.push(Checkbox::new(
+        tr("use-duignore"),
+        self.scan_config.use_duignore,
+        Message::UseDuignoreToggled,
+    ));
+    results = results.push(Checkbox::new(
+        tr("btrfs-dedup"),
+        self.scan_config.btrfs_dedup,
+        Message::BtrfsDedupToggled,
+    ));
+
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(Text::new(tr("language-label")))
+            .push(PickList::new(Language::ALL, Some(self.settings.language), Message::LanguageSelected)),
+    );
+
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(Text::new(tr("ui-scale-label")))
+            .push(PickList::new(UiScale::PRESETS, Some(self.settings.ui_scale), Message::UiScaleSelected)),
+    );
+
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(Text::new(tr("scan-profile-label")))
+            .push(PickList::new(
+                ScanProfile::ALL,
+                Some(self.settings.scan_profile),
+                Message::ScanProfileSelected,
+            )),
+    );
+
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(Text::new(tr("scan-priority-label")))
+            .push(PickList::new(ScanPriority::ALL, Some(self.settings.scan_priority), Message::ScanPrioritySelected)),
+    );
+
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(Text::new(tr("storage-order-label")))
+            .push(PickList::new(SortMode::ALL, Some(self.settings.storage_order), Message::StorageOrderSelected)),
+    );
+
+    if self.settings.scan_profile == ScanProfile::Custom {
+        let max_depth = self.setting_text(SettingInput::CustomMaxDepth, || {
+            self.settings.custom_max_depth.map(|depth| depth.to_string()).unwrap_or_default()
+        });
+        let max_files = self.setting_text(SettingInput::CustomMaxFiles, || {
+            self.settings.custom_max_files.map(|files| files.to_string()).unwrap_or_default()
+        });
+        results = results.push(
+            Row::new()
+                .spacing(10)
+                .push(TextInput::new(&tr("max-depth-placeholder"), &max_depth).on_input(Message::CustomMaxDepthChanged).padding(5))
+                .push(TextInput::new(&tr("max-files-placeholder"), &max_files).on_input(Message::CustomMaxFilesChanged).padding(5)),
+        );
+    }
+
+    let time_limit = self.setting_text(SettingInput::MaxScanDuration, || {
+        self.settings.max_scan_duration_secs.map(|secs| secs.to_string()).unwrap_or_default()
+    });
+    results = results.push(
+        TextInput::new(&tr("time-limit-placeholder"), &time_limit)
+            .on_input(Message::MaxScanDurationChanged)
+            .padding(5),
+    );
+
+    let io_quota = self.setting_text(SettingInput::IoQuota, || {
+        self.settings.io_quota_mb.map(|mb| mb.to_string()).unwrap_or_default()
+    });
+    results = results.push(
+        TextInput::new(&tr("io-quota-placeholder"), &io_quota)
+            .on_input(Message::IoQuotaChanged)
+            .padding(5),
+    );
+
+    let throttle = self.setting_text(SettingInput::ThrottleCpu, || {
+        self.settings.throttle_at_cpu_pct.map(|pct| pct.to_string()).unwrap_or_default()
+    });
+    results = results.push(
+        TextInput::new(&tr("throttle-cpu-placeholder"), &throttle)
+            .on_input(Message::ThrottleCpuChanged)
+            .padding(5),
+    );
+    let scan_threads = self.setting_text(SettingInput::ScanThreads, || {
+        self.settings.scan_threads.map(|threads| threads.to_string()).unwrap_or_default()
+    });
+    results = results.push(
+        TextInput::new(&tr_args("scan-threads-placeholder", &[("max", format_count(scan::max_threads()))]), &scan_threads)
+            .on_input(Message::ScanThreadsChanged)
+            .padding(5),
+    );
+
+    let max_entries = self.setting_text(SettingInput::MaxFileEntries, || {
+        self.settings.max_file_entries.map(|count| count.to_string()).unwrap_or_default()
+    });
+    results = results.push(
+        TextInput::new(&tr("max-file-entries-placeholder"), &max_entries)
+            .on_input(Message::MaxFileEntriesChanged)
+            .padding(5),
+    );
+    if self.settings.max_file_entries.is_some() {
+        let buffer_size = self.setting_text(SettingInput::EntryBufferSize, || {
+            self.settings.entry_buffer_size.map(|count| count.to_string()).unwrap_or_default()
+        });
+        results = results.push(
+            TextInput::new(&tr_args("entry-buffer-placeholder", &[("default", format_count(scan::DEFAULT_BUFFER_SIZE))]), &buffer_size)
+                .on_input(Message::EntryBufferSizeChanged)
+                .padding(5),
+        );
+    }
+    let checkpoint_interval = self.setting_text(SettingInput::CheckpointInterval, || {
+        self.settings.checkpoint_interval.map(|count| count.to_string()).unwrap_or_default()
+    });
+    results = results.push(
+        TextInput::new(&tr("checkpoint-interval-placeholder"), &checkpoint_interval)
+            .on_input(Message::CheckpointIntervalChanged)
+            .padding(5),
+    );
+
+    let kept_scans = self.setting_text(SettingInput::KeptScans, || self.settings.kept_scans.to_string());
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(Text::new(tr("kept-scans-label")))
+            .push(TextInput::new("3", &kept_scans)
+                .on_input(Message::KeptScansChanged)
+                .padding(5)
+                .width(Length::Fixed(60.0))),
+    );
+
+    let store_above = self.setting_text(SettingInput::StoreFilesAbove, || self.settings.store_files_above_mb.to_string());
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(Text::new(tr("store-files-above-label")))
+            .push(TextInput::new("0", &store_above)
+                .on_input(Message::StoreFilesAboveChanged)
+                .padding(5)
+                .width(Length::Fixed(60.0))),
+    );
+
+    let binary_limit = self.setting_text(SettingInput::BinaryCheckLimit, || {
+        self.settings.binary_check_max_mb.map(|mb| mb.to_string()).unwrap_or_default()
+    });
+    results = results.push(
+        TextInput::new(&tr("binary-check-placeholder"), &binary_limit)
+            .on_input(Message::BinaryCheckLimitChanged)
+            .padding(5),
+    );
+
+    let line_count_limit = self.setting_text(SettingInput::LineCountLimit, || {
+        self.settings.line_count_max_mb.map(|mb| mb.to_string()).unwrap_or_default()
+    });
+    results = results.push(
+        TextInput::new(&tr("line-count-placeholder"), &line_count_limit)
+            .on_input(Message::LineCountLimitChanged)
+            .padding(5),
+    );
+
+    let checksum_top_n = self.setting_text(SettingInput::ChecksumTopN, || {
+        if self.settings.checksum_top_n > 0 { self.settings.checksum_top_n.to_string() } else { String::new() }
+    });
+    results = results.push(
+        TextInput::new(&tr("checksum-top-n-placeholder"), &checksum_top_n)
+            .on_input(Message::ChecksumTopNChanged)
+            .padding(5),
+    );
+
+    let min_dir_size = self.setting_text(SettingInput::MinDirSize, || self.settings.min_dir_size_mb.to_string());
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(Text::new(tr("min-dir-size-label")))
+            .push(TextInput::new("1", &min_dir_size)
+                .on_input(Message::MinDirSizeChanged)
+                .padding(5)
+                .width(Length::Fixed(60.0)))
+            .push(Text::new(tr("chart-coloring-label")))
+            .push(PickList::new(dir_chart::ChartColoring::ALL, Some(self.settings.chart_coloring), Message::ChartColoringSelected)),
+    );
+    if self.settings.chart_coloring == dir_chart::ChartColoring::Age {
+        let legend = dir_chart::AgeBand::ALL.iter().fold(Row::new().spacing(15), |row, band| {
+            row.push(Text::new(format!("■ {}", band)).size(14).style(band.color()))
+        });
+        results = results.push(legend);
+    }
+
+    results = results.push(Checkbox::new(
+        tr_args("notify-on-complete", &[("seconds", self.settings.notify_threshold_secs.to_string())]),
+        self.settings.notify_on_complete,
+        Message::NotifyOnCompleteToggled,
+    ));
+
+    let nearly_full = self.setting_text(SettingInput::NearlyFullPercent, || self.settings.nearly_full_percent.to_string());
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(Text::new(tr("nearly-full-threshold")))
+            .push(TextInput::new("90", &nearly_full)
+                .on_input(Message::NearlyFullPercentChanged)
+                .padding(5)
+                .width(Length::Fixed(60.0)))
+            .push(Checkbox::new(tr("notify-nearly-full"), self.settings.notify_nearly_full, Message::NotifyNearlyFullToggled)),
+    );
+
+    let auto_refresh = self.setting_text(SettingInput::AutoRefreshMinutes, || {
+        self.settings.auto_refresh_minutes.map(|minutes| minutes.to_string()).unwrap_or_default()
+    });
+    results = results.push(
+        Row::new()
+            .spacing(10)
+            .push(Text::new(tr("auto-refresh-label")))
+            .push(TextInput::new(&tr("auto-refresh-off"), &auto_refresh)
+                .on_input(Message::AutoRefreshMinutesChanged)
+                .padding(5)
+                .width(Length::Fixed(60.0))),
+    );
+
+    results = results.push(Checkbox::new(tr("dark-mode"), self.settings.dark_mode, Message::ToggleDarkMode));
+
+    // Without a tray there is nothing to minimize to, so closing always quits.
+    if self.tray.is_some() {
+        results = results.push(Checkbox::new(
+            tr("minimize-to-tray"),
+            self.settings.minimize_to_tray,
+            Message::MinimizeToTrayToggled,
+        ));
+    }
+
+    results = results.push(Checkbox::new(
+        tr("growth-log-enabled"),
+        self.settings.growth_log_enabled,
+        Message::GrowthLogToggled,
+    ));
+    if self.settings.growth_log_enabled {
+        let growth_log_path = self.settings.growth_log_path.as_ref().map(|path| path.display().to_string()).unwrap_or_default();
+        results = results.push(
+            TextInput::new(&growth_log::default_path().display().to_string(), &growth_log_path)
+                .on_input(Message::GrowthLogPathChanged)
+                .padding(5),
+        );
+    }
+
+    if !self.settings.filter_presets.is_empty() {
+        results = results.push(Text::new(tr("filter-presets-header")));
+        for (index, preset) in self.settings.filter_presets.iter().enumerate() {
+            let mut row = Row::new()
+                .spacing(10)
+                .push(Button::new(Text::new("▲")).style(theme::Button::Text).on_press_maybe((index > 0).then_some(Message::MovePresetUp(index))))
+                .push(Checkbox::new(tr("preset-show-chip"), !preset.hide_chip, move |shown| Message::PresetChipToggled(index, shown)));
+            row = if preset.builtin {
+                row.push(Text::new(preset.label()).width(Length::Fill))
+            } else {
+                row.push(TextInput::new(&tr("preset-name-placeholder"), &preset.name)
+                        .on_input(move |name| Message::PresetRenamed(index, name))
+                        .padding(5))
+                    .push(Button::new(Text::new(tr("delete-preset"))).on_press(Message::DeletePreset(index)))
+            };
+            results = results.push(row);
+        }
+    }
+
+    let sources: BTreeSet<&str> = self.disks.iter().filter_map(|disk| disk.source.as_deref()).collect();
+    if !sources.is_empty() {
+        let total: f64 = self.disks.iter().map(|disk| disk.total_space).sum();
+        let used: f64 = self.disks.iter().map(|disk| disk.used_space).sum();
+        let has_local = self.disks.iter().any(|disk| disk.source.is_none());
+        results = results.push(Text::new(tr_args("combined-totals", &[
+            ("disks", format_count(self.disks.len())),
+            ("sources", format_count(sources.len() + usize::from(has_local))),
+            ("total", format_gb(total)),
+            ("used", format_gb(used)),
+        ])).size(18));
+        for source in sources {
+            results = results.push(Row::new()
+                .spacing(10)
+                .push(Text::new(format!("[{}]", source)).style(source_color(source)).width(Length::Fill))
+                .push(Button::new(Text::new(tr("remove-source"))).on_press(Message::RemoveSource(source.to_string()))));
+        }
+    }
+
+    for (index, disk) in self.disks.iter().enumerate() {
+        let usage_percentage = disk.used_percent();
+        let rescanning = self.active_rescans.iter().any(|root| root == Path::new(&disk.mount_point));
+        let mut header = Row::new().spacing(10);
+        if let Some(ref source) = disk.source {
+            header = header.push(Text::new(format!("[{}]", source)).style(source_color(source)));
+        }
+        header = header.push(Text::new(tr_args("disk-label", &[("name", disk.display_name())])).width(Length::Fill));
+        // Imported disks can't be rescanned from here.
+        if disk.source.is_none() {
+            let mut rescan_button = Button::new(Text::new(tr(if rescanning { "rescanning" } else { "rescan" })));
+            if !rescanning && controls.rescan {
+                rescan_button = rescan_button.on_press(Message::RescanDisk(disk.name.clone()));
+            }
+            header = header.push(rescan_button);
+        }
+        results = results.push(header);
+        if let Some(scanned_at) = disk.scanned_at {
+            results = results.push(
+                Text::new(tr_args("last-scanned", &[("age", human_duration(util::unix_now().saturating_sub(scanned_at)))]))
+                    .size(14)
+                    .style(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+            );
+        }
+        // A drive pulled mid-scan keeps its partial numbers, greyed out.
+        let figures_color = if disk.disconnected {
+            results = results.push(Text::new(tr("drive-removed")).style(iced::Color::from_rgb(0.9, 0.4, 0.1)));
+            Some(iced::Color::from_rgb(0.6, 0.6, 0.6))
+        } else {
+            None
+        };
+        let figure = |text: String| match figures_color {
+            Some(color) => Text::new(text).style(color),
+            None => Text::new(text),
+        };
+        results = results
+            .push(figure(tr_args("total-space", &[("size", format_gb(disk.total_space))])))
+            .push(figure(tr_args("used-space", &[("size", format_gb(disk.used_space))])))
+            .push(ProgressBar::new(0.0..=100.0, usage_percentage as f32).height(10));
+
+        // Per-disk low-space warning level, only used while auto-refresh runs.
+        if disk.source.is_none() && self.settings.auto_refresh_minutes.is_some() {
+            let name = disk.name.clone();
+            let threshold = self.setting_text(SettingInput::LowSpace(disk.name.clone()), || {
+                self.settings.low_space_percent(&disk.name).to_string()
+            });
+            results = results.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(tr("low-space-threshold")).size(14))
+                    .push(
+                        TextInput::new(&DEFAULT_LOW_SPACE_PERCENT.to_string(), &threshold)
+                            .on_input(move |value| Message::LowSpaceThresholdChanged(name.clone(), value))
+                            .padding(3)
+                            .size(14)
+                            .width(Length::Fixed(50.0)),
+                    ),
+            );
+        }
+
+        // Used space over the last recorded scans, once there is a trend to show.
+        if disk.source.is_none() {
+            let points = history::used_space_series(&self.scan_history, &disk.mount_point, sparkline::HISTORY_POINTS);
+            if points.len() >= 2 {
+                results = results.push(UsageSparkline::new(points, disk.total_space).view());
+            }
+        }
+
+        // Breadcrumb trail: Disk ▸ home ▸ me ▸ Videos, each step clickable.
+        let scope = self.scopes.get(index).cloned().flatten();
+        let mut breadcrumb = Row::new().spacing(5).push(
+            Button::new(Text::new(disk.name.clone()).size(14))
+                .style(theme::Button::Text)
+                .on_press(Message::SetScope(index, None)),
+        );
+        if let Some(ref scope) = scope {
+            let mut step = PathBuf::from(&disk.mount_point);
+            for component in scope.strip_prefix(&disk.mount_point).unwrap_or(scope).components() {
+                step.push(component);
+                breadcrumb = breadcrumb.push(Text::new("▸").size(14)).push(
+                    Button::new(Text::new(component.as_os_str().to_string_lossy().to_string()).size(14))
+                        .style(theme::Button::Text)
+                        .on_press(Message::SetScope(index, Some(step.clone()))),
+                );
+            }
+        }
+        let drilldown = self.drilldowns.get(index).cloned().flatten();
+        if let Some(ref extension) = drilldown {
+            breadcrumb = breadcrumb.push(Text::new("▸").size(14)).push(Text::new(report::extension_label(extension)).size(14));
+        }
+        results = results.push(breadcrumb);
+
+        if let Some(summary) = self.scope_summaries.get(index) {
+            if scope.is_some() {
+                // A scope's bar is its share of the whole disk, not of used space.
+                let fraction = if disk.total_space > 0.0 { summary.size_mb / 1024.0 / disk.total_space } else { 0.0 };
+                results = results
+                    .push(Text::new(tr_args("scope-size", &[
+                        ("size", format_size_mb(summary.size_mb)),
+                        ("percent", format_number(fraction * 100.0, 1)),
+                    ])))
+                    .push(ProgressBar::new(0.0..=1.0, fraction as f32).height(10));
+            }
+        }
+        // Largest directories in scope; clicking a bar scopes into it.
+        if let Some(chart) = self.dir_charts.get(index) {
+            if !chart.is_empty() {
+                results = results.push(chart.view());
+            }
+            if chart.small_dirs() > 0 {
+                results = results.push(
+                    Text::new(tr_args("chart-small-dirs", &[
+                        ("count", format_count(chart.small_dirs())),
+                        ("size", format_size_mb(self.settings.min_dir_size_mb)),
+                    ]))
+                    .size(12)
+                    .style(iced::Color::from_rgb(0.5, 0.5, 0.5)),
+                );
+            }
+        }
+
+        // Largest extensions in scope; clicking one drills the file list down to it.
+        match drilldown {
+            Some(_) => {
+                results = results.push(
+                    Row::new()
+                        .spacing(10)
+                        .push(Button::new(Text::new(tr("extension-back"))).on_press(Message::SetExtensionDrilldown(index, None)))
+                        .push(
+                            Button::new(Text::new(tr("export-extension-list")))
+                                .on_press_maybe((!self.exporting).then_some(Message::ExportExtensionList(index))),
+                        ),
+                );
+            }
+            None => {
+                let groups = self.extension_groups.get(index).map_or(&[][..], Vec::as_slice);
+                if !groups.is_empty() {
+                    results = results.push(Text::new(tr("extensions-header")));
+                }
+                for (extension, count, size_mb) in groups.iter().take(MAX_EXTENSIONS_SHOWN) {
+                    results = results.push(
+                        Button::new(Text::new(tr_args("extension-row", &[
+                            ("extension", report::extension_label(extension)),
+                            ("count", format_count(*count)),
+                            ("size", format_size_mb(*size_mb)),
+                        ])))
+                        .style(theme::Button::Text)
+                        .on_press(Message::SetExtensionDrilldown(index, Some(extension.clone()))),
+                    );
+                }
+                if let Some(&Some((count, size_mb))) = self.unlisted_totals.get(index).filter(|_| !groups.is_empty()) {
+                    results = results.push(
+                        Text::new(tr_args("extension-unlisted-row", &[
+                            ("count", format_count(count)),
+                            ("size", format_size_mb(size_mb)),
+                        ]))
+                        .style(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+                    );
+                }
+            }
+        }
+
+        if let Some(fill_time) = self.fill_estimates.get(&disk.mount_point) {
+            let days = fill_time
+                .duration_since(SystemTime::now())
+                .map(|remaining| remaining.as_secs() / 86_400)
+                .unwrap_or(0);
+            let estimate = Text::new(tr_args("fill-estimate", &[
+                ("days", format_count(days as usize)),
+                ("date", format_date(*fill_time)),
+            ]));
+            results = results.push(if days < 30 {
+                estimate.style(iced::Color::from_rgb(1.0, 0.55, 0.0))
+            } else {
+                estimate
+            });
+        }
+
+        if let Some(ref reason) = disk.skipped_reason {
+            results = results.push(Text::new(tr_args("skipped", &[("reason", reason.clone())])).style(iced::Color::from_rgb(0.6, 0.6, 0.6)));
+        }
+        if disk.quota_exceeded {
+            results = results.push(
+                Text::new(tr_args("scan-quota-truncated", &[("count", format_count(disk.files.len()))]))
+                    .style(iced::Color::from_rgb(1.0, 0.55, 0.0)),
+            );
+        } else if disk.truncated {
+            results = results.push(
+                Text::new(tr_args("scan-truncated", &[
+                    ("count", format_count(disk.files.len())),
+                    ("seconds", self.settings.max_scan_duration_secs.unwrap_or_default().to_string()),
+                ]))
+                .style(iced::Color::from_rgb(1.0, 0.55, 0.0)),
+            );
+        }
+        if disk.lines_of_code > 0 {
+            results = results.push(Text::new(tr_args("lines-of-code", &[("count", format_count(disk.lines_of_code as usize))])));
+        }
+        if !disk.unstored.is_empty() {
+            let unstored_mb: f64 = disk.unstored.values().map(|dir| dir.size_mb).sum();
+            results = results.push(
+                Text::new(tr_args("unstored-files", &[
+                    ("count", format_count(disk.total_files() - disk.files.len())),
+                    ("total", format_count(disk.total_files())),
+                    ("size", format_size_mb(unstored_mb)),
+                ]))
+                .style(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            );
+        }
+        if let Some(usage) = self.trash_usage.get(&disk.mount_point).filter(|_| disk.source.is_none()) {
+            let key = if usage.partial { "trash-size-partial" } else { "trash-size" };
+            let mut line = Row::new().spacing(10).push(Text::new(tr_args(key, &[
+                ("size", format_size_mb(usage.size_mb)),
+                ("count", format_count(usage.files)),
+            ])));
+            if trash::CAN_EMPTY {
+                line = line.push(
+                    Button::new(Text::new(tr(if self.emptying_trash { "emptying-trash" } else { "empty-trash" })))
+                        .style(theme::Button::Text)
+                        .on_press_maybe((!self.emptying_trash && !self.scanning).then(|| Message::EmptyTrash(disk.mount_point.clone()))),
+                );
+            }
+            results = results.push(line);
+        }
+        if let Some(ratio) = disk.dedup_ratio {
+            results = results.push(Text::new(tr_args("dedup-ratio", &[
+                ("ratio", format_number(ratio, 2)),
+                ("saving", format_gb(disk.used_space * (ratio - 1.0))),
+            ])));
+        }
+        if disk.skipped_placeholders > 0 {
+            results = results.push(
+                Text::new(tr_args("skipped-placeholders", &[("count", format_count(disk.skipped_placeholders))]))
+                    .style(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            );
+        }
+        if !disk.skipped_mounts.is_empty() {
+            results = results.push(
+                Text::new(tr_args("skipped-mounts", &[("count", format_count(disk.skipped_mounts.len()))]))
+                    .style(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            );
+            for mount in disk.skipped_mounts.iter().take(MAX_SKIPPED_MOUNTS_SHOWN) {
+                results = results.push(
+                    Text::new(util::truncate_middle(mount, MAX_PATH_CHARS))
+                        .size(14)
+                        .style(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+                );
+            }
+        }
+        if !disk.ignore_patterns.is_empty() {
+            results = results.push(
+                Text::new(tr_args("duignore-patterns", &[("count", format_count(disk.ignore_patterns.len()))]))
+                    .style(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            );
+            for ignore in disk.ignore_patterns.iter().take(MAX_IGNORE_PATTERNS_SHOWN) {
+                results = results.push(
+                    Text::new(format!("{}: {}", util::truncate_middle(&ignore.dir, MAX_PATH_CHARS), ignore.pattern))
+                        .size(14)
+                        .style(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+                );
+            }
+        }
+
+        let (temp_count, temp_mb) = disk
+            .files
+            .iter()
+            .filter(|file| file.is_temp)
+            .fold((0, 0.0), |(count, size), file| (count + 1, size + file.size_mb));
+        if temp_count > 0 {
+            results = results.push(Text::new(tr_args("temp-files-summary", &[
+                ("count", format_count(temp_count)),
+                ("size", format_size_mb(temp_mb)),
+            ])));
+        }
+
+        let (hidden_count, hidden_mb) = disk
+            .files
+            .iter()
+            .filter(|file| file.is_hidden)
+            .fold((0, 0.0), |(count, size), file| (count + 1, size + file.size_mb));
+        if hidden_count > 0 {
+            results = results.push(Text::new(tr_args("hidden-files-summary", &[
+                ("count", format_count(hidden_count)),
+                ("size", format_size_mb(hidden_mb)),
+            ])));
+        }
+
+        if !disk.hardlink_groups.is_empty() {
+            let entries: usize = disk.hardlink_groups.iter().map(|group| group.paths.len()).sum();
+            let duplicated_mb: f64 = disk.hardlink_groups.iter().map(HardlinkGroup::duplicated_mb).sum();
+            results = results.push(Text::new(tr_args("hardlinks-summary", &[
+                ("entries", format_count(entries)),
+                ("inodes", format_count(disk.hardlink_groups.len())),
+                ("size", format_size_mb(duplicated_mb)),
+            ])));
+            for group in disk.hardlink_groups.iter().take(MAX_HARDLINK_GROUPS_SHOWN) {
+                results = results.push(Text::new(tr_args("hardlink-group", &[
+                    ("count", format_count(group.paths.len())),
+                    ("size", format_size_mb(group.size_mb)),
+                    ("paths", group.paths.iter().map(|path| util::truncate_middle(path, MAX_PATH_CHARS)).collect::<Vec<_>>().join(", ")),
+                ])).size(14));
+            }
+        }
+
+        if disk.archive_overhead_mb > 0.0 {
+            results = results.push(Text::new(tr_args("archive-savings", &[("size", format_size_mb(disk.archive_overhead_mb))])));
+        }
+
+        // Where files sit in the tree, to help choose a scan depth.
+        let largest = disk.depth_histogram.iter().copied().max().unwrap_or(0);
+        if largest > 0 {
+            results = results.push(Text::new(tr("depth-histogram-header")));
+            for (depth, &count) in disk.depth_histogram.iter().enumerate().filter(|(_, &count)| count > 0) {
+                results = results.push(
+                    Row::new()
+                        .spacing(10)
+                        .push(Text::new(tr_args("depth-row", &[("depth", depth.to_string()), ("count", format_count(count))])).size(14).width(Length::Fixed(220.0)))
+                        .push(ProgressBar::new(0.0..=largest as f32, count as f32).height(8)),
+                );
+            }
+        }
+
+        // Many small files or a few large ones.
+        let largest = disk.size_histogram.iter().copied().max().unwrap_or(0);
+        if largest > 0 {
+            results = results.push(Text::new(tr("size-histogram-header")));
+            for (range, &count) in scan::SizeRange::ALL.iter().zip(&disk.size_histogram) {
+                results = results.push(
+                    Row::new()
+                        .spacing(10)
+                        .push(Text::new(tr_args("size-histogram-row", &[("range", range.to_string()), ("count", format_count(count))])).size(14).width(Length::Fixed(220.0)))
+                        .push(ProgressBar::new(0.0..=largest as f32, count as f32).height(8)),
+                );
+            }
+        }
+
+        
+        let direction = if self.settings.sort_descending { "sort-descending" } else { "sort-ascending" };
+        results = results.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(tr("sort-label")))
+                .push(PickList::new(SortKey::ALL, Some(self.settings.sort_key), Message::SortKeySelected))
+                .push(Button::new(Text::new(tr(direction))).on_press(Message::ToggleSortDirection)),
+        );
+
+        let legend = classify::FileCategory::ALL.iter().fold(Row::new().spacing(15), |row, category| {
+            row.push(Text::new(format!("■ {}", category)).size(14).style(classify::file_type_color(category)))
+        });
+        results = results.push(legend);
+
+        // Each disk scrolls its own list so later disks stay reachable.
+        let basis = match self.share_basis(index) {
+            ShareBasis::Disk => "share-of-disk",
+            ShareBasis::Scope => "share-of-folder",
+            ShareBasis::Filtered => "share-of-filtered",
+        };
+        results = results.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(tr("file-column")).size(14).width(Length::Fill))
+                .push(Text::new(tr(basis)).size(14).width(Length::Fixed(SHARE_COLUMN_WIDTH)))
+                .push(Text::new(tr("cumulative-column")).size(14).width(Length::Fixed(SHARE_COLUMN_WIDTH))),
+        );
+        let shares = self.visible_shares.get(index);
+        let mut file_list = Column::new();
+        let now = util::unix_now();
+        for (row_index, file) in self.visible_files(index).into_iter().enumerate() {
+            let share = shares.and_then(|shares| shares.get(row_index)).copied().unwrap_or_default();
+            file_list = file_list.push(self.file_row(file, share, row_index, self.focused_file == Some((index, row_index)), now));
+        }
+        results = results.push(
+            Scrollable::new(file_list)
+                .id(disk_scroll_id(index))
+                .height(Length::Fixed(300.0))
+                .width(Length::Fill)
+                .on_scroll(move |viewport| Message::DiskScrolled(index, viewport.relative_offset())),
+        );
+
+        let shown = self.shown_rows(index);
+        let total = self.visible_files.get(index).map_or(0, Vec::len);
+        if total > INITIAL_FILE_ROWS {
+            let mut more = Button::new(Text::new(tr_args("show-more-files", &[("count", format_count(MORE_FILE_ROWS.min(total - shown)))])));
+            if shown < total {
+                more = more.on_press(Message::ShowMoreFiles(disk.display_name()));
+            }
+            let mut collapse = Button::new(Text::new(tr("collapse-files"))).style(theme::Button::Text);
+            if shown > INITIAL_FILE_ROWS {
+                collapse = collapse.on_press(Message::CollapseFiles(disk.display_name()));
+            }
+            results = results.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(tr_args("files-shown", &[("shown", format_count(shown)), ("total", format_count(total))])).width(Length::Fill))
+                    .push(more)
+                    .push(collapse),
+            );
+        }
+
+        if !disk.most_files.is_empty() {
+            let expanded = self.expanded_most_files.contains(&disk.display_name());
+            results = results.push(
+                Button::new(Text::new(tr_args("most-files-summary", &[
+                    ("arrow", (if expanded { "▾" } else { "▸" }).to_string()),
+                ])))
+                .style(theme::Button::Text)
+                .on_press(Message::ToggleMostFiles(disk.display_name())),
+            );
+            if expanded {
+                results = results.push(self.most_files_panel(index, disk));
+            }
+        }
+
+        if !disk.symlinks.is_empty() {
+            let expanded = self.expanded_symlinks.contains(&disk.display_name());
+            let broken = disk.symlinks.iter().filter(|link| link.status != LinkStatus::Ok).count();
+            results = results.push(
+                Button::new(Text::new(tr_args("symlinks-summary", &[
+                    ("arrow", (if expanded { "▾" } else { "▸" }).to_string()),
+                    ("count", format_count(disk.symlinks.len())),
+                    ("broken", format_count(broken)),
+                ])))
+                .style(theme::Button::Text)
+                .on_press(Message::ToggleSymlinks(disk.display_name())),
+            );
+
+            if expanded {
+                for link in disk.symlinks.iter().take(MAX_SYMLINKS_SHOWN) {
+                    results = results.push(symlink_row(link));
+                }
+                if disk.symlinks.len() > MAX_SYMLINKS_SHOWN {
+                    results = results.push(Text::new(tr_args("symlinks-more", &[
+                        ("count", format_count(disk.symlinks.len() - MAX_SYMLINKS_SHOWN)),
+                    ])));
+                }
+            }
+        }
+
+        if !disk.archive_members.is_empty() {
+            let expanded = self.expanded_archives.contains(&disk.display_name());
+            results = results.push(
+                Button::new(Text::new(tr_args("archive-members-summary", &[
+                    ("arrow", (if expanded { "▾" } else { "▸" }).to_string()),
+                    ("count", format_count(disk.archive_members.len())),
+                ])))
+                .style(theme::Button::Text)
+                .on_press(Message::ToggleArchiveMembers(disk.display_name())),
+            );
+            if expanded {
+                for member in disk.archive_members.iter().take(MAX_ARCHIVE_MEMBERS_SHOWN) {
+                    results = results.push(
+                        Row::new()
+                            .spacing(10)
+                            .push(Text::new(util::truncate_middle(&member.path, MAX_PATH_CHARS)).width(Length::Fill))
+                            .push(Text::new(format_size_mb(member.size_mb))),
+                    );
+                }
+                if disk.archive_members.len() > MAX_ARCHIVE_MEMBERS_SHOWN {
+                    results = results.push(Text::new(tr_args("archive-members-more", &[
+                        ("count", format_count(disk.archive_members.len() - MAX_ARCHIVE_MEMBERS_SHOWN)),
+                    ])));
+                }
+            }
+        }
+    }
+
+    if !self.no_capacity_disks.is_empty() {
+        results = results.push(Text::new(tr("no-capacity-heading")).size(18));
+        for (name, mount_point) in &self.no_capacity_disks {
+            results = results.push(Text::new(format!("{} — {}", name, mount_point)).style(iced::Color::from_rgb(0.6, 0.6, 0.6)));
+        }
+    }
+
+    if !self.disks.is_empty() {
+        let selected_mb: f64 = self
+            .disks
+            .iter()
+            .flat_map(|disk| &disk.files)
+            .filter(|file| self.selected_files.contains(&*file.path))
+            .map(|file| file.size_mb)
+            .sum();
+        let mut select_temp = Button::new(Text::new(tr("select-all-temp")));
+        let mut clear_selection = Button::new(Text::new(tr("clear-selection")));
+        let mut copy_paths = Button::new(Text::new(tr("copy-selected-paths")));
+        let mut reveal = Button::new(Text::new(tr("reveal-in-folder")));
+        let mut trash_selected = Button::new(Text::new(tr(if self.trashing_selected { "trashing-selected" } else { "move-to-trash" })))
+            .style(theme::Button::Destructive);
+        if controls.filters {
+            select_temp = select_temp.on_press(Message::SelectAllTempFiles);
+            if !self.selected_files.is_empty() {
+                clear_selection = clear_selection.on_press(Message::ClearSelection);
+                copy_paths = copy_paths.on_press(Message::CopySelectedPaths);
+            }
+            // Showing one file's folder is clear; showing dozens is not.
+            if let (1, Some(path)) = (self.selected_files.len(), self.selected_files.iter().next()) {
+                reveal = reveal.on_press(Message::RevealInFolder(path.clone()));
+            }
+            if trash::CAN_MOVE && !self.trashing_selected && !self.selected_local_files().is_empty() {
+                trash_selected = trash_selected.on_press(Message::TrashSelected);
+            }
+        }
+        results = results
+            .push(Text::new(tr_args("selected-summary", &[
+                ("count", format_count(self.selected_files.len())),
+                ("size", format_size_mb(selected_mb)),
+            ])))
+            .push(Row::new()
+                .spacing(10)
+                .push(select_temp.width(Length::Fill))
+                .push(clear_selection.width(Length::Fill)))
+            .push(Row::new()
+                .spacing(10)
+                .push(copy_paths.width(Length::Fill))
+                .push(reveal.width(Length::Fill))
+                .push(trash_selected.width(Length::Fill)));
+    }
+
+    let mut projects: Vec<&ProjectRoot> = self.disks.iter().flat_map(|disk| &disk.projects).collect();
+    if !projects.is_empty() {
+        projects.sort_by(|a, b| b.total_size_mb.partial_cmp(&a.total_size_mb).unwrap_or(std::cmp::Ordering::Equal));
+        let mut panel = Column::new()
+            .spacing(5)
+            .push(Text::new(tr_args("projects-header", &[("count", format_count(projects.len()))])).size(18));
+        for project in projects.iter().take(MAX_PROJECTS_SHOWN) {
+            panel = panel.push(Text::new(tr_args("project-row", &[
+                ("path", util::truncate_middle(&project.path, MAX_PATH_CHARS)),
+                ("type", project.project_type.clone()),
+                ("size", format_size_mb(project.total_size_mb)),
+            ])));
+        }
+        results = results.push(
+            Container::new(panel)
+                .padding(10)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(PanelStyle))),
+        );
+    }
+
+    let artifacts: Vec<&scan::ArtifactDir> =
+        self.disks.iter().filter(|disk| disk.source.is_none()).flat_map(|disk| &disk.artifacts).collect();
+    if !artifacts.is_empty() {
+        results = results.push(self.artifacts_panel(&artifacts));
+    }
+
+    if let Some(ref pairs) = self.clone_pairs {
+        let mut panel = Column::new()
+            .spacing(5)
+            .push(Text::new(tr_args("clone-dirs-summary", &[("count", format_count(pairs.len()))])).size(18));
+        for pair in pairs.iter().take(50) {
+            panel = panel.push(Text::new(tr_args("clone-dirs-pair", &[
+                ("left", util::truncate_middle(&pair.left, MAX_PATH_CHARS)),
+                ("right", util::truncate_middle(&pair.right, MAX_PATH_CHARS)),
+                ("percent", format_number(pair.similarity * 100.0, 0)),
+                ("files", format_count(pair.shared_files)),
+                ("size", format_size_mb(pair.shared_mb)),
+            ])));
+        }
+        results = results.push(
+            Container::new(panel)
+                .padding(10)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(PanelStyle))),
+        );
+    }
+
+    if let Some(ref treemap) = self.all_disks_treemap {
+        let mut panel = Column::new().spacing(5).push(Text::new(tr("all-disks-treemap-header")).size(18));
+        panel = panel.push(if treemap.is_empty() { Element::from(Text::new(tr("all-disks-treemap-empty"))) } else { treemap.view() });
+        results = results.push(
+            Container::new(panel)
+                .padding(10)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(PanelStyle))),
+        );
+    }
+
+    if let Some(ref groups) = self.same_name_groups {
+        let mut panel = Column::new()
+            .spacing(5)
+            .push(Text::new(tr_args("same-name-summary", &[("count", format_count(groups.len()))])).size(18));
+        for group in groups.iter().take(MAX_SAME_NAME_GROUPS_SHOWN) {
+            // Wildly different sizes are most likely unrelated files.
+            let color = (!group.similar_sizes).then_some(iced::Color::from_rgb(0.6, 0.6, 0.6));
+            let mut header = Text::new(tr_args("same-name-group", &[
+                ("name", group.name.clone()),
+                ("count", format_count(group.files.len())),
+                ("size", format_size_mb(group.wasted_mb)),
+            ]));
+            if let Some(color) = color {
+                header = header.style(color);
+            }
+            panel = panel.push(header);
+            for (path, size_mb) in &group.files {
+                let mut location = Text::new(format!("{}  {}", util::truncate_middle(path, MAX_PATH_CHARS), format_size_mb(*size_mb))).size(14);
+                if let Some(color) = color {
+                    location = location.style(color);
+                }
+                panel = panel.push(Row::new()
+                    .spacing(10)
+                    .push(location.width(Length::Fill))
+                    .push(Button::new(Text::new(tr("reveal-in-folder")).size(14))
+                        .style(theme::Button::Text)
+                        .on_press(Message::RevealInFolder(path.clone()))));
+            }
+        }
+        if groups.len() > MAX_SAME_NAME_GROUPS_SHOWN {
+            panel = panel.push(Text::new(tr_args("same-name-more", &[("count", format_count(groups.len() - MAX_SAME_NAME_GROUPS_SHOWN))])));
+        }
+        results = results.push(
+            Container::new(panel)
+                .padding(10)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(PanelStyle))),
+        );
+    }
+
+    if let Some(ref report) = self.growth_report {
+        results = results.push(
+            Container::new(self.growth_panel(report))
+                .padding(10)
+                .width(Length::Fill)
+                .style(theme::Container::Custom(Box::new(PanelStyle))),
+        );
+    }
+
+    let results = Container::new(results).width(Length::Fill);
+    content = content.push(if self.scanning && !self.background_scan {
+        results.style(theme::Container::Custom(Box::new(DimmedStyle)))
+    } else {
+        results
+    });
+
+    if let Some(duration) = self.scan_duration {
+        content = content.push(Text::new(tr_args("scan-duration", &[("seconds", format_number(duration, 2))])));
+        if self.scan_runs.len() > 1 {
+            let runs = self.scan_runs[self.scan_runs.len().saturating_sub(sparkline::PERF_POINTS)..].to_vec();
+            content = content
+                .push(Text::new(tr_args("scan-perf-header", &[("count", format_count(runs.len()))])).size(14))
+                .push(ScanPerfChart::new(runs).view());
+        }
+        let threads = self.scan_progress.threads.load(Ordering::Relaxed);
+        if threads > 0 {
+            content = content.push(Text::new(tr_args("scan-threads-used", &[("count", format_count(threads))])));
+        }
+        let retries = self.scan_progress.retry_count.load(Ordering::Relaxed);
+        if retries > 0 {
+            content = content.push(Text::new(tr_args("scan-retries", &[("count", format_count(retries))])));
+        }
+        if let Some(seconds) = throttled_seconds(&self.scan_progress) {
+            content = content.push(Text::new(tr_args("scan-throttled", &[("seconds", format_number(seconds, 1))])));
+        }
+        let saved = self.scan_progress.path_memory_saved.load(Ordering::Relaxed);
+        if saved > 0 {
+            content = content.push(Text::new(tr_args("path-memory-saved", &[("size", format_size_mb(saved as f64 / 1_048_576.0))])));
+        }
+    }
+
+    match &self.auto_export_status {
+        Some(Ok(status)) => content = content.push(Text::new(status.clone()).size(14)),
+        Some(Err(status)) => {
+            content = content.push(Text::new(status.clone()).size(14).style(iced::Color::from_rgb(0.9, 0.2, 0.2)));
+        }
+        None => {}
+    }
+
+    let mut status_bar = Row::new()
+        .spacing(20)
+        .push(Text::new(tr_args("scans-performed", &[("count", format_count(self.scan_count.load(Ordering::SeqCst)))])));
+    if let Some(bytes) = self.app_memory {
+        status_bar = status_bar.push(Text::new(tr_args("app-memory", &[("size", format_size_mb(bytes as f64 / 1_048_576.0))])));
+    }
+    if let Some(progress) = &self.export_progress {
+        let status = if progress.total_rows > 0 {
+            tr_args("export-progress", &[
+                ("done", format_count(progress.rows_written())),
+                ("total", format_count(progress.total_rows)),
+            ])
+        } else {
+            tr("exporting")
+        };
+        status_bar = status_bar.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(status))
+                .push(
+                    Button::new(Text::new(tr("cancel")))
+                        .style(theme::Button::Text)
+                        .on_press_maybe((!progress.is_cancelled()).then_some(Message::CancelExport)),
+                ),
+        );
+    }
+    content = content.push(status_bar);
+
+        
+    content = content
+        .push(Row::new()
+            .spacing(10)
+            .push(Button::new(Text::new(tr("scan-disk"))).on_press_maybe(controls.scan.then_some(Message::Scan)).width(Length::Fill))
+            .push(Button::new(Text::new(tr("quick-scan"))).on_press_maybe(controls.scan.then_some(Message::QuickScan)).width(Length::Fill))
+            .push(Button::new(Text::new(tr("deep-scan"))).on_press_maybe(controls.scan.then_some(Message::DeepScan)).width(Length::Fill)));
+
+    content = content.push(
+        Container::new(
+            Button::new(Text::new(tr("refresh")))
+                .on_press_maybe(controls.refresh.then_some(Message::Refresh)),
+        )
+    );
+
+    content = content.push(
+        Button::new(Text::new(tr(if self.detecting_clones { "detecting-clone-dirs" } else { "detect-clone-dirs" })))
+            .on_press_maybe((controls.scan && !self.detecting_clones && !self.disks.is_empty()).then_some(Message::DetectCloneDirs)),
+    );
+
+    content = content.push(
+        Button::new(Text::new(tr(if self.all_disks_treemap.is_some() { "hide-all-disks-treemap" } else { "show-all-disks-treemap" })))
+            .on_press_maybe((!self.disks.is_empty()).then_some(Message::ToggleAllDisksTreemap)),
+    );
+
+    content = content.push(
+        Button::new(Text::new(tr(if self.finding_same_names {
+            "finding-same-name"
+        } else if self.same_name_groups.is_some() {
+            "hide-same-name"
+        } else {
+            "show-same-name"
+        })))
+            .on_press_maybe((!self.disks.is_empty() && !self.finding_same_names).then_some(Message::ToggleSameNamePanel)),
+    );
+
+    content = content.push(
+        Button::new(Text::new(tr(if self.growth_report.is_some() { "hide-what-grew" } else { "what-grew" })))
+            .on_press_maybe((!self.disks.is_empty()).then_some(Message::ToggleGrowthPanel)),
+    );
+
+    content = content.push(Row::new()
+        .spacing(10)
+        .push(PickList::new(ExportFormat::ALL, Some(self.settings.export_format), Message::ExportFormatSelected))
+        .push(Button::new(Text::new(tr("export"))).on_press_maybe(controls.export.then_some(Message::Export)).width(Length::Fill))
+        .push(Button::new(Text::new(tr("import-json"))).on_press_maybe(controls.scan.then_some(Message::ImportJson)).width(Length::Fill))
+    );
+    content = content.push(Row::new()
+        .spacing(10)
+        .push(Button::new(Text::new(AnalysisReport::Duplicates.to_string()))
+            .on_press_maybe(controls.export.then_some(Message::ExportAnalysis(AnalysisReport::Duplicates)))
+            .width(Length::Fill))
+        .push(Button::new(Text::new(AnalysisReport::Reclaimable.to_string()))
+            .on_press_maybe(controls.export.then_some(Message::ExportAnalysis(AnalysisReport::Reclaimable)))
+            .width(Length::Fill)));
+    if sqlite::AVAILABLE {
+        content = content.push(
+            Button::new(Text::new(tr("export-sqlite"))).on_press_maybe(controls.export.then_some(Message::ExportAsSqlite)),
+        );
+    }
+
+    content = content.push(
+        Button::new(Text::new(tr("copy-markdown-summary")))
+            .on_press_maybe((!self.disks.is_empty()).then_some(Message::CopyMarkdownSummary)),
+    );
+
+    content = content.push(Checkbox::new(tr("export-per-disk"), self.export_per_disk, Message::ExportPerDiskToggled));
+    content = content.push(Checkbox::new(tr("compress-exports"), self.settings.compress_exports, Message::CompressExportsToggled));
+
+    content = content.push(Checkbox::new(tr("auto-export-enabled"), self.settings.auto_export_enabled, Message::AutoExportToggled));
+    if self.settings.auto_export_enabled {
+        content = content
+            .push(Row::new()
+                .spacing(10)
+                .push(PickList::new(ExportFormat::ALL, Some(self.settings.auto_export_format), Message::AutoExportFormatSelected))
+                .push(TextInput::new(&tr("auto-export-path-placeholder"), &self.settings.auto_export_path)
+                    .on_input(Message::AutoExportPathChanged)
+                    .padding(5)))
+            .push(Checkbox::new(tr("auto-export-filtered"), self.settings.auto_export_filtered, Message::AutoExportFilteredToggled));
+    }
+
+    if let Some(ref checksum) = self.last_export_checksum {
+        content = content.push(Text::new(tr_args("export-checksum", &[("checksum", checksum.clone())])).size(14));
+    }
+
+    if !self.per_disk_export_report.is_empty() {
+        let written = self.per_disk_export_report.iter().filter(|(_, result)| result.is_ok()).count();
+        content = content.push(Text::new(tr_args("export-per-disk-summary", &[
+            ("written", format_count(written)),
+            ("total", format_count(self.per_disk_export_report.len())),
+        ])));
+        for (file_name, result) in &self.per_disk_export_report {
+            content = content.push(match result {
+                Ok(_) => Text::new(tr_args("export-file-written", &[("file", file_name.clone())])).size(14),
+                Err(e) => Text::new(tr_args("export-file-failed", &[("file", file_name.clone()), ("error", e.clone())]))
+                    .size(14)
+                    .style(iced::Color::from_rgb(0.9, 0.2, 0.2)),
+            });
+        }
+    }
+
+    
+    content = content.push(Space::with_height(Length::Fill));
+
+    let scrollable_content = Scrollable::new(content)
+        .id(main_scroll_id())
+        .height(Length::Fill)
+        .width(Length::Fill);
+
+    let mut final_layout = Column::new().spacing(10);
+    if !self.disks.is_empty() {
+        final_layout = final_layout.push(self.summary_card());
+    }
+    let final_layout = final_layout
+        .push(scrollable_content) 
+        .push(Button::new(Text::new(tr("done"))).on_press(Message::Done).width(Length::Shrink)); // "Done" button at the bottom
+
+    
+    Container::new(final_layout)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x()
+        .center_y()
+        .into()
+}
+    
+
+    fn theme(&self) -> iced::Theme {
+        if self.settings.dark_mode { iced::Theme::Dark } else { iced::Theme::Light }
+    }
+
+    fn scale_factor(&self) -> f64 {
+        self.settings.ui_scale.factor()
+    }
+
+    fn subscription(&self) -> Subscription<Self::Message> {
+        let mut subscriptions = vec![
+            iced::time::every(Duration::from_millis(500)).map(|_| Message::CheckFocusRequest),
+            iced::subscription::events_with(|event, status| match event {
+                iced::Event::Window(iced::window::Event::Resized { width, height }) => {
+                    Some(Message::WindowResized(width, height))
+                }
+                iced::Event::Window(iced::window::Event::Moved { x, y }) => Some(Message::WindowMoved(x, y)),
+                iced::Event::Window(iced::window::Event::CloseRequested) => Some(Message::CloseRequested),
+                // Several dropped paths arrive as one event each.
+                iced::Event::Window(iced::window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
+                // These work even while typing in a text input.
+                iced::Event::Keyboard(keyboard::Event::KeyPressed { key_code: keyboard::KeyCode::P, modifiers })
+                    if modifiers.command() =>
+                {
+                    Some(Message::OpenPalette)
+                }
+                iced::Event::Keyboard(keyboard::Event::KeyPressed { key_code: keyboard::KeyCode::Escape, .. }) => {
+                    Some(Message::ClearFocus)
+                }
+                // Keys a focused text input already handled are left alone.
+                iced::Event::Keyboard(keyboard::Event::KeyPressed { key_code, modifiers })
+                    if status == iced::event::Status::Ignored =>
+                {
+                    key_message(key_code, modifiers)
+                }
+                _ => None,
+            }),
+        ];
+        if self.scanning {
+            subscriptions.push(iced::time::every(Duration::from_secs(1)).map(|_| Message::Tick));
+        }
+        subscriptions.push(iced::time::every(APP_MEMORY_INTERVAL).map(|_| Message::SampleAppMemory));
+        if self.export_progress.is_some() {
+            subscriptions.push(iced::time::every(EXPORT_PROGRESS_INTERVAL).map(|_| Message::ExportTick));
+        }
+        if self.quitting {
+            subscriptions.push(iced::time::every(Duration::from_millis(200)).map(|_| Message::QuitWhenIdle));
+        }
+        if self.tray.is_some() {
+            subscriptions.push(iced::time::every(Duration::from_millis(250)).map(|_| Message::TrayPoll));
+        }
+        // Resizes and moves arrive in bursts while dragging; write them out at
+        // most every couple of seconds rather than on every event.
+        if self.window_state_dirty {
+            subscriptions.push(iced::time::every(Duration::from_secs(2)).map(|_| Message::SaveWindowState));
+        }
+        if let Some(minutes) = self.settings.auto_refresh_minutes {
+            subscriptions.push(iced::time::every(Duration::from_secs(minutes.saturating_mul(60))).map(|_| Message::AutoRefresh));
+        }
+        Subscription::batch(subscriptions)
+    }
+}
+
+const INITIAL_FILE_ROWS: usize = 10;
+const PALETTE_RESULTS: usize = 10;
+const MORE_FILE_ROWS: usize = 50;
+// Long paths are middle-truncated so the file name stays visible on one line.
+const MAX_PATH_CHARS: usize = 80;
+const MAX_PROJECTS_SHOWN: usize = 30;
+const MAX_ARTIFACT_PROJECTS_SHOWN: usize = 30;
+const MAX_HARDLINK_GROUPS_SHOWN: usize = 5;
+const MAX_IGNORE_PATTERNS_SHOWN: usize = 10;
+/// Left-out mount points listed under a disk's results.
+const MAX_SKIPPED_MOUNTS_SHOWN: usize = 10;
+const MAX_FREED_DIRS_SHOWN: usize = 50;
+const MAX_SYMLINKS_SHOWN: usize = 50;
+const MAX_ARCHIVE_MEMBERS_SHOWN: usize = 50;
+/// Rows of a disk's "Most files" list.
+const MOST_FILES_SHOWN: usize = 20;
+const MAX_SAME_NAME_GROUPS_SHOWN: usize = 50;
+const MAX_EXTENSIONS_SHOWN: usize = 10;
+const LOW_SPACE_NOTIFY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// A directory shown this long in the scanning panel is flagged as slow.
+const SLOW_DIRECTORY_SECS: u64 = 10;
+// How long a cancelled scan may take to wind down before the UI stops
+// waiting for it.
+const STOP_TIMEOUT: Duration = Duration::from_secs(15);
+const ACTIVE_PATHS_SHOWN: usize = 5;
+// Reading the process table is cheap but not free; a few seconds is enough
+// to see memory climbing during a scan.
+const APP_MEMORY_INTERVAL: Duration = Duration::from_secs(3);
+const SHARE_COLUMN_WIDTH: f32 = 90.0;
+const AGE_BADGE_WIDTH: f32 = 70.0;
+
+fn palette_input_id() -> text_input::Id {
+    text_input::Id::new("palette")
+}
+
+fn main_scroll_id() -> scrollable::Id {
+    scrollable::Id::new("main")
+}
+
+fn disk_scroll_id(index: usize) -> scrollable::Id {
+    scrollable::Id::new(format!("disk-files-{}", index))
+}
+
+const SPINNER_FRAMES: [&str; 4] = ["|", "/", "-", "\\"];
+
+struct DimmedStyle;
+
+impl container::StyleSheet for DimmedStyle {
+    type Style = iced::Theme;
+
+    fn appearance(&self, _style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            text_color: Some(iced::Color::from_rgb(0.6, 0.6, 0.6)),
+            ..Default::default()
+        }
+    }
+}
+
+/// A stable color per imported source, so its disks are easy to tell apart.
+fn source_color(source: &str) -> iced::Color {
+    const PALETTE: [(f32, f32, f32); 6] = [
+        (0.2, 0.5, 0.9),
+        (0.9, 0.5, 0.1),
+        (0.3, 0.7, 0.3),
+        (0.7, 0.3, 0.8),
+        (0.1, 0.7, 0.7),
+        (0.8, 0.3, 0.4),
+    ];
+    let hash = source.bytes().fold(0usize, |hash, byte| hash.wrapping_mul(31).wrapping_add(byte as usize));
+    let (r, g, b) = PALETTE[hash % PALETTE.len()];
+    iced::Color::from_rgb(r, g, b)
+}
+
+/// `link -> target`, marked when the link is broken or leaves the scanned
+/// root. Links waiting on an unmounted volume are set apart from broken ones.
+fn symlink_row<'a>(link: &SymlinkEntry) -> Element<'a, Message> {
+    let mut text = format!("{} -> {}", util::truncate_middle(&link.path, MAX_PATH_CHARS), link.target);
+    let color = match link.status {
+        LinkStatus::Ok => None,
+        LinkStatus::Broken => {
+            text = format!("{} {}", text, tr("symlink-broken"));
+            Some(iced::Color::from_rgb(1.0, 0.0, 0.0))
+        }
+        LinkStatus::MediaMissing => {
+            text = format!("{} {}", text, tr("symlink-media-missing"));
+            Some(iced::Color::from_rgb(1.0, 0.55, 0.0))
+        }
+    };
+    if link.outside_root {
+        text = format!("{} {}", text, tr("symlink-outside-root"));
+    }
+    match color {
+        Some(color) => Text::new(text).style(color).into(),
+        None => Text::new(text).into(),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ShareBasis {
+    Disk,
+    Scope,
+    Filtered,
+}
+
+/// `(share, cumulative share)` in percent for each size, in order.
+fn shares(sizes_mb: impl Iterator<Item = f64>, denominator_mb: f64) -> Vec<(f64, f64)> {
+    let mut cumulative = 0.0;
+    sizes_mb
+        .map(|size_mb| {
+            let share = if denominator_mb > 0.0 { size_mb / denominator_mb * 100.0 } else { 0.0 };
+            cumulative += share;
+            (share, cumulative)
+        })
+        .collect()
+}
+
+/// Size of a disk's current scope.
+#[derive(Debug, Clone, Default)]
+struct ScopeSummary {
+    size_mb: f64,
+}
+
+impl ScopeSummary {
+    /// Totals the files under `scope`, or the whole disk.
+    fn new(disk: &DiskInfo, scope: Option<&Path>) -> ScopeSummary {
+        let root = scope.unwrap_or_else(|| Path::new(&disk.mount_point));
+        let size_mb = disk
+            .files
+            .iter()
+            .filter(|file| file.symlink_target.is_none() && Path::new(&*file.path).starts_with(root))
+            .map(|file| file.size_mb)
+            .sum();
+        ScopeSummary { size_mb }
+    }
+}
+
+/// What the file lists are ordered by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+enum SortKey {
+    #[default]
+    Size,
+    Name,
+    Path,
+    Modified,
+    Extension,
+}
+
+impl SortKey {
+    const ALL: &[SortKey] = &[SortKey::Size, SortKey::Name, SortKey::Path, SortKey::Modified, SortKey::Extension];
+
+    /// Where `file` goes in a list ordered by this key, worked out once per
+    /// file so sorting doesn't rebuild names or extensions per comparison.
+    fn position(self, file: &FileInfo, descending: bool) -> SortPosition<'_> {
+        let value = match self {
+            SortKey::Size => SortValue::Size(file.size_mb),
+            SortKey::Name => SortValue::Natural(Path::new(&*file.path).file_name().map_or(Cow::Borrowed(""), |name| name.to_string_lossy())),
+            SortKey::Path => SortValue::Natural(Cow::Borrowed(&*file.path)),
+            SortKey::Extension => SortValue::Extension(
+                Path::new(&*file.path).extension().map(|ext| ext.to_string_lossy().to_lowercase()).unwrap_or_default(),
+            ),
+            SortKey::Modified => SortValue::Modified(file.modified),
+        };
+        SortPosition { value, descending, size_mb: file.size_mb, path: &file.path }
+    }
+}
+
+enum SortValue<'a> {
+    Size(f64),
+    /// Compared with `util::natural_cmp`.
+    Natural(Cow<'a, str>),
+    Extension(String),
+    Modified(Option<u64>),
+}
+
+/// A file's sort value, falling back to size and then path so ties stay
+/// stable. Files without a modification time sort last in either direction.
+struct SortPosition<'a> {
+    value: SortValue<'a>,
+    descending: bool,
+    size_mb: f64,
+    path: &'a str,
+}
+
+impl Ord for SortPosition<'_> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        use std::cmp::Ordering;
+        let ordering = match (&self.value, &other.value) {
+            (SortValue::Size(a), SortValue::Size(b)) => a.total_cmp(b),
+            (SortValue::Natural(a), SortValue::Natural(b)) => util::natural_cmp(a, b),
+            (SortValue::Extension(a), SortValue::Extension(b)) => a.cmp(b),
+            (SortValue::Modified(a), SortValue::Modified(b)) => match (a, b) {
+                (Some(x), Some(y)) => x.cmp(y),
+                (Some(_), None) => return Ordering::Less,
+                (None, Some(_)) => return Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            },
+            // Positions from different keys are never compared.
+            _ => Ordering::Equal,
+        };
+        let ordering = if self.descending { ordering.reverse() } else { ordering };
+        ordering.then_with(|| other.size_mb.total_cmp(&self.size_mb)).then_with(|| self.path.cmp(other.path))
+    }
+}
+
+impl PartialOrd for SortPosition<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SortPosition<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == std::cmp::Ordering::Equal
+    }
+}
+
+impl Eq for SortPosition<'_> {}
+
+impl std::fmt::Display for SortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self {
+            SortKey::Size => "sort-size",
+            SortKey::Name => "sort-name",
+            SortKey::Path => "sort-path",
+            SortKey::Modified => "sort-modified",
+            SortKey::Extension => "sort-extension",
+        };
+        write!(f, "{}", tr(key))
+    }
+}
+
+/// What the filters look at in a file, whether it came from a scan or from
+/// the `dupes` command's own walk.
+struct FileFacts<'a> {
+    path: &'a str,
+    is_hidden: bool,
+    is_binary: Option<bool>,
+    mime_type: Option<&'a str>,
+    size_mb: f64,
+    modified: Option<u64>,
+    uid: Option<u32>,
+    mode: Option<u32>,
+}
+
+impl<'a> FileFacts<'a> {
+    fn of(file: &'a FileInfo) -> FileFacts<'a> {
+        FileFacts {
+            path: &file.path,
+            is_hidden: file.is_hidden,
+            is_binary: file.is_binary,
+            mime_type: file.mime_type.as_deref(),
+            size_mb: file.size_mb,
+            modified: file.modified,
+            uid: file.uid,
+            mode: file.mode,
+        }
+    }
+
+    fn mime_mismatch(&self) -> bool {
+        self.mime_type.is_some_and(|mime| classify::mime_mismatch(self.path, mime))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+enum HiddenFilter {
+    #[default]
+    All,
+    Exclude,
+    Only,
+}
+
+impl HiddenFilter {
+    const ALL: &[HiddenFilter] = &[HiddenFilter::All, HiddenFilter::Exclude, HiddenFilter::Only];
+
+    fn matches(self, is_hidden: bool) -> bool {
+        match self {
+            HiddenFilter::All => true,
+            HiddenFilter::Exclude => !is_hidden,
+            HiddenFilter::Only => is_hidden,
+        }
+    }
+}
+
+impl std::fmt::Display for HiddenFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self {
+            HiddenFilter::All => "hidden-all",
+            HiddenFilter::Exclude => "hidden-exclude",
+            HiddenFilter::Only => "hidden-only",
+        };
+        write!(f, "{}", tr(key))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+enum BinaryFilter {
+    #[default]
+    All,
+    Binary,
+    Text,
+}
+
+impl BinaryFilter {
+    const ALL: &[BinaryFilter] = &[BinaryFilter::All, BinaryFilter::Binary, BinaryFilter::Text];
+
+    /// Files that weren't checked match neither `Binary` nor `Text`.
+    fn matches(self, is_binary: Option<bool>) -> bool {
+        match self {
+            BinaryFilter::All => true,
+            BinaryFilter::Binary => is_binary == Some(true),
+            BinaryFilter::Text => is_binary == Some(false),
+        }
+    }
+}
+
+impl std::fmt::Display for BinaryFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self {
+            BinaryFilter::All => "binary-all",
+            BinaryFilter::Binary => "binary-only",
+            BinaryFilter::Text => "binary-text-only",
+        };
+        write!(f, "{}", tr(key))
+    }
+}
+
+/// The age-category filter: every file, or only those in one category.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+enum AgeFilter {
+    #[default]
+    All,
+    Only(classify::AgeCategory),
+}
+
+impl AgeFilter {
+    const ALL: &[AgeFilter] = &[
+        AgeFilter::All,
+        AgeFilter::Only(classify::AgeCategory::Recent),
+        AgeFilter::Only(classify::AgeCategory::Moderate),
+        AgeFilter::Only(classify::AgeCategory::Old),
+        AgeFilter::Only(classify::AgeCategory::VeryOld),
+    ];
+
+    /// Files without a modification time only match `All`.
+    fn matches(self, modified: Option<u64>, now: u64) -> bool {
+        match self {
+            AgeFilter::All => true,
+            AgeFilter::Only(category) => modified.is_some_and(|secs| file_age(secs, now) == category),
+        }
+    }
+}
+
+impl std::fmt::Display for AgeFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AgeFilter::All => write!(f, "{}", tr("age-filter-all")),
+            AgeFilter::Only(category) => write!(f, "{}", tr_args("age-filter-only", &[("age", category.to_string())])),
+        }
+    }
+}
+
+/// The owner filter: every file, or only those of one user id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+enum OwnerFilter {
+    #[default]
+    All,
+    Uid(u32),
+}
+
+impl std::fmt::Display for OwnerFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OwnerFilter::All => write!(f, "{}", tr("owner-filter-all")),
+            OwnerFilter::Uid(uid) => write!(f, "{}", owners::owner_name(*uid)),
+        }
+    }
+}
+
+/// The age category at `now` of a modification time, both in seconds since
+/// the Unix epoch.
+fn file_age(modified: u64, now: u64) -> classify::AgeCategory {
+    classify::classify_age(UNIX_EPOCH + Duration::from_secs(modified), UNIX_EPOCH + Duration::from_secs(now))
+}
+
+#[derive(Debug, Clone, Default)]
+struct Palette {
+    query: String,
+    /// Index into the current matches.
+    selected: usize,
+}
+
+/// Warning colours for the "nearly full" banner.
+struct BannerStyle;
+
+impl container::StyleSheet for BannerStyle {
+    type Style = iced::Theme;
+
+    fn appearance(&self, style: &Self::Style) -> container::Appearance {
+        let palette = style.extended_palette();
+        container::Appearance {
+            background: Some(palette.danger.weak.color.into()),
+            text_color: Some(palette.danger.weak.text),
+            border_radius: 4.0.into(),
+            border_width: 1.0,
+            border_color: palette.danger.strong.color,
+        }
+    }
+}
+
+struct PanelStyle;
+
+impl container::StyleSheet for PanelStyle {
+    type Style = iced::Theme;
+
+    fn appearance(&self, style: &Self::Style) -> container::Appearance {
+        let palette = style.extended_palette();
+        container::Appearance {
+            background: Some(palette.background.weak.color.into()),
+            border_radius: 4.0.into(),
+            border_width: 1.0,
+            border_color: palette.background.strong.color,
+            ..Default::default()
+        }
+    }
+}
+
+/// Marks the keyboard-focused file row. Built from the theme's palette so it
+/// stands out in light and dark themes alike.
+struct FocusStyle;
+
+impl container::StyleSheet for FocusStyle {
+    type Style = iced::Theme;
+
+    fn appearance(&self, style: &Self::Style) -> container::Appearance {
+        let palette = style.extended_palette();
+        container::Appearance {
+            background: Some(palette.primary.weak.color.into()),
+            text_color: Some(palette.primary.weak.text),
+            border_radius: 4.0.into(),
+            border_width: 2.0,
+            border_color: palette.primary.strong.color,
+        }
+    }
+}
+
+/// Shades every other file row so long lists are easier to follow.
+struct StripeStyle;
+
+impl container::StyleSheet for StripeStyle {
+    type Style = iced::Theme;
+
+    fn appearance(&self, style: &Self::Style) -> container::Appearance {
+        container::Appearance {
+            background: Some(style.extended_palette().background.weak.color.into()),
+            border_radius: 2.0.into(),
+            ..Default::default()
+        }
+    }
+}
+
+/// Keyboard navigation: Tab/Shift+Tab move between inputs, the arrow keys
+/// move through the file lists (Up/Down within a disk, Left/Right between
+/// disks), Enter or Space toggles the focused file's selection, and Escape
+/// clears the focus. Backspace leaves the current directory scope.
+fn key_message(key_code: keyboard::KeyCode, modifiers: keyboard::Modifiers) -> Option<Message> {
+    match key_code {
+        keyboard::KeyCode::Tab => Some(Message::FocusNext(modifiers.shift())),
+        keyboard::KeyCode::Up => Some(Message::MoveFileFocus(-1)),
+        keyboard::KeyCode::Down => Some(Message::MoveFileFocus(1)),
+        keyboard::KeyCode::PageUp => Some(Message::MoveFileFocus(-10)),
+        keyboard::KeyCode::PageDown => Some(Message::MoveFileFocus(10)),
+        keyboard::KeyCode::Left => Some(Message::MoveDiskFocus(-1)),
+        keyboard::KeyCode::Right => Some(Message::MoveDiskFocus(1)),
+        keyboard::KeyCode::Enter | keyboard::KeyCode::Space => Some(Message::ActivateFocusedFile),
+        keyboard::KeyCode::Escape => Some(Message::ClearFocus),
+        keyboard::KeyCode::Backspace => Some(Message::ScopeUp),
+        _ => None,
+    }
+}
+
+impl DiskVisualizer {
+    /// Rebuilds the cached file list rows: per disk, the files matching the
+    /// filters in the chosen order. Views take as many rows as `shown_rows`
+    /// allows from the front.
+    fn refresh_visible_files(&mut self) {
+        let (key, descending) = (self.settings.sort_key, self.settings.sort_descending);
+        self.scopes.resize(self.disks.len(), None);
+        self.drilldowns.resize(self.disks.len(), None);
+        let filters = self.current_filters();
+        let now = util::unix_now();
+        // Files only tallied can't be told apart by any filter.
+        let unfiltered = filters.same_filters(&FilterPreset::default());
+        let mut extension_groups = Vec::with_capacity(self.disks.len());
+        let mut unlisted_totals = Vec::with_capacity(self.disks.len());
+        self.scope_summaries = self
+            .disks
+            .iter()
+            .zip(&self.scopes)
+            .map(|(disk, scope)| ScopeSummary::new(disk, scope.as_deref()))
+            .collect();
+        let (visible_files, visible_shares): (Vec<_>, Vec<_>) = self
+            .disks
+            .iter()
+            .enumerate()
+            .map(|(index, disk)| {
+                let scope = self.scopes[index].as_ref();
+                let in_scope = |file: &FileInfo| scope.is_none_or(|scope| Path::new(&*file.path).starts_with(scope));
+                let mut rows: Vec<usize> = (0..disk.files.len())
+                    .filter(|&i| in_scope(&disk.files[i]) && filters.matches(&FileFacts::of(&disk.files[i]), now))
+                    .collect();
+                extension_groups.push(report::by_extension(rows.iter().map(|&i| &disk.files[i])));
+                let unlisted = disk
+                    .unstored
+                    .iter()
+                    .filter(|(dir, _)| unfiltered && scope.is_none_or(|scope| Path::new(dir.as_str()).starts_with(scope)))
+                    .fold((0, 0.0), |(count, size_mb), (_, tally)| (count + tally.count, size_mb + tally.size_mb));
+                unlisted_totals.push((unlisted.0 > 0).then_some(unlisted));
+                if let Some(extension) = self.drilldowns[index].as_ref() {
+                    rows.retain(|&i| disk.files[i].symlink_target.is_none() && report::extension_key(&disk.files[i].path) == *extension);
+                }
+                let denominator_mb = match self.share_basis(index) {
+                    ShareBasis::Filtered => rows.iter().map(|&i| disk.files[i].size_mb).sum(),
+                    ShareBasis::Scope => self.scope_summaries[index].size_mb,
+                    ShareBasis::Disk => disk.used_space * 1024.0,
+                };
+                rows.sort_by_cached_key(|&i| key.position(&disk.files[i], descending));
+                let shares = shares(rows.iter().map(|&i| disk.files[i].size_mb), denominator_mb);
+                (rows, shares)
+            })
+            .unzip();
+        self.dir_charts = self
+            .disks
+            .iter()
+            .enumerate()
+            .map(|(index, disk)| {
+                let root = self.scopes[index].clone().unwrap_or_else(|| PathBuf::from(&disk.mount_point));
+                dir_chart::DirChart::new(
+                    index,
+                    &root,
+                    visible_files[index].iter().map(|&i| &disk.files[i]),
+                    disk.unstored.iter().filter(|_| unfiltered && self.drilldowns[index].is_none()),
+                    self.settings.min_dir_size_mb,
+                    self.settings.chart_coloring,
+                )
+            })
+            .collect();
+        self.visible_files = visible_files;
+        self.visible_shares = visible_shares;
+        self.extension_groups = extension_groups;
+        self.unlisted_totals = unlisted_totals;
+        if let Some((disk, row)) = self.focused_file {
+            if row >= self.shown_rows(disk) {
+                self.focused_file = None;
+            }
+        }
+    }
+
+    /// What the percentages in a disk's file list are shares of: the filtered
+    /// files when any filter is set, else the current folder, else the disk's
+    /// used space.
+    fn share_basis(&self, disk: usize) -> ShareBasis {
+        if self.current_filters() != FilterPreset::default()
+            || self.drilldowns.get(disk).is_some_and(Option::is_some)
+        {
+            ShareBasis::Filtered
+        } else if self.scopes.get(disk).is_some_and(Option::is_some) {
+            ShareBasis::Scope
+        } else {
+            ShareBasis::Disk
+        }
+    }
+
+    /// One line of a disk's file list: a category glyph, the path and size in
+    /// the category's color, and the share columns. `(share, cumulative)` are
+    /// in percent.
+    fn file_row<'a>(&self, file: &FileInfo, (share, cumulative): (f64, f64), row_index: usize, focused: bool, now: u64) -> Element<'a, Message> {
+        let category = classify::categorize(&file.path);
+        let color = classify::file_type_color(&category);
+        let marker = if self.selected_files.contains(&*file.path) { "[x] " } else { "" };
+        let label = tr_args("file-row", &[
+            ("path", util::truncate_middle(&file.path, MAX_PATH_CHARS)),
+            ("size", format_size_mb(file.size_mb)),
+        ]);
+        let mut name = Column::new().push(Text::new(format!("{}{}", marker, label)).style(color));
+        if let Some(mime) = file.mime_type.as_deref().filter(|mime| classify::mime_mismatch(&file.path, mime)) {
+            name = name.push(Text::new(tr_args("file-mime-mismatch", &[("mime", mime.to_string())])).size(12).style(iced::Color::from_rgb(0.85, 0.45, 0.1)));
+        }
+        if let Some(hash) = &file.sha256 {
+            name = name.push(Text::new(tr_args("file-sha256", &[("hash", hash.clone())])).size(12).style(iced::Color::from_rgb(0.5, 0.5, 0.5)));
+        }
+        let age_badge = match file.modified.map(|modified| file_age(modified, now)) {
+            Some(age) => Text::new(age.to_string()).size(12).style(classify::age_color(age)),
+            None => Text::new(""),
+        };
+        let columns = Row::new()
+            .spacing(10)
+            .push(Text::new(classify::category_glyph(&category)).style(color).width(Length::Fixed(16.0)))
+            .push(name.width(Length::Fill))
+            .push(age_badge.width(Length::Fixed(AGE_BADGE_WIDTH)))
+            .push(Text::new(format!("{}%", format_number(share, 1))).width(Length::Fixed(SHARE_COLUMN_WIDTH)))
+            .push(Text::new(format!("{}%", format_number(cumulative, 1))).width(Length::Fixed(SHARE_COLUMN_WIDTH)));
+        let row = Container::new(columns).width(Length::Fill).padding([3, 4]);
+        if focused {
+            row.style(theme::Container::Custom(Box::new(FocusStyle))).into()
+        } else if row_index % 2 == 1 {
+            row.style(theme::Container::Custom(Box::new(StripeStyle))).into()
+        } else {
+            row.into()
+        }
+    }
+
+    /// Rows of `disk`'s cached list currently shown.
+    fn shown_rows(&self, disk: usize) -> usize {
+        let (Some(info), Some(rows)) = (self.disks.get(disk), self.visible_files.get(disk)) else {
+            return 0;
+        };
+        let limit = self.row_limits.get(&info.display_name()).copied().unwrap_or(INITIAL_FILE_ROWS);
+        limit.min(rows.len())
+    }
+
+    fn visible_files(&self, disk: usize) -> Vec<&FileInfo> {
+        match (self.disks.get(disk), self.visible_files.get(disk)) {
+            (Some(info), Some(rows)) => rows[..self.shown_rows(disk)].iter().filter_map(|&i| info.files.get(i)).collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    fn focused_file_path(&self) -> Option<String> {
+        let (disk, row) = self.focused_file?;
+        self.visible_files(disk).get(row).map(|file| file.path.to_string())
+    }
+
+    /// Local and imported disks whose usage is at or above the "nearly full"
+    /// threshold, in display order.
+    fn nearly_full_disks(&self) -> Vec<usize> {
+        let threshold = f64::from(self.settings.nearly_full_percent);
+        (0..self.disks.len()).filter(|&index| self.disks[index].used_percent() >= threshold).collect()
+    }
+
+    /// Sends one desktop notification naming the disks that crossed the
+    /// threshold since the last check, if that notification is turned on.
+    fn notify_nearly_full(&mut self) {
+        let nearly_full: Vec<String> = self.nearly_full_disks().into_iter().map(|index| self.disks[index].display_name()).collect();
+        self.notified_full.retain(|name| nearly_full.contains(name));
+        let new: Vec<String> = nearly_full.into_iter().filter(|name| !self.notified_full.contains(name)).collect();
+        if new.is_empty() {
+            return;
+        }
+        if self.settings.notify_nearly_full {
+            notify::send(&tr("app-title"), &tr_args("notify-nearly-full-body", &[
+                ("disks", new.join(", ")),
+                ("percent", self.settings.nearly_full_percent.to_string()),
+            ]));
+        }
+        self.notified_full.extend(new);
+    }
+
+    /// While auto-refresh is on, warns about each local disk whose free space
+    /// is below its threshold, at most once per `LOW_SPACE_NOTIFY_INTERVAL`
+    /// per disk.
+    fn check_low_space(&mut self) {
+        if self.settings.auto_refresh_minutes.is_none() {
+            return;
+        }
+        let now = Instant::now();
+        for disk in self.disks.iter().filter(|disk| disk.source.is_none() && disk.total_space > 0.0) {
+            let available = disk.total_space - disk.used_space;
+            if available / disk.total_space * 100.0 >= f64::from(self.settings.low_space_percent(&disk.name)) {
+                continue;
+            }
+            if self.last_notified.get(&disk.name).is_some_and(|sent| now.duration_since(*sent) < LOW_SPACE_NOTIFY_INTERVAL) {
+                continue;
+            }
+            notify::send(&tr("app-title"), &tr_args("notify-low-space-body", &[
+                ("disk", disk.display_name()),
+                ("size", format_gb(available)),
+            ]));
+            self.last_notified.insert(disk.name.clone(), now);
+        }
+    }
+
+    /// Focuses `row` of `disk`'s file list, clamped to the rows it has, and
+    /// scrolls the list so the row stays in view.
+    fn focus_file_row(&mut self, disk: usize, row: usize) -> Command<Message> {
+        let rows = self.shown_rows(disk);
+        if rows == 0 {
+            self.focused_file = None;
+            return Command::none();
+        }
+        let row = row.min(rows - 1);
+        self.focused_file = Some((disk, row));
+        let offset = if rows > 1 { row as f32 / (rows - 1) as f32 } else { 0.0 };
+        scrollable::snap_to(disk_scroll_id(disk), RelativeOffset { x: 0.0, y: offset })
+    }
+
+    fn load_history() -> Command<Message> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(history::load());
+        });
+        Command::perform(async move { rx.recv().unwrap_or_default() }, Message::HistoryLoaded)
+    }
+
+    fn save_history(&self) {
+        history::save_in_background(self.scan_history.clone(), self.scan_runs.clone());
+    }
+
+    /// Earlier scans of the local disks, newest first, and the machines
+    /// imported, to compare the current scan against.
+    fn growth_baselines(&self) -> Vec<growth::GrowthBaseline> {
+        let local: Vec<&DiskInfo> = self.disks.iter().filter(|disk| disk.source.is_none()).collect();
+        let current_since = local.iter().filter_map(|disk| disk.scanned_at).min().unwrap_or(0);
+        let mut timestamps: Vec<u64> = self
+            .scan_history
+            .iter()
+            .filter(|record| record.timestamp < current_since && local.iter().any(|disk| disk.name == record.disk.name))
+            .map(|record| record.timestamp)
+            .collect();
+        timestamps.sort_unstable_by(|a, b| b.cmp(a));
+        timestamps.dedup();
+        let mut hosts: Vec<String> = self.disks.iter().filter_map(|disk| disk.source.clone()).collect();
+        hosts.sort();
+        hosts.dedup();
+        // Every kept result but the one shown, including the latest while an
+        // earlier one is shown.
+        let mut kept: Vec<u64> = self.snapshots.iter().filter_map(|disks| snapshot_time(disks)).collect();
+        kept.sort_unstable_by(|a, b| b.cmp(a));
+        kept.dedup();
+        kept.into_iter()
+            .map(growth::GrowthBaseline::Snapshot)
+            .chain(timestamps.into_iter().map(growth::GrowthBaseline::Scan))
+            .chain(hosts.into_iter().map(growth::GrowthBaseline::Import))
+            .collect()
+    }
+
+    /// Compares the directory sizes recorded for each local disk's current
+    /// scan with `baseline`: an earlier record of the same disk, or the
+    /// imported disk with the same mount point.
+    fn compare_growth(&self, baseline: growth::GrowthBaseline) -> growth::GrowthReport {
+        let mut report = growth::GrowthReport::new(baseline.clone());
+        for disk in self.disks.iter().filter(|disk| disk.source.is_none()) {
+            let Some(scanned_at) = disk.scanned_at else {
+                continue;
+            };
+            let in_memory;
+            let current = match &baseline {
+                // The history holds the latest scans only; both sides are at hand.
+                growth::GrowthBaseline::Snapshot(_) => {
+                    in_memory = growth::recorded_dir_sizes(disk);
+                    &in_memory
+                }
+                _ => match self
+                    .scan_history
+                    .iter()
+                    .filter(|record| record.disk.name == disk.name && record.timestamp >= scanned_at)
+                    .max_by_key(|record| record.timestamp)
+                {
+                    Some(record) => &record.dirs,
+                    None => continue,
+                },
+            };
+            let imported;
+            let previous = match &baseline {
+                growth::GrowthBaseline::Scan(timestamp) => self
+                    .scan_history
+                    .iter()
+                    .find(|record| record.timestamp == *timestamp && record.disk.name == disk.name)
+                    .map(|record| &record.dirs),
+                growth::GrowthBaseline::Import(host) => {
+                    imported = self
+                        .disks
+                        .iter()
+                        .find(|other| other.source.as_ref() == Some(host) && other.mount_point == disk.mount_point)
+                        .map(growth::recorded_dir_sizes);
+                    imported.as_ref()
+                }
+                growth::GrowthBaseline::Snapshot(timestamp) => {
+                    imported = self
+                        .snapshots
+                        .iter()
+                        .find(|disks| snapshot_time(disks) == Some(*timestamp))
+                        .and_then(|disks| disks.iter().find(|other| other.source.is_none() && other.name == disk.name))
+                        .map(growth::recorded_dir_sizes);
+                    imported.as_ref()
+                }
+            };
+            if let Some(previous) = previous {
+                report.add_disk(&disk.name, current, previous);
+            }
+        }
+        if let growth::GrowthBaseline::Snapshot(timestamp) = baseline {
+            if let Some(disks) = self.snapshots.iter().find(|disks| snapshot_time(disks) == Some(timestamp)) {
+                report.extensions = growth::compute_extension_growth(disks, &self.disks);
+            }
+        }
+        report.finish(&self.scan_history)
+    }
+
+    /// Keeps `disks`, the results a scan is about to replace, to flip back
+    /// to. Called only while the latest results are shown.
+    fn keep_snapshot(&mut self, disks: Arc<Vec<DiskInfo>>) {
+        if disks.iter().all(|disk| disk.source.is_some()) {
+            return;
+        }
+        self.snapshots.push(disks);
+        self.trim_snapshots();
+    }
+
+    /// Drops the oldest snapshots beyond `kept_scans`. With a file entry
+    /// cap, the earlier results together keep one cap's worth of entries.
+    fn trim_snapshots(&mut self) {
+        let excess = (self.snapshots.len() + 1).saturating_sub(self.settings.kept_scans);
+        self.snapshots.drain(..excess);
+        if let Some(cap) = self.scan_config.max_file_entries {
+            cap_file_entries(self.snapshots.iter_mut().flat_map(|disks| Arc::make_mut(disks).iter_mut()), cap);
+        }
+    }
+
+    /// Shows the kept snapshot at `index`, or the latest results for `None`,
+    /// and resets what was derived from the disks shown before.
+    fn show_snapshot(&mut self, index: Option<usize>) {
+        if index == self.shown_snapshot {
+            return;
+        }
+        if let Some(shown) = self.shown_snapshot.take() {
+            std::mem::swap(&mut self.disks, &mut self.snapshots[shown]);
+        }
+        if let Some(index) = index {
+            std::mem::swap(&mut self.disks, &mut self.snapshots[index]);
+            self.shown_snapshot = Some(index);
+        }
+        self.selected_files.clear();
+        self.clone_pairs = None;
+        self.same_name_groups = None;
+        self.refresh_treemap();
+        self.refresh_owner_choices();
+        self.focused_file = None;
+        self.scopes = vec![None; self.disks.len()];
+        self.drilldowns = vec![None; self.disks.len()];
+        self.row_limits.clear();
+        self.refresh_visible_files();
+        self.disk_scroll_offsets = vec![RelativeOffset::START; self.disks.len()];
+        self.refresh_growth_report();
+    }
+
+    /// Redoes an open comparison after the scans it is based on changed.
+    fn refresh_growth_report(&mut self) {
+        if let Some(baseline) = self.growth_report.as_ref().and_then(|report| report.baseline.clone()) {
+            self.growth_report = Some(self.compare_growth(baseline));
+        }
+    }
+
+    fn growth_panel<'a>(&self, report: &growth::GrowthReport) -> Element<'a, Message> {
+        let mut panel = Column::new().spacing(5).push(Text::new(tr("growth-header")).size(18));
+        let baselines = self.growth_baselines();
+        if baselines.is_empty() {
+            return panel.push(Text::new(tr("growth-no-baseline"))).into();
+        }
+        panel = panel.push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(tr("growth-baseline-label")))
+                .push(PickList::new(baselines, report.baseline.clone(), Message::GrowthBaselineSelected))
+                .push(Button::new(Text::new(tr("growth-export"))).on_press_maybe(report.baseline.is_some().then_some(Message::ExportGrowth))),
+        );
+        if report.baseline.is_none() {
+            return panel.into();
+        }
+
+        if report.grown.is_empty() {
+            panel = panel.push(Text::new(tr("growth-nothing")));
+        }
+        for dir in &report.grown {
+            let path = util::truncate_middle(&dir.path, MAX_PATH_CHARS);
+            let size = format_size_mb(dir.growth_mb());
+            let label = match dir.percent() {
+                Some(percent) => tr_args("growth-row", &[("path", path), ("size", size), ("percent", format_number(percent, 1))]),
+                None => tr_args("growth-row-new", &[("path", path), ("size", size)]),
+            };
+            let mut row = Row::new().spacing(10).push(Text::new(label).width(Length::Fill));
+            if !dir.trend.is_empty() {
+                let max = dir.trend.iter().copied().fold(0.0, f64::max) as f32;
+                row = row.push(Sparkline::new(dir.trend.iter().map(|&size| size as f32), max, iced::Color::from_rgb(0.85, 0.45, 0.1)).view());
+            }
+            panel = panel.push(row);
+        }
+
+        // Those that grew most, then those that shrank most.
+        let changed: Vec<&growth::ExtensionGrowth> = report.extensions.iter().filter(|extension| extension.delta_mb != 0.0).collect();
+        if !changed.is_empty() {
+            panel = panel.push(Text::new(tr("growth-extensions-header")).size(16));
+            let grew = changed.iter().take_while(|extension| extension.delta_mb > 0.0).take(growth::EXTENSION_GROWTH_ROWS);
+            let shrank = changed.iter().rev().take_while(|extension| extension.delta_mb < 0.0).take(growth::EXTENSION_GROWTH_ROWS);
+            for extension in grew.chain(shrank) {
+                let key = if extension.delta_mb > 0.0 { "growth-extension-grew" } else { "growth-extension-shrank" };
+                panel = panel.push(Text::new(tr_args(key, &[
+                    ("extension", report::extension_label(&extension.extension)),
+                    ("size", format_size_mb(extension.delta_mb.abs())),
+                    ("before", format_count(extension.old_count)),
+                    ("after", format_count(extension.new_count)),
+                ])));
+            }
+        }
+
+        if !report.freed.is_empty() {
+            let freed_mb: f64 = report.freed.iter().map(|dir| dir.before_mb).sum();
+            panel = panel.push(
+                Button::new(Text::new(tr_args("growth-freed-header", &[
+                    ("count", format_count(report.freed.len())),
+                    ("size", format_size_mb(freed_mb)),
+                ])))
+                .style(theme::Button::Text)
+                .on_press(Message::ToggleFreedDirs),
+            );
+            if self.freed_dirs_expanded {
+                for dir in report.freed.iter().take(MAX_FREED_DIRS_SHOWN) {
+                    panel = panel.push(Text::new(tr_args("growth-freed-row", &[
+                        ("path", util::truncate_middle(&dir.path, MAX_PATH_CHARS)),
+                        ("size", format_size_mb(dir.before_mb)),
+                    ])));
+                }
+            }
+        }
+        if let Some(ref path) = self.growth_exported {
+            panel = panel.push(Text::new(tr_args("growth-exported", &[("path", path.clone())])));
+        }
+        panel.into()
+    }
+
+    /// The roots the next scan walks: the queued folder, or the disks
+    /// ticked for a full scan.
+    fn scan_roots(&mut self) -> Vec<PathBuf> {
+        if let Some(path) = self.scan_queue.front() {
+            return vec![path.clone()];
+        }
+        self.refresh_detected_disks();
+        self.detected_disks
+            .iter()
+            .filter(|disk| !self.settings.excluded_disks.contains(&disk.mount_point))
+            .map(|disk| PathBuf::from(&disk.mount_point))
+            .collect()
+    }
+
+    /// Re-reads the disks a full scan would walk, so drives plugged in or
+    /// removed since, and changed scan options, show in `disk_selection`.
+    fn refresh_detected_disks(&mut self) {
+        self.scan_config.virtual_filesystems = self.settings.virtual_filesystems.clone();
+        self.detected_disks = scan::detect_disks(&self.scan_config);
+    }
+
+    /// A checkbox per detected disk, with its size, choosing what a full scan
+    /// walks. Shown in the empty state and, once there are results, with the
+    /// scan settings.
+    fn disk_selection<'a>(&self) -> Element<'a, Message> {
+        if self.detected_disks.is_empty() {
+            return Text::new(tr("no-disks-detected")).into();
+        }
+        self.detected_disks
+            .iter()
+            .fold(Column::new().spacing(5).push(Text::new(tr("disks-to-scan"))), |column, disk| {
+                let mount_point = disk.mount_point.clone();
+                let label = tr_args("detected-disk", &[
+                    ("name", disk.name.clone()),
+                    ("mount", disk.mount_point.clone()),
+                    ("used", format_gb(disk.used_space)),
+                    ("total", format_gb(disk.total_space)),
+                ]);
+                column.push(Checkbox::new(label, !self.settings.excluded_disks.contains(&disk.mount_point), move |selected| {
+                    Message::DiskSelected(mount_point.clone(), selected)
+                }))
+            })
+            .into()
+    }
+
+    /// Adds the scan that just finished to the performance history, which is
+    /// saved along with the disks' records by `update_fill_estimates`.
+    fn record_scan_run(&mut self, duration: f64) {
+        let kind = if self.scan_path.is_some() {
+            ScanKind::Folder
+        } else if self.settings.scan_profile == ScanProfile::Quick {
+            ScanKind::Quick
+        } else {
+            ScanKind::Full
+        };
+        let run = ScanRun {
+            timestamp: util::unix_now(),
+            kind,
+            duration_secs: duration,
+            files: self.scan_progress.files_scanned.load(Ordering::Relaxed),
+            bytes_walked: self.scan_progress.bytes_scanned.load(Ordering::Relaxed),
+            profile: self.settings.scan_profile,
+            threads: self.scan_progress.threads.load(Ordering::Relaxed),
+            priority: self.scan_config.priority,
+            store_files_above_mb: self.scan_config.store_files_above_mb,
+        };
+        history::append_runs(&mut self.scan_runs, vec![run]);
+    }
+
+    /// Records the disks of a whole-disk scan in the scan history and
+    /// re-derives each disk's fill date from its previous record. A folder
+    /// scan records nothing: the disks shown beside it were recorded when
+    /// they were scanned.
+    fn update_fill_estimates(&mut self) {
+        let now = util::unix_now();
+        if self.scan_path.is_none() {
+            let scanned = self.disks.iter().filter(|disk| disk.source.is_none() && !disk.folder);
+            history::record_scan(&mut self.scan_history, scanned, now);
+            self.refresh_fill_estimates(now);
+        }
+        if self.history_loaded {
+            self.save_history();
+        }
+    }
+
+    /// Fill dates of the local disks from their record before the one taken
+    /// at `recorded_at`.
+    fn refresh_fill_estimates(&mut self, recorded_at: u64) {
+        self.fill_estimates.clear();
+        for disk in self.disks.iter().filter(|disk| disk.source.is_none()) {
+            if let Some(previous) = history::previous_record(&self.scan_history, disk, recorded_at) {
+                let elapsed = Duration::from_secs(recorded_at - previous.timestamp);
+                if let Some(fill_time) = history::estimate_time_to_fill(disk, &previous.disk, elapsed) {
+                    self.fill_estimates.insert(disk.mount_point.clone(), fill_time);
+                }
+            }
+        }
+    }
+
+    fn save_settings(&mut self) {
+        if let Err(e) = self.settings.save() {
+            self.error_message = Some(tr_args("settings-save-failed", &[("error", e)]));
+        }
+    }
+
+    /// Makes `profile` the default for future scans and remembers it across launches.
+    fn select_scan_profile(&mut self, profile: ScanProfile) {
+        self.settings.scan_profile = profile;
+        self.save_settings();
+    }
+
+    fn apply_scan_profile(&mut self) {
+        let (max_depth, max_files) = self
+            .settings
+            .scan_profile
+            .limits()
+            .unwrap_or((self.settings.custom_max_depth, self.settings.custom_max_files));
+        self.scan_config.max_depth = max_depth;
+        self.scan_config.max_files = max_files;
+        self.scan_config.max_scan_duration = self.settings.max_scan_duration_secs.map(Duration::from_secs);
+        self.scan_config.io_quota_bytes = self.settings.io_quota_mb.filter(|&mb| mb > 0).map(|mb| mb.saturating_mul(1_048_576));
+        self.scan_config.throttle_at_cpu_pct = self.settings.throttle_at_cpu_pct;
+        self.scan_config.threads = self.settings.scan_threads.map(|threads| threads.min(scan::max_threads()));
+        self.scan_config.excluded_disks = self.settings.excluded_disks.clone();
+        self.scan_config.priority = self.settings.scan_priority;
+        self.scan_config.storage_order = self.settings.storage_order;
+        self.scan_config.store_files_above_mb = self.settings.store_files_above_mb;
+        self.scan_config.max_file_entries = self.settings.max_file_entries;
+        self.scan_config.buffer_size = self.settings.entry_buffer_size;
+        self.scan_config.checkpoint_interval = self.settings.checkpoint_interval;
+        self.scan_config.virtual_filesystems = self.settings.virtual_filesystems.clone();
+        self.scan_config.artifact_rules = self.settings.artifact_rules.clone();
+        self.scan_config.binary_check_max_bytes = self.settings.binary_check_max_mb.map_or(0, |mb| mb.saturating_mul(1_048_576));
+        self.scan_config.line_count_max_bytes = self.settings.line_count_max_mb.map_or(0, |mb| mb.saturating_mul(1_048_576));
+        self.scan_config.checksum_top_n = self.settings.checksum_top_n;
+    }
+
+    /// The filters as they are set right now, as an unnamed preset. Size and
+    /// age fields that don't parse count as unset.
+    fn current_filters(&self) -> FilterPreset {
+        FilterPreset {
+            file_type: self.file_type_filter.clone(),
+            file_name: self.file_name_filter.clone(),
+            hidden: self.hidden_filter,
+            binary: self.binary_filter,
+            mime_type: self.mime_filter.clone(),
+            mime_mismatch: self.mime_mismatch_only,
+            owner: match self.owner_filter {
+                OwnerFilter::All => None,
+                OwnerFilter::Uid(uid) => Some(uid),
+            },
+            world_writable: self.world_writable_only,
+            setuid: self.setuid_only,
+            min_size_mb: self.min_size_filter.trim().replace(',', ".").parse().ok(),
+            older_than_days: self.older_than_filter.trim().parse().ok(),
+            age: self.age_filter,
+            ..FilterPreset::default()
+        }
+    }
+
+    fn is_active_preset(&self, preset: &FilterPreset) -> bool {
+        preset.same_filters(&self.current_filters())
+    }
+
+    /// The selected files found on this machine's disks: those of imported
+    /// disks are another machine's, and only listed.
+    fn selected_local_files(&self) -> Vec<String> {
+        self.disks
+            .iter()
+            .filter(|disk| disk.source.is_none())
+            .flat_map(|disk| &disk.files)
+            .filter(|file| self.selected_files.contains(&*file.path))
+            .map(|file| file.path.to_string())
+            .collect()
+    }
+
+    /// Gathers the owners of the current disks' files for the owner filter,
+    /// once whenever the files change rather than on every refresh of the
+    /// list. A filter for an owner no longer among them is cleared.
+    fn refresh_owner_choices(&mut self) {
+        let uids: BTreeSet<u32> = self.disks.iter().flat_map(|disk| &disk.files).filter_map(|file| file.uid).collect();
+        self.owner_choices = std::iter::once(OwnerFilter::All).chain(uids.into_iter().map(OwnerFilter::Uid)).collect();
+        if !self.owner_choices.contains(&self.owner_filter) {
+            self.owner_filter = OwnerFilter::All;
+        }
+    }
+
+    /// Rebuilds the "All disks" treemap, if open, from the current disks.
+    fn refresh_treemap(&mut self) {
+        if self.all_disks_treemap.is_some() {
+            self.all_disks_treemap = Some(treemap::Treemap::new(treemap::merged_files(&self.disks), treemap::merged_unstored(&self.disks)));
+        }
+    }
+
+    /// What a number field of the settings shows: the text typed into it, or
+    /// else `setting` formatted.
+    fn setting_text(&self, input: SettingInput, setting: impl FnOnce() -> String) -> String {
+        self.setting_inputs.get(&input).cloned().unwrap_or_else(setting)
+    }
+
+    /// The mount points of the disks scanned on this machine.
+    fn local_mount_points(&self) -> Vec<String> {
+        self.disks.iter().filter(|disk| disk.source.is_none()).map(|disk| disk.mount_point.clone()).collect()
+    }
+
+    /// Measures the trash on this machine's disks on a worker thread.
+    fn measure_trash(&self) -> Command<Message> {
+        let mount_points = self.local_mount_points();
+        let (tx, rx) = std::sync::mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(trash::measure(&mount_points));
+        });
+        Command::perform(async move { rx.recv().unwrap_or_default() }, Message::TrashMeasured)
+    }
+
+    /// Writes the fresh results to the auto-export destination on a worker
+    /// thread. Failures are reported but never stop scanning.
+    fn auto_export(&self) -> Option<Command<Message>> {
+        if !self.settings.auto_export_enabled {
+            return None;
+        }
+        let format = self.settings.auto_export_format;
+        let path = PathBuf::from(
+            self.settings
+                .auto_export_path
+                .replace("{date}", &util::today_iso())
+                .replace("{time}", &util::now_hms())
+                .replace("{ext}", format.extension()),
+        );
+        let mut disks = Arc::clone(&self.disks);
+        if self.settings.auto_export_filtered {
+            let filters = self.current_filters();
+            let now = util::unix_now();
+            for (index, disk) in Arc::make_mut(&mut disks).iter_mut().enumerate() {
+                disk.files.retain(|file| filters.matches(&FileFacts::of(file), now));
+                let denominator_mb = match self.share_basis(index) {
+                    ShareBasis::Disk => disk.used_space * 1024.0,
+                    // Exports aren't narrowed to a folder, only filtered.
+                    ShareBasis::Filtered | ShareBasis::Scope => disk.files.iter().map(|file| file.size_mb).sum(),
+                };
+                let shares = shares(disk.files.iter().map(|file| file.size_mb), denominator_mb);
+                for (file, (share, cumulative)) in disk.files.iter_mut().zip(shares) {
+                    file.share_percent = Some(share);
+                    file.cumulative_percent = Some(cumulative);
+                }
+            }
+        }
+        let compress = self.settings.compress_exports;
+        let nearly_full_percent = self.settings.nearly_full_percent;
+        let (tx, rx) = std::sync::mpsc::channel();
+        thread::spawn(move || {
+            let path = export_path(path, compress);
+            if let Some(parent) = path.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            let result = write_export(&path, format, &disks, compress, nearly_full_percent, &Arc::default())
+                .map(|_| path.display().to_string());
+            if let Err(ref e) = result {
+                eprintln!("Auto-export to {} failed: {}", path.display(), e);
+            }
+            let _ = tx.send(result);
+        });
+        Some(Command::perform(
+            async move { rx.recv().unwrap_or_else(|_| Err(tr("auto-export-worker-stopped"))) },
+            Message::AutoExportCompleted,
+        ))
+    }
+
+    /// Appends this scan's per-disk totals to the growth log, if one is enabled
+    /// by setting or by `--growth-log`, on a worker thread.
+    fn append_growth_log(&self) -> Option<Command<Message>> {
+        let path = match &self.growth_log_override {
+            Some(path) => path.clone(),
+            None if self.settings.growth_log_enabled => {
+                self.settings.growth_log_path.clone().unwrap_or_else(growth_log::default_path)
+            }
+            None => return None,
+        };
+        let records = growth_log::records_for(&self.disks, util::unix_now());
+        let (tx, rx) = std::sync::mpsc::channel();
+        thread::spawn(move || {
+            let _ = tx.send(growth_log::append(&path, &records));
+        });
+        Some(Command::perform(
+            async move { rx.recv().unwrap_or(Ok(())) },
+            Message::GrowthLogAppended,
+        ))
+    }
+
+    fn window_state(&self) -> WindowState {
+        self.settings.window.unwrap_or(WindowState {
+            width: 1024,
+            height: 768,
+            x: None,
+            y: None,
+        })
+    }
+
+    /// A scan or export is in flight, so quitting now would lose work.
+    fn is_busy(&self) -> bool {
+        self.activity() != Activity::Idle || self.auto_exporting || !self.active_rescans.is_empty()
+    }
+
+    /// Saves pending window state and exits.
+    fn quit(&self) -> Command<Message> {
+        if self.window_state_dirty {
+            let _ = self.settings.save();
+        }
+        instance::release();
+        std::process::exit(0);
+    }
+
+    fn activity(&self) -> Activity {
+        if self.scanning && self.background_scan {
+            Activity::Refreshing
+        } else if self.scanning {
+            Activity::Scanning
+        } else if self.exporting {
+            Activity::Exporting
+        } else {
+            Activity::Idle
+        }
+    }
+
+    /// Starts scanning the next dropped folder, if any. Queued folders were
+    /// asked for together, so the cooldown between scans doesn't apply.
+    fn scan_next_queued(&mut self) -> Command<Message> {
+        if self.scan_queue.is_empty() || self.scanning {
+            return Command::none();
+        }
+        self.last_scan_started = None;
+        self.update(Message::Scan)
+    }
+
+    /// Everything the command palette can do right now, as label and the
+    /// message it sends. Actions the current activity disables are left out.
+    fn palette_actions(&self) -> Vec<(String, Message)> {
+        let controls = controls_for(self.activity());
+        let mut actions = Vec::new();
+        if controls.scan {
+            actions.push((tr("scan-disk"), Message::Scan));
+            actions.push((tr("quick-scan"), Message::QuickScan));
+            actions.push((tr("deep-scan"), Message::DeepScan));
+            actions.push((tr("import-json"), Message::ImportJson));
+        }
+        if controls.cancel {
+            actions.push((tr("cancel-scan"), Message::StopScan));
+        }
+        for (index, disk) in self.disks.iter().enumerate() {
+            if controls.rescan && disk.source.is_none() {
+                actions.push((tr_args("palette-rescan", &[("disk", disk.display_name())]), Message::RescanDisk(disk.name.clone())));
+            }
+            actions.push((tr_args("palette-jump", &[("disk", disk.display_name())]), Message::JumpToDisk(index)));
+        }
+        if controls.export && !self.disks.is_empty() {
+            for &format in ExportFormat::ALL {
+                actions.push((tr_args("palette-export", &[("format", format.to_string())]), Message::ExportAs(format)));
+            }
+            actions.push((tr("copy-markdown-summary"), Message::CopyMarkdownSummary));
+        }
+        if self.shown_snapshot.map_or(!self.snapshots.is_empty(), |index| index > 0) {
+            actions.push((tr("palette-older-scan"), Message::ShowOlderScan));
+        }
+        if self.shown_snapshot.is_some() {
+            actions.push((tr("palette-newer-scan"), Message::ShowNewerScan));
+        }
+        actions.push((tr("palette-toggle-dark-mode"), Message::ToggleDarkMode(!self.settings.dark_mode)));
+        actions.push((tr("quit"), Message::Done));
+        actions
+    }
+
+    /// The actions matching the palette's query, best first.
+    fn palette_matches(&self) -> Vec<(String, Message)> {
+        let query = self.palette.as_ref().map_or("", |palette| palette.query.as_str());
+        let mut matches: Vec<(i64, (String, Message))> = self
+            .palette_actions()
+            .into_iter()
+            .filter_map(|action| util::fuzzy_score(query, &action.0).map(|score| (score, action)))
+            .collect();
+        // Stable, so equal scores keep the registry's order.
+        matches.sort_by_key(|&(score, _)| std::cmp::Reverse(score));
+        matches.into_iter().take(PALETTE_RESULTS).map(|(_, action)| action).collect()
     }
 
-    content = content.push(Text::new(format!("Scans performed: {}", self.scan_count.load(Ordering::SeqCst))));
+    fn palette_view(&self, palette: &Palette) -> Element<'_, Message> {
+        let input = TextInput::new(&tr("palette-placeholder"), &palette.query)
+            .id(palette_input_id())
+            .on_input(Message::PaletteQueryChanged)
+            .on_submit(Message::PaletteSubmit)
+            .padding(8);
+        let matches = self.palette_matches();
+        let mut list = Column::new().spacing(2).push(input);
+        if matches.is_empty() {
+            list = list.push(Text::new(tr("palette-no-matches")).size(14));
+        }
+        for (index, (label, _)) in matches.into_iter().enumerate() {
+            let style = if index == palette.selected { theme::Button::Primary } else { theme::Button::Text };
+            list = list.push(Button::new(Text::new(label)).width(Length::Fill).style(style).on_press(Message::PaletteRun(index)));
+        }
+        Container::new(list)
+            .padding(10)
+            .width(Length::Fill)
+            .style(theme::Container::Custom(Box::new(PanelStyle)))
+            .into()
+    }
 
-        
-    content = content
-        .push(Container::new(
-            Button::new(Text::new("Scan Disk"))
-                .on_press(Message::Scan)
-                .width(Length::Fixed(80.0)),
-        ))
-        .push(Container::new(
-            Button::new(Text::new("Stop Scan"))
-                .on_press(Message::StopScan)
-                .width(Length::Fixed(85.0)),
-        ));
+    /// Machine-wide totals kept above the scrolling content; clicking it
+    /// toggles a per-disk table.
+    fn summary_card(&self) -> Element<'_, Message> {
+        let summary = report::MachineSummary::new(&self.disks, self.settings.nearly_full_percent);
+        let mut card = Column::new()
+            .spacing(5)
+            .push(Text::new(summary.headline()).size(20))
+            .push(Text::new(summary.details()).size(14));
+        if self.summary_expanded {
+            let cell = |text: String, width: f32| Text::new(text).size(14).width(Length::Fixed(width));
+            card = card.push(
+                Row::new()
+                    .spacing(10)
+                    .push(Text::new(tr("summary-col-disk")).size(14).width(Length::Fill))
+                    .push(cell(tr("summary-col-used"), 200.0))
+                    .push(cell(tr("summary-col-free"), 100.0))
+                    .push(cell(tr("summary-col-files"), 80.0)),
+            );
+            for disk in self.disks.iter() {
+                card = card.push(
+                    Row::new()
+                        .spacing(10)
+                        .push(Text::new(disk.display_name()).size(14).width(Length::Fill))
+                        .push(cell(tr_args("summary-used-cell", &[
+                            ("used", format_gb(disk.used_space)),
+                            ("total", format_gb(disk.total_space)),
+                            ("percent", format_number(disk.used_percent(), 0)),
+                        ]), 200.0))
+                        .push(cell(format_gb(disk.total_space - disk.used_space), 100.0))
+                        .push(cell(format_count(disk.total_files()), 80.0)),
+                );
+            }
+        }
+        Button::new(card)
+            .width(Length::Fill)
+            .padding(10)
+            .style(theme::Button::Secondary)
+            .on_press(Message::ToggleSummaryTable)
+            .into()
+    }
 
-    content = content.push(
-        Container::new(
-            Button::new(Text::new("Refresh Disk Info"))
-                .on_press(Message::Refresh)
-                .width(Length::Fixed(130.0)),
-        )
-    );
+    fn scanning_panel(&self) -> Element<'_, Message> {
+        let progress = &self.scan_progress;
+        let files_scanned = progress.files_scanned.load(Ordering::Relaxed);
+        let bytes_scanned = progress.bytes_scanned.load(Ordering::Relaxed);
+        let expected_bytes = progress.expected_bytes.load(Ordering::Relaxed);
+        let cancelling = progress.is_cancelled();
+        let fraction = if expected_bytes > 0 {
+            (bytes_scanned as f64 / expected_bytes as f64).min(1.0) as f32
+        } else {
+            0.0
+        };
+        let spinner = SPINNER_FRAMES[self.elapsed_time.as_secs() as usize % SPINNER_FRAMES.len()];
+        let current_dir = match &self.current_dir_seen {
+            Some((dir, since)) => {
+                let dir = util::truncate_middle(dir, MAX_PATH_CHARS);
+                if (self.elapsed_time - *since).as_secs() > SLOW_DIRECTORY_SECS {
+                    tr_args("current-dir-slow", &[("path", dir)])
+                } else {
+                    dir
+                }
+            }
+            None => String::new(),
+        };
 
-    content = content.push(Row::new()
-        .spacing(10)
-        .push(Button::new(Text::new("Export as JSON")).on_press(Message::ExportAsJson).width(Length::Fixed(120.0)))
-        .push(Button::new(Text::new("Export as CSV")).on_press(Message::ExportAsCsv).width(Length::Fixed(110.0)))
-    );
+        let cancel = Button::new(Text::new(tr(if cancelling { "cancelling" } else { "cancel-scan" })))
+            .on_press_maybe((controls_for(self.activity()).cancel && !cancelling).then_some(Message::StopScan));
 
-    
-    content = content.push(Space::with_height(Length::Fill));
+        let panel = Column::new()
+            .spacing(10)
+            .push(Text::new(format!("{} {}", spinner, tr_args("scanning-header", &[("percent", format_number(fraction as f64 * 100.0, 0))]))).size(24))
+            .push(ProgressBar::new(0.0..=1.0, fraction).height(10))
+            .push(Text::new(current_dir))
+            .push(self.resource_sparklines())
+            .push(Text::new(tr_args("scan-counters", &[
+                ("files", format_count(files_scanned)),
+                ("size", format_gb(bytes_scanned as f64 / 1_073_741_824.0)),
+                ("seconds", self.elapsed_time.as_secs().to_string()),
+            ])))
+            .push(Text::new(tr_args("scan-retries", &[("count", format_count(progress.retry_count.load(Ordering::Relaxed)))])))
+            .push(
+                throttled_seconds(progress)
+                    .map(|seconds| tr_args("scan-throttled", &[("seconds", format_number(seconds, 1))]))
+                    .into_iter()
+                    .fold(Column::new(), |column, line| column.push(Text::new(line))),
+            )
+            .push(self.active_paths.iter().fold(Column::new().spacing(2), |column, path| {
+                column.push(Text::new(tr_args("currently-scanning", &[("path", util::truncate_middle(path, MAX_PATH_CHARS))])).size(14))
+            }))
+            .push(
+                self.scan_path
+                    .iter()
+                    .map(|path| tr_args("scanning-folder", &[("path", util::truncate_middle(&path.display().to_string(), MAX_PATH_CHARS))]))
+                    .chain((!self.scan_queue.is_empty()).then(|| tr_args("scan-queue", &[("count", format_count(self.scan_queue.len()))])))
+                    .fold(Column::new().spacing(2), |column, line| column.push(Text::new(line).size(14))),
+            )
+            .push(cancel);
 
-    let scrollable_content = iced::widget::scrollable::Scrollable::new(content)
-        .height(Length::Fill)
-        .width(Length::Fill);
+        Container::new(panel)
+            .padding(15)
+            .width(Length::Fill)
+            .style(theme::Container::Custom(Box::new(PanelStyle)))
+            .into()
+    }
 
-    let final_layout = Column::new()
-        .spacing(10)
-        .push(scrollable_content) 
-        .push(Button::new(Text::new("Done")).on_press(Message::Done).width(Length::Shrink)); // "Done" button at the bottom
+    fn sample_resources(&mut self) {
+        self.resource_monitor.refresh_cpu();
+        self.resource_monitor.refresh_memory();
+        if self.cpu_history.len() == sparkline::SAMPLES {
+            self.cpu_history.pop_front();
+            self.mem_history.pop_front();
+        }
+        self.cpu_history.push_back(self.resource_monitor.global_cpu_info().cpu_usage());
+        self.mem_history.push_back(self.resource_monitor.used_memory() as f64);
+    }
 
-    
-    Container::new(final_layout)
-        .width(Length::Fill)
-        .height(Length::Fill)
-        .center_x()
-        .center_y()
-        .into()
-}
-    
+    /// Artifact directories grouped by project, largest project first, each
+    /// with reveal and trash buttons.
+    /// The directories of disk `index` holding the most files, optionally
+    /// only those whose files are small on average. Clicking one scopes the
+    /// disk to it.
+    fn most_files_panel<'a>(&self, index: usize, disk: &DiskInfo) -> Element<'a, Message> {
+        let max_avg_mb = self.most_files_max_avg_kb.trim().parse::<f64>().ok().map(|kb| kb / 1024.0);
+        let mut panel = Column::new().spacing(2).push(
+            Row::new()
+                .spacing(10)
+                .push(Text::new(tr("most-files-max-avg-label")))
+                .push(TextInput::new("", &self.most_files_max_avg_kb)
+                    .on_input(Message::MostFilesMaxAvgChanged)
+                    .padding(5)
+                    .width(Length::Fixed(80.0))),
+        );
+        let dirs = disk
+            .most_files
+            .iter()
+            .filter(|dir| max_avg_mb.is_none_or(|max| dir.average_size_mb() < max))
+            .take(MOST_FILES_SHOWN);
+        let mut shown = 0;
+        for dir in dirs {
+            shown += 1;
+            panel = panel.push(
+                Row::new()
+                    .spacing(10)
+                    .push(
+                        Button::new(Text::new(util::truncate_middle(&dir.path, MAX_PATH_CHARS)).size(14))
+                            .style(theme::Button::Text)
+                            .on_press(Message::SetScope(index, Some(PathBuf::from(&dir.path))))
+                            .width(Length::Fill),
+                    )
+                    .push(Text::new(tr_args("most-files-row", &[
+                        ("count", format_count(dir.file_count)),
+                        ("size", format_size_mb(dir.size_mb)),
+                        ("average", format_size_mb(dir.average_size_mb())),
+                    ])).size(14)),
+            );
+        }
+        if shown == 0 {
+            panel = panel.push(Text::new(tr("most-files-none")).size(14));
+        }
+        panel.into()
+    }
 
-    fn subscription(&self) -> Subscription<Self::Message> {
+    fn artifacts_panel<'a>(&self, artifacts: &[&scan::ArtifactDir]) -> Element<'a, Message> {
+        let mut by_project: HashMap<&str, Vec<&scan::ArtifactDir>> = HashMap::new();
+        for artifact in artifacts {
+            by_project.entry(artifact.project_root.as_str()).or_default().push(artifact);
+        }
+        let mut projects: Vec<(&str, f64, Vec<&scan::ArtifactDir>)> = by_project
+            .into_iter()
+            .map(|(root, dirs)| (root, dirs.iter().map(|dir| dir.size_mb).sum(), dirs))
+            .collect();
+        projects.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+
+        let total_mb: f64 = artifacts.iter().map(|artifact| artifact.size_mb).sum();
+        let mut panel = Column::new().spacing(5).push(
+            Text::new(tr_args("artifacts-header", &[
+                ("size", format_size_mb(total_mb)),
+                ("count", format_count(projects.len())),
+            ]))
+            .size(18),
+        );
+        for (root, size_mb, dirs) in projects.iter().take(MAX_ARTIFACT_PROJECTS_SHOWN) {
+            panel = panel.push(Text::new(tr_args("artifacts-project", &[
+                ("path", util::truncate_middle(root, MAX_PATH_CHARS)),
+                ("size", format_size_mb(*size_mb)),
+            ])));
+            for dir in dirs {
+                let name = Path::new(&dir.path).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                let trashing = self.trashing_artifacts.contains(&dir.path);
+                let mut row = Row::new()
+                    .spacing(10)
+                    .push(Space::with_width(Length::Fixed(20.0)))
+                    .push(Text::new(tr_args("artifacts-dir", &[
+                        ("name", name),
+                        ("size", format_size_mb(dir.size_mb)),
+                        ("files", format_count(dir.files)),
+                    ])).width(Length::Fill))
+                    .push(Button::new(Text::new(tr("reveal-in-folder"))).style(theme::Button::Text).on_press(Message::RevealInFolder(dir.path.clone())));
+                if trash::CAN_MOVE {
+                    row = row.push(
+                        Button::new(Text::new(tr("move-to-trash")))
+                            .style(theme::Button::Destructive)
+                            .on_press_maybe((!trashing).then_some(Message::TrashArtifact(dir.path.clone()))),
+                    );
+                }
+                panel = panel.push(row);
+            }
+        }
+        if projects.len() > MAX_ARTIFACT_PROJECTS_SHOWN {
+            panel = panel.push(Text::new(tr_args("artifacts-more", &[
+                ("count", format_count(projects.len() - MAX_ARTIFACT_PROJECTS_SHOWN)),
+            ])));
+        }
+        Container::new(panel)
+            .padding(10)
+            .width(Length::Fill)
+            .style(theme::Container::Custom(Box::new(PanelStyle)))
+            .into()
+    }
+
+    /// Exports every disk as `format`, into one file or, when exporting per
+    /// disk, into a folder the user picks first.
+    fn export(&mut self, format: ExportFormat) -> Command<Message> {
+        self.exporting = true;
+        if self.export_per_disk {
+            return pick_export_folder(format);
+        }
+        let disks = Arc::clone(&self.disks);
+        let compress = self.settings.compress_exports;
+        let nearly_full_percent = self.settings.nearly_full_percent;
+        let path = export_path(PathBuf::from(format.default_path()), compress);
+        let progress = self.start_export_progress(format.row_count(&disks));
+        export_on_worker(move || write_export(&path, format, &disks, compress, nearly_full_percent, &progress))
+    }
+
+    /// Hands a fresh progress counter to an export about to start and keeps
+    /// it for the status bar.
+    fn start_export_progress(&mut self, total_rows: usize) -> Arc<ExportProgress> {
+        let progress = Arc::new(ExportProgress { total_rows, ..ExportProgress::default() });
+        self.export_progress = Some(Arc::clone(&progress));
+        progress
+    }
+
+    fn sample_app_memory(&mut self) {
+        let Ok(pid) = sysinfo::get_current_pid() else {
+            return;
+        };
+        self.resource_monitor.refresh_process(pid);
+        self.app_memory = self.resource_monitor.process(pid).map(|process| process.memory());
+    }
+
+    fn resource_sparklines(&self) -> Element<'_, Message> {
+        // The first CPU sample has no previous refresh to compare against, so it reads 0.
+        let cpu = self.cpu_history.back().copied().unwrap_or(0.0);
+        let mem = self.mem_history.back().copied().unwrap_or(0.0);
+        let total_mem = self.resource_monitor.total_memory() as f32;
+
+        Row::new()
+            .spacing(20)
+            .push(Column::new()
+                .spacing(2)
+                .push(Text::new(tr_args("cpu-usage", &[("percent", format_number(cpu as f64, 0))])).size(14))
+                .push(Sparkline::new(self.cpu_history.iter().copied(), 100.0, iced::Color::from_rgb(0.2, 0.5, 0.9)).view()))
+            .push(Column::new()
+                .spacing(2)
+                .push(Text::new(tr_args("memory-usage", &[("size", format_gb(mem / 1_073_741_824.0))])).size(14))
+                .push(Sparkline::new(self.mem_history.iter().map(|&bytes| bytes as f32), total_mem, iced::Color::from_rgb(0.3, 0.7, 0.3)).view()))
+            .into()
+    }
+
+    /// Re-walks `root` on a background thread and splices the fresh files into
+    /// `disk_name` when done. Rescans may run side by side as long as their
+    /// subtrees don't overlap.
+    fn start_rescan(&mut self, disk_name: String, mount_point: PathBuf, root: PathBuf) -> Command<Message> {
         if self.scanning {
-            iced::time::every(Duration::from_secs(1)).map(|_| Message::Tick)
-        } else {
-            Subscription::none()
+            self.error_message = Some(tr("full-scan-running"));
+            return Command::none();
+        }
+        if self.active_rescans.iter().any(|active| active.starts_with(&root) || root.starts_with(active)) {
+            self.error_message = Some(tr_args("rescan-overlap", &[("path", root.display().to_string())]));
+            return Command::none();
+        }
+
+        self.active_rescans.push(root.clone());
+        let config = ScanConfig { skip_mounts: scan::mount_points(), ..self.scan_config.clone() };
+        let (tx, rx) = std::sync::mpsc::channel();
+
+        let walk_root = root.clone();
+        thread::spawn(move || {
+            config.priority.apply_to_current_thread();
+            if !walk_root.exists() {
+                let _ = tx.send(Err(tr_args("path-missing", &[("path", walk_root.display().to_string())])));
+                return;
+            }
+            let walk = scan::walk_files(&walk_root, &config, &ScanProgress::default(), Instant::now());
+            let _ = tx.send(Ok(Box::new(scan::SubtreeScan { walk, space: scan::disk_space(&mount_point) })));
+        });
+
+        Command::perform(async move {
+            rx.recv().unwrap_or_else(|_| Err(tr("rescan-worker-stopped")))
+        }, move |result| Message::Rescanned(disk_name, root, result))
+    }
+}
+
+const JSON_EXPORT_PATH: &str = "disk_usage.json";
+const CSV_EXPORT_PATH: &str = "disk_usage.csv";
+const YAML_EXPORT_PATH: &str = "disk_usage.yaml";
+const TOML_EXPORT_PATH: &str = "disk_usage.toml";
+const XLSX_EXPORT_PATH: &str = "disk_usage.xlsx";
+const MARKDOWN_EXPORT_PATH: &str = "disk_usage.md";
+const NDJSON_EXPORT_PATH: &str = "disk_usage.ndjson";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+enum ExportFormat {
+    #[default]
+    Json,
+    Csv,
+    Yaml,
+    /// Per-disk summary only; TOML handles arrays of millions of files poorly.
+    Toml,
+    Xlsx,
+    Markdown,
+    /// One line per file, streamed, for log tools such as Splunk or Elasticsearch.
+    Ndjson,
+}
+
+impl ExportFormat {
+    const ALL: &[ExportFormat] = &[
+        ExportFormat::Json,
+        ExportFormat::Csv,
+        ExportFormat::Yaml,
+        ExportFormat::Toml,
+        ExportFormat::Xlsx,
+        ExportFormat::Markdown,
+        ExportFormat::Ndjson,
+    ];
+
+    fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+            ExportFormat::Yaml => "yaml",
+            ExportFormat::Toml => "toml",
+            ExportFormat::Xlsx => "xlsx",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+
+    fn default_path(self) -> &'static str {
+        match self {
+            ExportFormat::Json => JSON_EXPORT_PATH,
+            ExportFormat::Csv => CSV_EXPORT_PATH,
+            ExportFormat::Yaml => YAML_EXPORT_PATH,
+            ExportFormat::Toml => TOML_EXPORT_PATH,
+            ExportFormat::Xlsx => XLSX_EXPORT_PATH,
+            ExportFormat::Markdown => MARKDOWN_EXPORT_PATH,
+            ExportFormat::Ndjson => NDJSON_EXPORT_PATH,
+        }
+    }
+
+    /// File rows `write` reports to its progress; summaries report none.
+    fn row_count(self, disks: &[DiskInfo]) -> usize {
+        match self {
+            ExportFormat::Json | ExportFormat::Csv | ExportFormat::Yaml | ExportFormat::Ndjson => {
+                disks.iter().map(|disk| disk.files.len()).sum()
+            }
+            ExportFormat::Xlsx => disks.iter().map(|disk| disk.files.len().min(XLSX_MAX_ROWS)).sum(),
+            ExportFormat::Toml | ExportFormat::Markdown => 0,
+        }
+    }
+
+    fn write(
+        self,
+        disks: &[DiskInfo],
+        out: &mut dyn Write,
+        nearly_full_percent: u8,
+        progress: &Arc<ExportProgress>,
+    ) -> Result<(), String> {
+        match self {
+            ExportFormat::Json => progress.counting_files(|| write_json(disks, out)),
+            ExportFormat::Csv => write_csv(disks, out, progress),
+            ExportFormat::Yaml => progress.counting_files(|| write_yaml(disks, out)),
+            ExportFormat::Toml => write_toml(disks, out),
+            ExportFormat::Xlsx => write_xlsx(disks, out, progress),
+            ExportFormat::Markdown => {
+                let report = report::markdown_report(disks, nearly_full_percent);
+                out.write_all(report.as_bytes()).map_err(|e| e.to_string())
+            }
+            ExportFormat::Ndjson => write_ndjson(disks, out, progress),
+        }
+    }
+}
+
+impl std::fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let key = match self {
+            ExportFormat::Json => "format-json",
+            ExportFormat::Csv => "format-csv",
+            ExportFormat::Yaml => "format-yaml",
+            ExportFormat::Toml => "format-toml",
+            ExportFormat::Xlsx => "format-xlsx",
+            ExportFormat::Markdown => "format-markdown",
+            ExportFormat::Ndjson => "format-ndjson",
+        };
+        write!(f, "{}", tr(key))
+    }
+}
+
+/// Appends `.gz` when the export is compressed.
+fn export_path(path: PathBuf, compress: bool) -> PathBuf {
+    if compress {
+        let mut name = path.into_os_string();
+        name.push(".gz");
+        PathBuf::from(name)
+    } else {
+        path
+    }
+}
+
+/// How often the status bar re-reads a running export's row count.
+const EXPORT_PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Shared between an export's worker thread and the status bar.
+#[derive(Debug, Default)]
+struct ExportProgress {
+    rows_written: AtomicUsize,
+    /// Zero when the format writes a summary rather than a row per file.
+    total_rows: usize,
+    cancelled: AtomicBool,
+}
+
+thread_local! {
+    /// The export the current thread is serializing, for `serialize_files`.
+    static FILE_PROGRESS: RefCell<Option<Arc<ExportProgress>>> = const { RefCell::new(None) };
+}
+
+impl ExportProgress {
+    fn rows_written(&self) -> usize {
+        self.rows_written.load(Ordering::Relaxed)
+    }
+
+    fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Counts one written row. Fails once the export is cancelled, which
+    /// stops the writer at its next row.
+    fn row(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            return Err(tr("export-cancelled"));
+        }
+        self.rows_written.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Runs a serde export with every `DiskInfo` file counted as a row.
+    fn counting_files<T>(self: &Arc<Self>, write: impl FnOnce() -> T) -> T {
+        FILE_PROGRESS.with(|current| *current.borrow_mut() = Some(Arc::clone(self)));
+        let result = write();
+        FILE_PROGRESS.with(|current| *current.borrow_mut() = None);
+        result
+    }
+}
+
+/// Serializes `DiskInfo::files` like a plain `Vec`, reporting each file to
+/// the export running on this thread, if any.
+fn serialize_files<S: serde::Serializer>(files: &[FileInfo], serializer: S) -> Result<S::Ok, S::Error> {
+    use serde::ser::{Error, SerializeSeq};
+
+    FILE_PROGRESS.with(|current| {
+        let progress = current.borrow();
+        let mut seq = serializer.serialize_seq(Some(files.len()))?;
+        for file in files {
+            if let Some(progress) = progress.as_ref() {
+                progress.row().map_err(S::Error::custom)?;
+            }
+            seq.serialize_element(file)?;
+        }
+        seq.end()
+    })
+}
+
+/// Runs `export` on a thread of its own, so writing a large scan doesn't
+/// hold up the executor the UI's commands run on.
+fn export_on_worker(export: impl FnOnce() -> Result<String, String> + Send + 'static) -> Command<Message> {
+    let (tx, rx) = std::sync::mpsc::channel();
+    thread::spawn(move || {
+        let _ = tx.send(export());
+    });
+    Command::perform(
+        async move { rx.recv().unwrap_or_else(|_| Err(tr("export-worker-stopped"))) },
+        Message::ExportCompleted,
+    )
+}
+
+/// Streams `disks` to `path`, through gzip when `compress` is set, hashing the
+/// bytes on their way to disk. Returns the hex SHA-256 of the file as written,
+/// which is also stored in the `.sha256` sidecar. `nearly_full_percent` is
+/// the Markdown report's threshold.
+fn write_export(
+    path: &Path,
+    format: ExportFormat,
+    disks: &[DiskInfo],
+    compress: bool,
+    nearly_full_percent: u8,
+    progress: &Arc<ExportProgress>,
+) -> Result<String, String> {
+    write_checksummed(path, compress, |out| format.write(disks, out, nearly_full_percent, progress))
+}
+
+/// Runs `write` into `path` the way `write_export` does, with optional gzip
+/// and the checksum sidecar. A failed or cancelled write leaves no partial
+/// file behind.
+fn write_checksummed(
+    path: &Path,
+    compress: bool,
+    write: impl FnOnce(&mut dyn Write) -> Result<(), String>,
+) -> Result<String, String> {
+    let result = write_checksummed_file(path, compress, write);
+    if result.is_err() {
+        let _ = fs::remove_file(path);
+    }
+    result
+}
+
+fn write_checksummed_file(
+    path: &Path,
+    compress: bool,
+    write: impl FnOnce(&mut dyn Write) -> Result<(), String>,
+) -> Result<String, String> {
+    let file = BufWriter::new(File::create(path).map_err(|e| e.to_string())?);
+    let mut hashing = HashingWriter::new(file);
+    if compress {
+        let mut encoder = GzEncoder::new(&mut hashing, Compression::default());
+        write(&mut encoder)?;
+        encoder.finish().map_err(|e| e.to_string())?;
+    } else {
+        write(&mut hashing)?;
+    }
+    let (mut file, checksum) = hashing.finish();
+    file.flush().map_err(|e| e.to_string())?;
+    checksum::write_sidecar(path, &checksum)?;
+    Ok(checksum)
+}
+
+/// Writes one analysis of `disks` to `path`: CSV for a `.csv` file name,
+/// otherwise JSON wrapped in a `ReportEnvelope`.
+fn export_analysis(report: AnalysisReport, disks: &[DiskInfo], path: PathBuf, compress: bool) -> Result<String, String> {
+    let as_csv = path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("csv"));
+    let files = disks.iter().flat_map(|disk| &disk.files);
+    write_checksummed(&export_path(path, compress), compress, |out| match report {
+        AnalysisReport::Duplicates => {
+            let sets = analysis::duplicate_sets(files);
+            if as_csv {
+                analysis::write_duplicates_csv(&sets, out)
+            } else {
+                serde_json::to_writer_pretty(out, &ReportEnvelope::new(report, &sets)).map_err(|e| e.to_string())
+            }
+        }
+        AnalysisReport::Reclaimable => {
+            let groups = analysis::reclaimable_groups(files);
+            if as_csv {
+                analysis::write_reclaimable_csv(&groups, out)
+            } else {
+                serde_json::to_writer_pretty(out, &ReportEnvelope::new(report, &groups)).map_err(|e| e.to_string())
+            }
+        }
+    })
+}
+
+fn pick_analysis_file(report: AnalysisReport) -> Command<Message> {
+    Command::perform(
+        async move {
+            rfd::AsyncFileDialog::new()
+                .add_filter("JSON", &["json"])
+                .add_filter("CSV", &["csv"])
+                .set_file_name(report.default_file_name())
+                .save_file()
+                .await
+                .map(|file| file.path().to_path_buf())
+        },
+        move |path| Message::AnalysisExportPicked(report, path),
+    )
+}
+
+/// Writes `<disk-name>_<date>.<ext>` into `dir` for every disk, using the same
+/// writers as the single-file exports. Returns each file name with its
+/// checksum or the error that stopped it; one failure doesn't stop the rest.
+fn export_per_disk(
+    disks: &[DiskInfo],
+    format: ExportFormat,
+    dir: PathBuf,
+    compress: bool,
+    nearly_full_percent: u8,
+    progress: &Arc<ExportProgress>,
+) -> Vec<(String, Result<String, String>)> {
+    let date = util::today_iso();
+    let mut used_stems = HashSet::new();
+    disks
+        .iter()
+        .map(|disk| {
+            let mut stem = [disk.display_name(), disk.mount_point.clone()]
+                .iter()
+                .map(|name| util::sanitize_file_name(name))
+                .find(|name| !name.is_empty())
+                .unwrap_or_else(|| "disk".to_string());
+            // Several disks can share a device name (e.g. `overlay`), also
+            // across imported machines.
+            let base = stem.clone();
+            let mut suffix = 2;
+            while !used_stems.insert(stem.clone()) {
+                stem = format!("{}-{}", base, suffix);
+                suffix += 1;
+            }
+            let path = export_path(dir.join(format!("{}_{}.{}", stem, date, format.extension())), compress);
+            let file_name = path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+            let result = write_export(&path, format, std::slice::from_ref(disk), compress, nearly_full_percent, progress);
+            (file_name, result)
+        })
+        .collect()
+}
+
+/// Version of the JSON and YAML export layout. Bump it when `DiskInfo` or
+/// `FileInfo` change in a way older files can't be read as, and add a
+/// migration from the previous version to `parse_export`.
+const SCHEMA_VERSION: u32 = 2;
+
+/// JSON and YAML exports are wrapped in this. The host name lets merged
+/// imports tell machines apart; disks that were themselves imported keep
+/// their own `source`.
+#[derive(Serialize, Deserialize)]
+struct ExportEnvelope<T> {
+    schema_version: u32,
+    hostname: String,
+    exported_at: u64,
+    disks: T,
+}
+
+impl<'a> ExportEnvelope<&'a [DiskInfo]> {
+    fn new(disks: &'a [DiskInfo]) -> Self {
+        ExportEnvelope { schema_version: SCHEMA_VERSION, hostname: util::hostname(), exported_at: util::unix_now(), disks }
+    }
+}
+
+/// The duplicate and reclaimable reports carry the same metadata as the main
+/// export, with their entries in place of the disks.
+#[derive(Serialize)]
+struct ReportEnvelope<'a, T> {
+    schema_version: u32,
+    hostname: String,
+    exported_at: u64,
+    report: AnalysisReport,
+    entries: &'a [T],
+}
+
+impl<'a, T> ReportEnvelope<'a, T> {
+    fn new(report: AnalysisReport, entries: &'a [T]) -> Self {
+        ReportEnvelope { schema_version: SCHEMA_VERSION, hostname: util::hostname(), exported_at: util::unix_now(), report, entries }
+    }
+}
+
+/// Schema 1: a bare array of disks, or the first envelope, which had no
+/// `schema_version` yet.
+#[derive(Deserialize)]
+struct ExportEnvelopeV1 {
+    #[serde(default)]
+    hostname: String,
+    #[serde(default)]
+    exported_at: u64,
+    disks: Vec<DiskInfo>,
+}
+
+fn migrate_v1_to_v2(envelope: ExportEnvelopeV1) -> ExportEnvelope<Vec<DiskInfo>> {
+    ExportEnvelope {
+        schema_version: 2,
+        hostname: envelope.hostname,
+        exported_at: envelope.exported_at,
+        disks: envelope.disks,
+    }
+}
+
+/// Every layout an export has had, told apart while it is read. Variants are
+/// tried in order, so an envelope whose disks don't read as this version's
+/// lands in `Unreadable` with just its version.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AnyExport {
+    /// Schema 1 as a bare array of disks.
+    Bare(Vec<DiskInfo>),
+    Versioned(ExportEnvelope<Vec<DiskInfo>>),
+    Unreadable { schema_version: u32 },
+    /// Schema 1's envelope, which had no `schema_version` yet.
+    V1(ExportEnvelopeV1),
+}
+
+/// Takes an export of any known schema version, migrating older ones
+/// forward step by step. Files from a newer version of the app are refused
+/// rather than half-read.
+fn parse_export(export: AnyExport) -> Result<ExportEnvelope<Vec<DiskInfo>>, String> {
+    match export {
+        AnyExport::Bare(disks) => Ok(migrate_v1_to_v2(ExportEnvelopeV1 { hostname: String::new(), exported_at: 0, disks })),
+        AnyExport::V1(v1) => Ok(migrate_v1_to_v2(v1)),
+        AnyExport::Versioned(ExportEnvelope { schema_version: 1, hostname, exported_at, disks }) => {
+            Ok(migrate_v1_to_v2(ExportEnvelopeV1 { hostname, exported_at, disks }))
+        }
+        AnyExport::Versioned(envelope) if envelope.schema_version == SCHEMA_VERSION => Ok(envelope),
+        AnyExport::Versioned(ExportEnvelope { schema_version, .. }) | AnyExport::Unreadable { schema_version }
+            if schema_version > SCHEMA_VERSION =>
+        {
+            Err(tr_args("import-newer-schema", &[
+                ("version", schema_version.to_string()),
+                ("supported", SCHEMA_VERSION.to_string()),
+            ]))
+        }
+        _ => Err(tr("import-not-an-export")),
+    }
+}
+
+fn write_json(disks: &[DiskInfo], out: &mut dyn Write) -> Result<(), String> {
+    serde_json::to_writer_pretty(out, &ExportEnvelope::new(disks)).map_err(|e| e.to_string())
+}
+
+/// One NDJSON line: a file with the disk it lives on.
+#[derive(Serialize)]
+struct NdjsonRecord<'a> {
+    disk: &'a str,
+    mount_point: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    source: Option<&'a str>,
+    #[serde(flatten)]
+    file: &'a FileInfo,
+}
+
+/// Writes a line per file as it goes instead of building the whole document,
+/// so memory stays flat however many files were scanned. Each line is flushed
+/// so a consumer tailing the file sees it straight away.
+fn write_ndjson(disks: &[DiskInfo], out: &mut dyn Write, progress: &ExportProgress) -> Result<(), String> {
+    for disk in disks {
+        for file in &disk.files {
+            progress.row()?;
+            let record = NdjsonRecord { disk: &disk.name, mount_point: &disk.mount_point, source: disk.source.as_deref(), file };
+            serde_json::to_writer(&mut *out, &record).map_err(|e| e.to_string())?;
+            out.write_all(b"\n").map_err(|e| e.to_string())?;
+            out.flush().map_err(|e| e.to_string())?;
         }
     }
+    Ok(())
+}
+
+fn write_yaml(disks: &[DiskInfo], out: &mut dyn Write) -> Result<(), String> {
+    serde_yaml::to_writer(out, &ExportEnvelope::new(disks)).map_err(|e| e.to_string())
+}
+
+#[derive(Serialize)]
+struct TomlSummary<'a> {
+    disks: Vec<DiskSummary<'a>>,
+}
+
+#[derive(Serialize)]
+struct DiskSummary<'a> {
+    name: &'a str,
+    mount_point: &'a str,
+    total_space_gb: f64,
+    used_space_gb: f64,
+    file_count: usize,
+    total_file_size_mb: f64,
+    truncated: bool,
+    /// File counts per size range, smallest first.
+    size_histogram: [usize; 6],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    skipped_reason: Option<&'a str>,
+}
+
+fn write_toml(disks: &[DiskInfo], out: &mut dyn Write) -> Result<(), String> {
+    let summary = TomlSummary {
+        disks: disks
+            .iter()
+            .map(|disk| DiskSummary {
+                name: &disk.name,
+                mount_point: &disk.mount_point,
+                total_space_gb: disk.total_space,
+                used_space_gb: disk.used_space,
+                file_count: disk.total_files(),
+                total_file_size_mb: disk.files.iter().map(|file| file.size_mb).sum(),
+                truncated: disk.truncated,
+                size_histogram: disk.size_histogram,
+                skipped_reason: disk.skipped_reason.as_deref(),
+            })
+            .collect(),
+    };
+    let text = toml::to_string_pretty(&summary).map_err(|e| e.to_string())?;
+    out.write_all(text.as_bytes()).map_err(|e| e.to_string())
+}
+
+/// Rows per disk sheet, well under Excel's 1,048,576 so workbooks stay
+/// openable; the cut drops the smallest files.
+const XLSX_MAX_ROWS: usize = 500_000;
+const XLSX_SUMMARY_SHEET: &str = "Summary";
+
+/// Excel sheet names: no `[]:*?/\`, at most 31 characters, and unique
+/// ignoring case. The summary sheet's name is reserved.
+fn xlsx_sheet_names(disks: &[DiskInfo]) -> Vec<String> {
+    let mut used: HashSet<String> = HashSet::from([XLSX_SUMMARY_SHEET.to_lowercase()]);
+    disks
+        .iter()
+        .map(|disk| {
+            let cleaned: String = [&disk.name, &disk.mount_point]
+                .iter()
+                .map(|name| {
+                    name.chars()
+                        .map(|c| if matches!(c, '[' | ']' | ':' | '*' | '?' | '/' | '\\') { '_' } else { c })
+                        .collect::<String>()
+                        .trim_matches(|c: char| c == '_' || c == '\'' || c.is_whitespace())
+                        .to_string()
+                })
+                .find(|name| !name.is_empty())
+                .unwrap_or_else(|| "Disk".to_string());
+            let base: String = cleaned.chars().take(31).collect();
+            let mut name = base.clone();
+            let mut suffix = 2;
+            while !used.insert(name.to_lowercase()) {
+                let tag = format!(" ({})", suffix);
+                name = format!("{}{}", base.chars().take(31 - tag.len()).collect::<String>(), tag);
+                suffix += 1;
+            }
+            name
+        })
+        .collect()
 }
 
-fn export_to_json(disks: Vec<DiskInfo>) -> Result<(), String> {
-    serde_json::to_writer_pretty(&File::create("disk_usage.json").map_err(|e| e.to_string())?, &disks)
-        .map_err(|e| e.to_string())
+fn write_xlsx(disks: &[DiskInfo], out: &mut dyn Write, progress: &ExportProgress) -> Result<(), String> {
+    use rust_xlsxwriter::{Format, Workbook};
+
+    let xlsx_err = |e: rust_xlsxwriter::XlsxError| e.to_string();
+    let bold = Format::new().set_bold();
+    let mut workbook = Workbook::new();
+
+    let summary = workbook.add_worksheet();
+    summary.set_name(XLSX_SUMMARY_SHEET).map_err(xlsx_err)?;
+    for (col, header) in ["Disk", "Mount point", "Capacity (GB)", "Used (GB)", "Free (GB)", "Files", "Note"].iter().enumerate() {
+        summary.write_string_with_format(0, col as u16, *header, &bold).map_err(xlsx_err)?;
+    }
+    for (i, disk) in disks.iter().enumerate() {
+        let row = i as u32 + 1;
+        summary.write_string(row, 0, &disk.name).map_err(xlsx_err)?;
+        summary.write_string(row, 1, &disk.mount_point).map_err(xlsx_err)?;
+        summary.write_number(row, 2, disk.total_space).map_err(xlsx_err)?;
+        summary.write_number(row, 3, disk.used_space).map_err(xlsx_err)?;
+        summary.write_number(row, 4, disk.total_space - disk.used_space).map_err(xlsx_err)?;
+        summary.write_number(row, 5, disk.total_files() as f64).map_err(xlsx_err)?;
+        if disk.files.len() > XLSX_MAX_ROWS {
+            let note = format!("File sheet lists the largest {} of {} files", XLSX_MAX_ROWS, disk.total_files());
+            summary.write_string(row, 6, &note).map_err(xlsx_err)?;
+        }
+    }
+    summary.set_freeze_panes(1, 0).map_err(xlsx_err)?;
+    summary.autofilter(0, 0, disks.len() as u32, 6).map_err(xlsx_err)?;
+
+    for (disk, sheet_name) in disks.iter().zip(xlsx_sheet_names(disks)) {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(&sheet_name).map_err(xlsx_err)?;
+        for (col, header) in ["Path", "Size (MB)", "Temporary", "Symlink target"].iter().enumerate() {
+            sheet.write_string_with_format(0, col as u16, *header, &bold).map_err(xlsx_err)?;
+        }
+        let files = scan::largest_files(&disk.files, XLSX_MAX_ROWS);
+        for (i, file) in files.iter().enumerate() {
+            progress.row()?;
+            let row = i as u32 + 1;
+            sheet.write_string(row, 0, &*file.path).map_err(xlsx_err)?;
+            sheet.write_number(row, 1, file.size_mb).map_err(xlsx_err)?;
+            sheet.write_boolean(row, 2, file.is_temp).map_err(xlsx_err)?;
+            if let Some(ref target) = file.symlink_target {
+                sheet.write_string(row, 3, target).map_err(xlsx_err)?;
+            }
+        }
+        sheet.set_freeze_panes(1, 0).map_err(xlsx_err)?;
+        sheet.autofilter(0, 0, files.len() as u32, 3).map_err(xlsx_err)?;
+    }
+
+    let bytes = workbook.save_to_buffer().map_err(xlsx_err)?;
+    out.write_all(&bytes).map_err(|e| e.to_string())
 }
 
-fn export_to_csv(disks: Vec<DiskInfo>) -> Result<(), String> {
-    let mut wtr = Writer::from_writer(File::create("disk_usage.csv").map_err(|e| e.to_string())?);
+const CSV_HEADER: [&str; 6] = ["disk_name", "total_space_gb", "used_space_gb", "file_path", "size", "unit"];
+const CSV_SHARE_HEADER: [&str; 2] = ["share_percent", "cumulative_percent"];
+
+/// Set once at startup from `--csv-no-header`.
+static CSV_NO_HEADER: AtomicBool = AtomicBool::new(false);
+
+/// One record per file. `csv::Writer` quotes fields as needed, so paths with
+/// commas or quotes stay in one column.
+fn write_csv(disks: &[DiskInfo], out: &mut dyn Write, progress: &ExportProgress) -> Result<(), String> {
+    let mut wtr = Writer::from_writer(out);
+    // Filtered exports carry each file's share; only then get the extra columns.
+    let with_shares = disks.iter().flat_map(|disk| &disk.files).any(|file| file.share_percent.is_some());
+    if !CSV_NO_HEADER.load(Ordering::Relaxed) {
+        let mut header = CSV_HEADER.to_vec();
+        if with_shares {
+            header.extend(CSV_SHARE_HEADER);
+        }
+        wtr.write_record(header).map_err(|e| e.to_string())?;
+    }
     for disk in disks {
-        for file in disk.files {
-            wtr.write_record(&[
-                &disk.name,
-                &format!("{:.2}", disk.total_space),
-                &format!("{:.2}", disk.used_space),
-                &file.path,
-                &format!("{:.2}", if file.size_mb >= 1000.0 { file.size_mb / 1024.0 } else { file.size_mb }),
-                &(if file.size_mb >= 1000.0 { "GB" } else { "MB" }).to_string(), 
-            ]).map_err(|e| e.to_string())?;
+        for file in &disk.files {
+            progress.row()?;
+            let mut record = vec![
+                disk.name.clone(),
+                format!("{:.2}", disk.total_space),
+                format!("{:.2}", disk.used_space),
+                file.path.to_string(),
+                format!("{:.2}", if file.size_mb >= 1000.0 { file.size_mb / 1024.0 } else { file.size_mb }),
+                (if file.size_mb >= 1000.0 { "GB" } else { "MB" }).to_string(),
+            ];
+            if with_shares {
+                record.push(file.share_percent.map(|share| format!("{:.1}", share)).unwrap_or_default());
+                record.push(file.cumulative_percent.map(|share| format!("{:.1}", share)).unwrap_or_default());
+            }
+            wtr.write_record(&record).map_err(|e| e.to_string())?;
         }
     }
     wtr.flush().map_err(|e| e.to_string())
 }
+
+fn pick_export_folder(format: ExportFormat) -> Command<Message> {
+    Command::perform(
+        async { rfd::AsyncFileDialog::new().pick_folder().await.map(|folder| folder.path().to_path_buf()) },
+        move |folder| Message::ExportFolderPicked(format, folder),
+    )
+}
+
+/// Loads a JSON export after checking it against its sidecar, with a warning
+/// to show when there was no sidecar to check. Gzip is detected from the
+/// file's magic bytes, so a renamed file still loads. Every disk is tagged
+/// with the machine it came from: the envelope's host name, or the file name
+/// for older exports that don't record one. Older schema versions are
+/// migrated by `parse_export`.
+fn import_from_json(path: &Path) -> Result<(Vec<DiskInfo>, Option<String>), String> {
+    let warning = (!checksum::verify(path)?)
+        .then(|| tr_args("import-unverified", &[("path", checksum::sidecar_path(path).display().to_string())]));
+
+    let mut reader = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
+    let gzipped = reader.fill_buf().map_err(|e| e.to_string())?.starts_with(&[0x1f, 0x8b]);
+    let export: Result<AnyExport, serde_json::Error> = if gzipped {
+        serde_json::from_reader(BufReader::new(GzDecoder::new(reader)))
+    } else {
+        serde_json::from_reader(reader)
+    };
+    // Valid JSON of some other shape matches none of the layouts.
+    let export = export.map_err(|e| if e.is_data() { tr("import-not-an-export") } else { e.to_string() })?;
+
+    let ExportEnvelope { hostname, mut disks, .. } = parse_export(export)?;
+    let hostname = if hostname.is_empty() {
+        path.file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default()
+    } else {
+        hostname
+    };
+    for disk in &mut disks {
+        disk.source.get_or_insert_with(|| hostname.clone());
+        disk.clamp_space();
+    }
+    Ok((disks, warning))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fixture_disks() -> Vec<DiskInfo> {
+        serde_json::from_value(serde_json::json!([
+            {
+                "name": "/dev/sda1",
+                "mount_point": "/",
+                "total_space": 500.0,
+                "used_space": 320.5,
+                "files": [
+                    { "path": "/var/log/syslog", "size_mb": 1200.0, "modified": 1_700_000_000 },
+                    { "path": "/home/ana/video_final.mp4", "size_mb": 850.25 },
+                    { "path": "/home/ana/notes.txt", "size_mb": 0.01, "is_hidden": true },
+                ],
+            },
+            {
+                "name": "/dev/sdb1",
+                "mount_point": "/data",
+                "total_space": 1000.0,
+                "used_space": 10.0,
+                "files": [{ "path": "/data/backup.tar", "size_mb": 9000.0, "is_archive": true }],
+            },
+        ]))
+        .unwrap()
+    }
+
+    fn as_json(disks: &[DiskInfo]) -> serde_json::Value {
+        serde_json::to_value(disks).unwrap()
+    }
+
+    #[test]
+    fn compressed_json_export_round_trips() {
+        let dir = util::test_dir("gzip-round-trip");
+        let disks = fixture_disks();
+        let path = export_path(dir.join("scan.json"), true);
+        assert_eq!(path.extension().unwrap(), "gz");
+        write_export(&path, ExportFormat::Json, &disks, true, 90, &Arc::default()).unwrap();
+        assert_eq!(&fs::read(&path).unwrap()[..2], &[0x1f, 0x8b]);
+
+        let (mut imported, warning) = import_from_json(&path).unwrap();
+        assert_eq!(warning, None);
+        for disk in &mut imported {
+            assert!(disk.source.take().is_some());
+        }
+        assert_eq!(as_json(&imported), as_json(&disks));
+    }
+
+    #[test]
+    fn yaml_export_keeps_every_file() {
+        let disks = fixture_disks();
+        let mut out = Vec::new();
+        write_yaml(&disks, &mut out).unwrap();
+        let envelope: ExportEnvelope<Vec<DiskInfo>> = serde_yaml::from_slice(&out).unwrap();
+        assert_eq!(envelope.schema_version, SCHEMA_VERSION);
+        assert_eq!(as_json(&envelope.disks), as_json(&disks));
+    }
+
+    #[test]
+    fn toml_export_summarizes_each_disk() {
+        let mut out = Vec::new();
+        write_toml(&fixture_disks(), &mut out).unwrap();
+        let summary: toml::Value = toml::from_str(std::str::from_utf8(&out).unwrap()).unwrap();
+        let disks = summary["disks"].as_array().unwrap();
+        assert_eq!(disks.len(), 2);
+        assert_eq!(disks[0]["name"].as_str(), Some("/dev/sda1"));
+        assert_eq!(disks[0]["file_count"].as_integer(), Some(3));
+        assert_eq!(disks[0]["total_file_size_mb"].as_float(), Some(1200.0 + 850.25 + 0.01));
+        assert_eq!(disks[1]["used_space_gb"].as_float(), Some(10.0));
+        assert!(disks[0].get("files").is_none());
+    }
+
+    #[test]
+    fn export_progress_counts_a_million_rows() {
+        let mut disks = fixture_disks();
+        disks.truncate(1);
+        disks[0].files = vec![disks[0].files[0].clone(); 1_000_000];
+        for format in [ExportFormat::Json, ExportFormat::Csv, ExportFormat::Ndjson] {
+            let progress = Arc::new(ExportProgress { total_rows: format.row_count(&disks), ..ExportProgress::default() });
+            format.write(&disks, &mut std::io::sink(), 90, &progress).unwrap();
+            assert_eq!(progress.total_rows, 1_000_000);
+            assert_eq!(progress.rows_written(), 1_000_000);
+        }
+
+        let progress = Arc::new(ExportProgress::default());
+        progress.cancel();
+        assert!(ExportFormat::Csv.write(&disks, &mut std::io::sink(), 90, &progress).is_err());
+        assert_eq!(progress.rows_written(), 0);
+    }
+
+    #[test]
+    fn extension_growth_compares_the_same_disks() {
+        let disk = |name: &str, mount_point: &str, files: serde_json::Value| -> DiskInfo {
+            serde_json::from_value(serde_json::json!({
+                "name": name,
+                "mount_point": mount_point,
+                "total_space": 100.0,
+                "used_space": 50.0,
+                "files": files,
+            }))
+            .unwrap()
+        };
+        let old = vec![disk("/dev/sda1", "/", serde_json::json!([{ "path": "/var/log/app.log", "size_mb": 1024.0 }]))];
+        let new = vec![
+            disk("/dev/sdb1", "/data", serde_json::json!([{ "path": "/data/backup.tar", "size_mb": 9000.0 }])),
+            disk("/dev/sda1", "/", serde_json::json!([{ "path": "/var/log/app.log", "size_mb": 5120.0 }])),
+        ];
+
+        let growth = growth::compute_extension_growth(&old, &new);
+        assert_eq!(growth.len(), 1);
+        assert_eq!(growth[0].extension, ".log");
+        assert_eq!(growth[0].delta_mb, 4096.0);
+        assert_eq!((growth[0].old_count, growth[0].new_count), (1, 1));
+    }
+
+    #[test]
+    fn controls_follow_the_activity() {
+        let idle = controls_for(Activity::Idle);
+        assert!(idle.scan && idle.refresh && idle.export && idle.filters && idle.rescan);
+        assert!(!idle.cancel);
+
+        // Only one scan at a time, and only a scan can be cancelled.
+        for activity in [Activity::Scanning, Activity::Refreshing, Activity::Exporting] {
+            let controls = controls_for(activity);
+            assert!(!controls.scan && !controls.refresh && !controls.export && !controls.rescan, "{:?}", activity);
+            assert_eq!(controls.cancel, activity != Activity::Exporting, "{:?}", activity);
+        }
+
+        // A scan dims the results; a background refresh or an export leaves them usable.
+        assert!(!controls_for(Activity::Scanning).filters);
+        assert!(controls_for(Activity::Refreshing).filters);
+        assert!(controls_for(Activity::Exporting).filters);
+    }
+
+    #[test]
+    fn markdown_report_snapshot() {
+        let expected = [
+            "2 disks: 330.50 GB used of 1,500.00 GB, 1,169.50 GB free (22% full)",
+            "",
+            "4 files · 0 nearly full · last scan never",
+            "",
+            "## /dev/sda1 (`/`)",
+            "",
+            "Used 320.50 GB of 500.00 GB (64.1%), 3 files",
+            "",
+            "| Path | Size | Modified |",
+            "|---|---:|---|",
+            "| `/var/log/syslog` | 1.17 GB | 2023-11-14 |",
+            "| `/home/ana/video_final.mp4` | 850.25 MB |  |",
+            "| `/home/ana/notes.txt` | 0.01 MB |  |",
+            "",
+            "| Extension | Files | Size |",
+            "|---|---:|---:|",
+            "| `(none)` | 1 | 1.17 GB |",
+            "| `.mp4` | 1 | 850.25 MB |",
+            "| `.txt` | 1 | 0.01 MB |",
+            "",
+            "## /dev/sdb1 (`/data`)",
+            "",
+            "Used 10.00 GB of 1,000.00 GB (1.0%), 1 files",
+            "",
+            "| Path | Size | Modified |",
+            "|---|---:|---|",
+            "| `/data/backup.tar` | 8.79 GB |  |",
+            "",
+            "| Extension | Files | Size |",
+            "|---|---:|---:|",
+            "| `.tar` | 1 | 8.79 GB |",
+            "",
+            "",
+        ]
+        .join("\n");
+        assert_eq!(report::markdown_report(&fixture_disks(), 90), expected);
+    }
+
+    #[test]
+    fn markdown_report_escapes_names_and_paths() {
+        let disks: Vec<DiskInfo> = serde_json::from_value(serde_json::json!([{
+            "name": "usb_stick #2",
+            "mount_point": "/media/a|b",
+            "total_space": 16.0,
+            "used_space": 15.0,
+            "files": [{ "path": "/media/a|b/it`s.txt", "size_mb": 1.0 }],
+        }]))
+        .unwrap();
+        let report = report::markdown_report(&disks, 90);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines[2], "1 files · 1 nearly full · last scan never");
+        assert!(lines.contains(&r"## usb\_stick \#2 (`/media/a\|b`)"));
+        assert!(lines.contains(&r"| `` /media/a\|b/it`s.txt `` | 1.00 MB |  |"));
+    }
+
+    #[test]
+    fn natural_order_compares_digit_runs_by_value() {
+        let mut names = vec![
+            "file10.txt",
+            "File2.txt",
+            "file99999999999999999999999.txt",
+            "file1.txt",
+            "file02.txt",
+            "file2.txt",
+            "file",
+            "a",
+        ];
+        names.sort_by(|a, b| util::natural_cmp(a, b));
+        assert_eq!(names, [
+            "a",
+            "file",
+            "file1.txt",
+            "File2.txt",
+            "file2.txt",
+            "file02.txt",
+            "file10.txt",
+            "file99999999999999999999999.txt",
+        ]);
+        assert_eq!(util::natural_cmp("Äpfel 9", "äpfel 10"), std::cmp::Ordering::Less);
+    }
+
+    #[test]
+    fn files_without_a_modified_time_sort_last() {
+        let files: Vec<FileInfo> = serde_json::from_value(serde_json::json!([
+            { "path": "/a", "size_mb": 1.0, "modified": 200 },
+            { "path": "/b", "size_mb": 1.0 },
+            { "path": "/c", "size_mb": 1.0, "modified": 100 },
+        ]))
+        .unwrap();
+        let order = |descending| {
+            let mut rows: Vec<usize> = (0..files.len()).collect();
+            rows.sort_by_cached_key(|&i| SortKey::Modified.position(&files[i], descending));
+            rows.into_iter().map(|i| &*files[i].path).collect::<Vec<&str>>()
+        };
+        assert_eq!(order(false), ["/c", "/a", "/b"]);
+        assert_eq!(order(true), ["/a", "/c", "/b"]);
+    }
+
+    #[test]
+    fn degenerate_disk_sizes_stay_drawable() {
+        let cases = [
+            (0.0, 5.0),
+            (-10.0, 3.0),
+            (f64::NAN, 1.0),
+            (f64::INFINITY, 2.0),
+            (100.0, 250.0),
+            (100.0, -4.0),
+            (100.0, f64::NAN),
+        ];
+        for (total, used) in cases {
+            let mut disk = fixture_disks().remove(0);
+            (disk.total_space, disk.used_space) = (total, used);
+            disk.clamp_space();
+            assert!(disk.total_space.is_finite() && disk.total_space >= 0.0, "{} / {}", total, used);
+            assert!((0.0..=disk.total_space).contains(&disk.used_space), "{} / {}", total, used);
+            assert!((0.0..=100.0).contains(&disk.used_percent()), "{} / {}", total, used);
+            assert!(!format_gb(disk.total_space - disk.used_space).contains("NaN"));
+        }
+
+        // The percentage is guarded even before clamping.
+        let mut disk = fixture_disks().remove(0);
+        (disk.total_space, disk.used_space) = (0.0, 3.0);
+        assert_eq!(disk.used_percent(), 0.0);
+        (disk.total_space, disk.used_space) = (10.0, 30.0);
+        assert_eq!(disk.used_percent(), 100.0);
+
+        let mut empty = fixture_disks().remove(0);
+        (empty.total_space, empty.used_space) = (0.0, 0.0);
+        assert!(report::MachineSummary::new(&[empty], 90).headline().ends_with("(0% full)"));
+    }
+
+    #[test]
+    fn machine_summary_counts_only_local_whole_disks() {
+        let mut disks = fixture_disks();
+        let mut folder = disks[0].clone();
+        folder.name = "/home/ana".to_string();
+        folder.mount_point = "/home/ana".to_string();
+        folder.folder = true;
+        let mut imported = disks[1].clone();
+        imported.source = Some("server1".to_string());
+        disks.extend([folder, imported]);
+
+        let summary = report::MachineSummary::new(&disks, 60);
+        assert_eq!(summary.disks, 2);
+        assert_eq!(summary.total_gb, 1500.0);
+        assert_eq!(summary.used_gb, 330.5);
+        assert_eq!(summary.files, 4);
+        assert_eq!(summary.nearly_full, 1);
+    }
+}
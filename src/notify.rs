@@ -0,0 +1,16 @@
+/// Shows a native desktop notification without blocking the caller.
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos", target_os = "windows"))]
+pub fn send(summary: &str, body: &str) {
+    let summary = summary.to_string();
+    let body = body.to_string();
+    std::thread::spawn(move || {
+        let _ = notify_rust::Notification::new()
+            .appname("Disk Usage Visualizer")
+            .summary(&summary)
+            .body(&body)
+            .show();
+    });
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "freebsd", target_os = "macos", target_os = "windows")))]
+pub fn send(_summary: &str, _body: &str) {}
@@ -0,0 +1,43 @@
+//! Names for the uids the scan records, for the owner filter. A lookup can go
+//! through NSS (LDAP, sssd, ...), so each uid is resolved once and remembered
+//! for the rest of the session.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+
+/// The name of the user with `uid`, or the number itself when there is none.
+pub fn owner_name(uid: u32) -> String {
+    static NAMES: OnceLock<Mutex<HashMap<u32, String>>> = OnceLock::new();
+    let names = NAMES.get_or_init(Default::default);
+    if let Some(name) = names.lock().unwrap().get(&uid) {
+        return name.clone();
+    }
+    let name = lookup(uid).unwrap_or_else(|| uid.to_string());
+    names.lock().unwrap().insert(uid, name.clone());
+    name
+}
+
+#[cfg(unix)]
+fn lookup(uid: u32) -> Option<String> {
+    let mut buffer: Vec<libc::c_char> = vec![0; 1024];
+    loop {
+        let mut passwd: libc::passwd = unsafe { std::mem::zeroed() };
+        let mut result = std::ptr::null_mut();
+        let status = unsafe { libc::getpwuid_r(uid, &mut passwd, buffer.as_mut_ptr(), buffer.len(), &mut result) };
+        // Entries with long member lists need a bigger buffer.
+        if status == libc::ERANGE && buffer.len() < 1_048_576 {
+            buffer.resize(buffer.len() * 4, 0);
+            continue;
+        }
+        if status != 0 || result.is_null() {
+            return None;
+        }
+        let name = unsafe { std::ffi::CStr::from_ptr(passwd.pw_name) };
+        return Some(name.to_string_lossy().into_owned());
+    }
+}
+
+#[cfg(not(unix))]
+fn lookup(_uid: u32) -> Option<String> {
+    None
+}
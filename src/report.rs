@@ -0,0 +1,158 @@
+use crate::i18n::{format_count, format_date, format_date_time, format_gb, format_number, format_size_mb, tr, tr_args};
+use crate::scan;
+use crate::{DiskInfo, FileInfo};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::time::{Duration, UNIX_EPOCH};
+
+const TOP_FILES: usize = 20;
+const TOP_EXTENSIONS: usize = 10;
+
+/// Combined figures across the whole disks scanned on this machine, for the
+/// summary card and the top of the Markdown report. Folder scans sit on one
+/// of those disks and imported disks belong to other machines, so neither is
+/// counted.
+pub struct MachineSummary {
+    pub disks: usize,
+    pub total_gb: f64,
+    pub used_gb: f64,
+    pub files: usize,
+    /// Disks at or above the "nearly full" threshold.
+    pub nearly_full: usize,
+    /// Most recent `scanned_at` of any disk.
+    pub last_scan: Option<u64>,
+}
+
+impl MachineSummary {
+    pub fn new(disks: &[DiskInfo], nearly_full_percent: u8) -> Self {
+        let disks: Vec<&DiskInfo> = disks.iter().filter(|disk| disk.source.is_none() && !disk.folder).collect();
+        MachineSummary {
+            disks: disks.len(),
+            total_gb: disks.iter().map(|disk| disk.total_space).sum(),
+            used_gb: disks.iter().map(|disk| disk.used_space).sum(),
+            files: disks.iter().map(|disk| disk.total_files()).sum(),
+            nearly_full: disks.iter().filter(|disk| disk.used_percent() >= f64::from(nearly_full_percent)).count(),
+            last_scan: disks.iter().filter_map(|disk| disk.scanned_at).max(),
+        }
+    }
+
+    /// Capacity, used and free space in one line.
+    pub fn headline(&self) -> String {
+        let percent = if self.total_gb > 0.0 { self.used_gb / self.total_gb * 100.0 } else { 0.0 };
+        tr_args("summary-headline", &[
+            ("disks", format_count(self.disks)),
+            ("used", format_gb(self.used_gb)),
+            ("total", format_gb(self.total_gb)),
+            ("free", format_gb(self.total_gb - self.used_gb)),
+            ("percent", format_number(percent, 0)),
+        ])
+    }
+
+    /// File count, nearly full disks and when the last scan ran.
+    pub fn details(&self) -> String {
+        let last_scan = match self.last_scan {
+            Some(secs) => format_date_time(UNIX_EPOCH + Duration::from_secs(secs)),
+            None => tr("summary-never-scanned"),
+        };
+        tr_args("summary-details", &[
+            ("files", format_count(self.files)),
+            ("nearly_full", format_count(self.nearly_full)),
+            ("last_scan", last_scan),
+        ])
+    }
+}
+
+/// A compact Markdown report: the machine summary, then per disk a usage
+/// line, the largest files and a breakdown by extension. Backs both the
+/// Markdown export and the clipboard summary so the two can't drift apart.
+pub fn markdown_report(disks: &[DiskInfo], nearly_full_percent: u8) -> String {
+    let mut md = String::new();
+    let summary = MachineSummary::new(disks, nearly_full_percent);
+    let _ = writeln!(md, "{}\n", escape(&summary.headline()));
+    let _ = writeln!(md, "{}\n", escape(&summary.details()));
+    for disk in disks {
+        let _ = writeln!(md, "## {} ({})\n", escape(&disk.name), code(&disk.mount_point));
+        let _ = writeln!(md, "{}\n", tr_args("md-usage", &[
+            ("used", format_gb(disk.used_space)),
+            ("total", format_gb(disk.total_space)),
+            ("percent", format_number(disk.used_percent(), 1)),
+            ("files", format_count(disk.total_files())),
+        ]));
+
+        if disk.files.is_empty() {
+            continue;
+        }
+
+        let _ = writeln!(md, "| {} | {} | {} |", tr("md-path"), tr("md-size"), tr("md-modified"));
+        let _ = writeln!(md, "|---|---:|---|");
+        for file in scan::largest_files(&disk.files, TOP_FILES) {
+            let modified = file
+                .modified
+                .map(|secs| format_date(UNIX_EPOCH + Duration::from_secs(secs)))
+                .unwrap_or_default();
+            let _ = writeln!(md, "| {} | {} | {} |", code(&file.path), format_size_mb(file.size_mb), modified);
+        }
+        md.push('\n');
+
+        let _ = writeln!(md, "| {} | {} | {} |", tr("md-extension"), tr("md-files"), tr("md-size"));
+        let _ = writeln!(md, "|---|---:|---:|");
+        for (extension, count, size_mb) in by_extension(disk.files.iter()).into_iter().take(TOP_EXTENSIONS) {
+            let _ = writeln!(md, "| {} | {} | {} |", code(&extension_label(&extension)), format_count(count), format_size_mb(size_mb));
+        }
+        md.push('\n');
+    }
+    md
+}
+
+/// The extension group a file falls into: its extension lowercased with the
+/// dot, so `.JPG` and `.jpg` group together, or `""` when it has none.
+pub fn extension_key(path: &str) -> String {
+    Path::new(path).extension().map(|ext| format!(".{}", ext.to_string_lossy().to_lowercase())).unwrap_or_default()
+}
+
+/// An `extension_key` as shown to the user, with `(none)` for no extension.
+pub fn extension_label(extension: &str) -> String {
+    if extension.is_empty() {
+        tr("md-no-extension")
+    } else {
+        extension.to_string()
+    }
+}
+
+/// `(extension_key, file count, total MB)`, largest total first. Symlinks
+/// are left out.
+pub fn by_extension<'a>(files: impl Iterator<Item = &'a FileInfo>) -> Vec<(String, usize, f64)> {
+    let mut groups: HashMap<String, (usize, f64)> = HashMap::new();
+    for file in files.filter(|file| file.symlink_target.is_none()) {
+        let group = groups.entry(extension_key(&file.path)).or_default();
+        group.0 += 1;
+        group.1 += file.size_mb;
+    }
+    let mut groups: Vec<(String, usize, f64)> = groups.into_iter().map(|(ext, (count, size))| (ext, count, size)).collect();
+    groups.sort_by(|a, b| b.2.partial_cmp(&a.2).unwrap_or(std::cmp::Ordering::Equal).then_with(|| a.0.cmp(&b.0)));
+    groups
+}
+
+/// Wraps text in a code span so underscores and asterisks in paths render
+/// literally, using a longer fence when the text itself contains backticks.
+/// Pipes are escaped so they don't split table cells.
+fn code(text: &str) -> String {
+    let text = text.replace('|', "\\|");
+    if text.contains('`') {
+        format!("`` {} ``", text)
+    } else {
+        format!("`{}`", text)
+    }
+}
+
+/// Escapes Markdown emphasis and table characters in plain text.
+fn escape(text: &str) -> String {
+    text.chars().fold(String::new(), |mut escaped, c| {
+        if matches!(c, '\\' | '*' | '_' | '`' | '|' | '[' | ']' | '#') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        escaped
+    })
+}
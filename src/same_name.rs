@@ -0,0 +1,52 @@
+use crate::FileInfo;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+/// Copies of one file differ in size by at most this factor; groups spread
+/// wider than that are probably unrelated files sharing a common name.
+const MAX_COPY_SIZE_RATIO: f64 = 2.0;
+
+/// Files with the same name, ignoring case, found in more than one directory.
+#[derive(Debug, Clone)]
+pub struct SameNameGroup {
+    /// The name as spelled by the largest file.
+    pub name: String,
+    /// Path and size in MB of each file, largest first.
+    pub files: Vec<(String, f64)>,
+    /// What keeping only the largest would free if they are copies.
+    pub wasted_mb: f64,
+    /// Sizes are close enough for the files to plausibly be copies.
+    pub similar_sizes: bool,
+}
+
+/// Groups `files` by lowercased file name, keeping names found in at least
+/// two directories, most wasted space first. Names are only compared, never
+/// contents, so this is a cheap first look before hashing.
+pub fn same_name_groups<'a>(files: impl Iterator<Item = &'a FileInfo>) -> Vec<SameNameGroup> {
+    let mut by_name: HashMap<String, Vec<&FileInfo>> = HashMap::new();
+    for file in files.filter(|file| file.symlink_target.is_none()) {
+        if let Some(name) = Path::new(&*file.path).file_name() {
+            by_name.entry(name.to_string_lossy().to_lowercase()).or_default().push(file);
+        }
+    }
+    let mut groups: Vec<SameNameGroup> = by_name
+        .into_values()
+        .filter(|files| {
+            let dirs: HashSet<Option<&Path>> = files.iter().map(|file| Path::new(&*file.path).parent()).collect();
+            dirs.len() > 1
+        })
+        .map(|mut files| {
+            files.sort_by(|a, b| b.size_mb.total_cmp(&a.size_mb));
+            let largest = files[0].size_mb;
+            let smallest = files[files.len() - 1].size_mb;
+            SameNameGroup {
+                name: Path::new(&*files[0].path).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default(),
+                wasted_mb: files[1..].iter().map(|file| file.size_mb).sum(),
+                similar_sizes: largest <= smallest * MAX_COPY_SIZE_RATIO,
+                files: files.iter().map(|file| (file.path.to_string(), file.size_mb)).collect(),
+            }
+        })
+        .collect();
+    groups.sort_by(|a, b| b.wasted_mb.total_cmp(&a.wasted_mb).then_with(|| a.name.cmp(&b.name)));
+    groups
+}
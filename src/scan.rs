@@ -0,0 +1,1998 @@
+use crate::archives;
+use crate::checkpoint;
+use crate::checksum;
+use crate::classify;
+use crate::util;
+use crate::FileInfo;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use rayon::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use sysinfo::{CpuExt, DiskExt, DiskKind, System, SystemExt};
+use walkdir::WalkDir;
+
+#[derive(Debug, Clone, Default)]
+pub struct ScanConfig {
+    // Opening every archive is slow, so this is off unless the user asks for it.
+    pub inspect_archives: bool,
+    /// List the members of archives as entries of their own, opening
+    /// archives nested in them up to `max_depth` minus 1 levels deep. Needs
+    /// the `recurse-archives` feature.
+    pub recurse_archives: bool,
+    // Network shares and cloud placeholders can take hours to walk or trigger
+    // mass downloads, so they are skipped unless explicitly included.
+    pub include_network: bool,
+    /// Walk pseudo file systems (`proc`, `tmpfs`, ...) too. Their files take
+    /// no real space and walking them can hang, so they are skipped by default.
+    pub include_virtual_filesystems: bool,
+    /// File system types treated as virtual, matched case-insensitively.
+    pub virtual_filesystems: Vec<String>,
+    /// Mount points of disks a full scan leaves out.
+    pub excluded_disks: Vec<String>,
+    pub max_depth: Option<usize>,
+    /// Files counted per walk, so each disk of a full scan gets its own share.
+    pub max_files: Option<usize>,
+    /// Wall-clock budget for a whole scan; walking stops with partial results
+    /// once it has been used up.
+    pub max_scan_duration: Option<Duration>,
+    /// Budget of bytes read per walk, so each disk of a full scan gets its
+    /// own, counted as for `ScanProgress::io_bytes`; walking stops with
+    /// partial results once it has been used up. Meant for metered network
+    /// and cloud storage.
+    pub io_quota_bytes: Option<u64>,
+    /// Regular files smaller than this are only counted, per directory, not
+    /// kept as entries; 0 keeps every file. Saves memory on disks with
+    /// millions of tiny files.
+    pub store_files_above_mb: f64,
+    /// Keep at most this many entries, the largest; smaller ones are
+    /// dropped as the walk goes, so memory stays bounded on huge disks.
+    pub max_file_entries: Option<usize>,
+    /// With `max_file_entries`, entries collected past the cap before the
+    /// list is trimmed back to it; `None` for `DEFAULT_BUFFER_SIZE`. Peak
+    /// memory is the cap plus this many entries.
+    pub buffer_size: Option<usize>,
+    /// Save the walk's progress every this many files, so a cancelled or
+    /// crashed scan can be resumed. Walks that checkpoint visit directories
+    /// sorted by name.
+    pub checkpoint_interval: Option<usize>,
+    /// Pick up from a checkpoint of the walked root, if there is one.
+    pub resume_checkpoints: bool,
+    /// Directories a walk doesn't descend into when they lie below its root:
+    /// the machine's mount points, for walks of whole disks, so a file under
+    /// `/home` is listed under that disk and not again under `/`, and bind
+    /// mounts don't list a tree twice. Folder scans leave it empty.
+    pub skip_mounts: Vec<PathBuf>,
+    pub priority: ScanPriority,
+    /// Pause the walk while machine-wide CPU usage is above this percentage,
+    /// checked every `THROTTLE_CHECK_INTERVAL` entries.
+    pub throttle_at_cpu_pct: Option<f32>,
+    /// Walker threads per disk; `None` picks them by disk kind, see
+    /// `auto_threads`.
+    pub threads: Option<usize>,
+    /// Order the walked files are stored in. The file list sorts its own
+    /// rows, so this only matters to exports and to skipping work.
+    pub storage_order: SortMode,
+    /// Skip paths matched by `.duignore` files (gitignore syntax) in the
+    /// scanned directories.
+    pub use_duignore: bool,
+    /// Work out the Btrfs deduplication ratio after the walk. It reads every
+    /// extent on the file system, so it is off unless asked for.
+    pub btrfs_dedup: bool,
+    /// Files up to this size are sniffed for binary content; 0 turns it off.
+    pub binary_check_max_bytes: u64,
+    /// Source files up to this size are read in full to count their lines;
+    /// 0 turns it off.
+    pub line_count_max_bytes: u64,
+    /// Hash this many of the largest files after the walk; 0 turns it off.
+    pub checksum_top_n: usize,
+    /// How build output and dependency directories are recognized.
+    pub artifact_rules: Vec<ArtifactRule>,
+}
+
+/// CPU priority of scan threads, so a full-disk scan doesn't make the rest of
+/// an interactive machine sluggish.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ScanPriority {
+    #[default]
+    Background,
+    Normal,
+    High,
+}
+
+impl ScanPriority {
+    pub const ALL: &[ScanPriority] = &[ScanPriority::Background, ScanPriority::Normal, ScanPriority::High];
+
+    /// Applies this priority to the calling thread. Raising it may need
+    /// privileges the user doesn't have; the scan then runs at the priority it
+    /// already had.
+    #[cfg(target_os = "linux")]
+    pub fn apply_to_current_thread(self) {
+        let nice = match self {
+            ScanPriority::Background => 10,
+            ScanPriority::Normal => 0,
+            ScanPriority::High => -5,
+        };
+        // On Linux a thread id targets just this thread.
+        let who = unsafe { libc::gettid() } as libc::id_t;
+        if unsafe { libc::setpriority(libc::PRIO_PROCESS, who, nice) } != 0 {
+            eprintln!("Failed to set scan priority to {:?}: {}", self, std::io::Error::last_os_error());
+        }
+    }
+
+    #[cfg(windows)]
+    pub fn apply_to_current_thread(self) {
+        use windows_sys::Win32::System::Threading::{
+            GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_ABOVE_NORMAL, THREAD_PRIORITY_BELOW_NORMAL,
+            THREAD_PRIORITY_NORMAL,
+        };
+        let priority = match self {
+            ScanPriority::Background => THREAD_PRIORITY_BELOW_NORMAL,
+            ScanPriority::Normal => THREAD_PRIORITY_NORMAL,
+            ScanPriority::High => THREAD_PRIORITY_ABOVE_NORMAL,
+        };
+        if unsafe { SetThreadPriority(GetCurrentThread(), priority) } == 0 {
+            eprintln!("Failed to set scan priority to {:?}: {}", self, std::io::Error::last_os_error());
+        }
+    }
+
+    /// Other systems can only renice the whole process, which would slow
+    /// the UI along with the scan, so the priority is left alone there.
+    #[cfg(not(any(target_os = "linux", windows)))]
+    pub fn apply_to_current_thread(self) {}
+}
+
+impl fmt::Display for ScanPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = match self {
+            ScanPriority::Background => "priority-background",
+            ScanPriority::Normal => "priority-normal",
+            ScanPriority::High => "priority-high",
+        };
+        write!(f, "{}", crate::i18n::tr(key))
+    }
+}
+
+/// Order scanned files are stored in, independent of how the file list
+/// shows them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SortMode {
+    #[default]
+    BySize,
+    ByName,
+    ByModified,
+    /// Walk order; skips sorting altogether, which adds up on very large
+    /// file lists.
+    Unsorted,
+}
+
+impl SortMode {
+    pub const ALL: &[SortMode] = &[SortMode::BySize, SortMode::ByName, SortMode::ByModified, SortMode::Unsorted];
+
+    /// Sorts `files` in place: largest first, by file name, or newest first
+    /// with files lacking a modification time last.
+    pub fn apply(self, files: &mut [FileInfo]) {
+        use std::cmp::Ordering;
+        match self {
+            SortMode::BySize => files.sort_by(|a, b| b.size_mb.partial_cmp(&a.size_mb).unwrap_or(Ordering::Equal)),
+            SortMode::ByName => {
+                let name = |file: &FileInfo| Path::new(&*file.path).file_name().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+                files.sort_by(|a, b| util::natural_cmp(&name(a), &name(b)).then_with(|| a.path.cmp(&b.path)));
+            }
+            SortMode::ByModified => files.sort_by(|a, b| match (a.modified, b.modified) {
+                (Some(x), Some(y)) => y.cmp(&x),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => Ordering::Equal,
+            }),
+            SortMode::Unsorted => {}
+        }
+    }
+}
+
+impl fmt::Display for SortMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = match self {
+            SortMode::BySize => "storage-order-size",
+            SortMode::ByName => "storage-order-name",
+            SortMode::ByModified => "storage-order-modified",
+            SortMode::Unsorted => "storage-order-unsorted",
+        };
+        write!(f, "{}", crate::i18n::tr(key))
+    }
+}
+
+/// The `count` largest entries of `files`, largest first, whatever order
+/// `files` is stored in.
+pub fn largest_files(files: &[FileInfo], count: usize) -> Vec<&FileInfo> {
+    let by_size = |a: &&FileInfo, b: &&FileInfo| b.size_mb.total_cmp(&a.size_mb);
+    let mut largest: Vec<&FileInfo> = files.iter().collect();
+    // Only the front gets sorted: picking it out is linear in the files.
+    if count < largest.len() {
+        if count == 0 {
+            return Vec::new();
+        }
+        largest.select_nth_unstable_by(count - 1, by_size);
+        largest.truncate(count);
+    }
+    largest.sort_by(by_size);
+    largest
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ScanProfile {
+    Quick,
+    #[default]
+    Deep,
+    Custom,
+}
+
+impl ScanProfile {
+    pub const ALL: &[ScanProfile] = &[ScanProfile::Quick, ScanProfile::Deep, ScanProfile::Custom];
+
+    /// `(max_depth, max_files)` for the built-in profiles, or `None` for
+    /// `Custom`, whose limits come from the user's settings.
+    pub fn limits(self) -> Option<(Option<usize>, Option<usize>)> {
+        match self {
+            ScanProfile::Quick => Some((Some(4), Some(100_000))),
+            ScanProfile::Deep => Some((None, None)),
+            ScanProfile::Custom => None,
+        }
+    }
+}
+
+impl fmt::Display for ScanProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = match self {
+            ScanProfile::Quick => "profile-quick",
+            ScanProfile::Deep => "profile-deep",
+            ScanProfile::Custom => "profile-custom",
+        };
+        write!(f, "{}", crate::i18n::tr(key))
+    }
+}
+
+/// Live counters shared between a scan worker and the UI, which polls them on each tick.
+#[derive(Debug, Default)]
+pub struct ScanProgress {
+    pub files_scanned: AtomicUsize,
+    pub bytes_scanned: AtomicU64,
+    /// Used space of every disk being walked, for estimating completion.
+    pub expected_bytes: AtomicU64,
+    pub cancelled: AtomicBool,
+    /// Parent directory of a recently visited entry, refreshed every
+    /// `CURRENT_DIR_INTERVAL` entries rather than on every file.
+    pub current_dir: Mutex<String>,
+    /// Entries that failed with a transient error and were retried.
+    pub retry_count: AtomicUsize,
+    /// The directory each walker thread is in, refreshed every
+    /// `ACTIVE_PATH_INTERVAL` entries. A thread stuck on a slow mount keeps
+    /// showing the directory it is stuck in.
+    pub active_paths: Arc<Mutex<HashMap<ThreadId, String>>>,
+    /// Bytes read by the walk so far: `METADATA_RECORD_BYTES` per entry
+    /// looked up plus the file heads read for content checks.
+    pub io_bytes: Arc<AtomicU64>,
+    /// Bytes saved by sharing the finished scan's paths, see `path_memory_saved`.
+    pub path_memory_saved: AtomicUsize,
+    /// Time the walk spent paused for `ScanConfig::throttle_at_cpu_pct`.
+    pub throttled_ms: AtomicU64,
+    /// Most walker threads any disk's walk ran with.
+    pub threads: AtomicUsize,
+}
+
+const CURRENT_DIR_INTERVAL: usize = 256;
+/// What one metadata lookup is counted as against the I/O quota, about the
+/// size of a `stat` record; the real cost on the wire varies by protocol.
+const METADATA_RECORD_BYTES: u64 = 256;
+const ACTIVE_PATH_INTERVAL: usize = 32;
+const THROTTLE_CHECK_INTERVAL: usize = 1000;
+/// Entries between checks that the walked root is still there.
+const ROOT_CHECK_INTERVAL: usize = 4096;
+/// Failed entries between checks that the walked root is still there.
+const ROOT_CHECK_ERRORS: usize = 8;
+pub const DEFAULT_BUFFER_SIZE: usize = 500_000;
+/// Shortest pause while CPU usage is over the limit; raised to
+/// `System::MINIMUM_CPU_UPDATE_INTERVAL` (200 ms on Linux, macOS and
+/// Windows), since usage can't be re-read any sooner.
+const THROTTLE_PAUSE: Duration = Duration::from_millis(100);
+/// Walker threads on a spinning disk when picked automatically.
+const HDD_THREADS: usize = 2;
+/// Most walker threads an SSD gets when picked automatically.
+const MAX_SSD_THREADS: usize = 32;
+
+impl ScanProgress {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn current_dir(&self) -> String {
+        self.current_dir.lock().unwrap().clone()
+    }
+
+    /// Up to `limit` of the directories walker threads are in, sorted so the
+    /// list doesn't jump around between ticks.
+    pub fn active_paths(&self, limit: usize) -> Vec<String> {
+        let mut paths: Vec<String> = self.active_paths.lock().unwrap().values().cloned().collect();
+        paths.sort();
+        paths.dedup();
+        paths.truncate(limit);
+        paths
+    }
+
+    /// Shares path strings across `files`, the scan's results, once its walks
+    /// are done, and records how much that saved. Doing it afterwards on one
+    /// thread keeps the walker threads from contending for an interner.
+    pub fn share_paths<'a>(&self, files: impl Iterator<Item = &'a mut FileInfo>) {
+        let mut interner = PathInterner::default();
+        let mut files: Vec<&mut FileInfo> = files.collect();
+        for file in files.iter_mut() {
+            file.path = interner.intern(&file.path);
+        }
+        self.path_memory_saved.store(path_memory_saved(files.iter().map(|file| &**file)), Ordering::Relaxed);
+    }
+}
+
+/// Hands out one shared copy of each distinct path string in a scan's
+/// results, so files reached by more than one walk (a mount point nested
+/// inside another scanned disk, or the same file under two path forms) share it.
+#[derive(Debug, Default)]
+struct PathInterner {
+    paths: HashSet<Arc<str>>,
+}
+
+impl PathInterner {
+    fn intern(&mut self, path: &Arc<str>) -> Arc<str> {
+        if let Some(shared) = self.paths.get(&**path) {
+            return Arc::clone(shared);
+        }
+        self.paths.insert(Arc::clone(path));
+        Arc::clone(path)
+    }
+}
+
+/// Bytes the paths of `files` take less than one `String` per file would:
+/// each file still holds a pointer, but every distinct path is stored once.
+/// Zero when no path is shared, since the reference counts cost a little.
+pub fn path_memory_saved<'a>(files: impl Iterator<Item = &'a FileInfo>) -> usize {
+    let mut naive = 0;
+    let mut interned = 0;
+    let mut stored = HashSet::new();
+    for file in files {
+        naive += std::mem::size_of::<String>() + file.path.len();
+        interned += std::mem::size_of::<Arc<str>>();
+        if stored.insert(Arc::as_ptr(&file.path) as *const u8) {
+            interned += 2 * std::mem::size_of::<usize>() + file.path.len();
+        }
+    }
+    naive.saturating_sub(interned)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct WalkOutput {
+    pub files: Vec<FileInfo>,
+    pub skipped_placeholders: usize,
+    /// The walk stopped early because `max_scan_duration` ran out or the
+    /// I/O quota was used up.
+    pub truncated: bool,
+    /// The `io_quota_bytes` the walk stopped early at.
+    pub quota_exceeded: Option<u64>,
+    pub projects: Vec<ProjectRoot>,
+    /// Every pattern read from a `.duignore` file during the walk.
+    pub ignore_patterns: Vec<IgnorePattern>,
+    /// Files under `store_files_above_mb`, by the directory holding them.
+    pub unstored: HashMap<String, UnstoredFiles>,
+    /// Entries were dropped to stay within `max_file_entries`; they are
+    /// tallied in `unstored`.
+    pub capped: bool,
+    pub artifacts: Vec<ArtifactDir>,
+    /// Mount points below the root the walk left out, see
+    /// `ScanConfig::skip_mounts`, sorted.
+    pub skipped_mounts: Vec<String>,
+    /// The root went away mid-walk, as when a drive is unplugged, and the
+    /// walk stopped with what it had.
+    pub disconnected: bool,
+    /// Files inside archives, with `recurse_archives`, sorted by path. Their
+    /// bytes are already counted in the archive's own entry, so they are kept
+    /// apart from `files` and out of every total.
+    pub archive_members: Vec<FileInfo>,
+}
+
+/// Files of one directory that were counted but not kept as entries.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct UnstoredFiles {
+    pub count: usize,
+    pub size_mb: f64,
+}
+
+const DUIGNORE_FILE: &str = ".duignore";
+
+/// A pattern read from a `.duignore` file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IgnorePattern {
+    /// Directory holding the `.duignore` file; the pattern is relative to it.
+    pub dir: String,
+    pub pattern: String,
+}
+
+/// The `.duignore` matchers of the directories above the entry being walked,
+/// outermost first. `WalkDir` hands out entries depth-first from a single
+/// thread, before they are spread over the pool, so one stack per walk is
+/// enough and leaving a directory only ever pops from the end.
+#[derive(Default)]
+struct DuignoreStack {
+    matchers: Vec<(PathBuf, Gitignore)>,
+    patterns: Vec<IgnorePattern>,
+}
+
+impl DuignoreStack {
+    /// Whether `entry` should be walked. For a directory that is, its own
+    /// `.duignore` is loaded to apply to its contents.
+    fn admit(&mut self, entry: &walkdir::DirEntry) -> bool {
+        let path = entry.path();
+        while self.matchers.last().is_some_and(|(dir, _)| !path.starts_with(dir)) {
+            self.matchers.pop();
+        }
+        let is_dir = entry.file_type().is_dir();
+        // As with `.gitignore`, the deepest file with a matching pattern decides.
+        let ignored = self
+            .matchers
+            .iter()
+            .rev()
+            .map(|(_, matcher)| matcher.matched(path, is_dir))
+            .find(|matched| !matched.is_none())
+            .is_some_and(|matched| matched.is_ignore());
+        if ignored {
+            return false;
+        }
+        if is_dir {
+            self.load(path);
+        }
+        true
+    }
+
+    fn load(&mut self, dir: &Path) {
+        let file = dir.join(DUIGNORE_FILE);
+        let Ok(contents) = fs::read_to_string(&file) else {
+            return;
+        };
+        let mut builder = GitignoreBuilder::new(dir);
+        for line in contents.lines() {
+            let pattern = line.trim();
+            if pattern.is_empty() || pattern.starts_with('#') {
+                continue;
+            }
+            if builder.add_line(Some(file.clone()), line).is_ok() {
+                self.patterns.push(IgnorePattern {
+                    dir: util::normalize_path(dir).display().to_string(),
+                    pattern: pattern.to_string(),
+                });
+            }
+        }
+        if let Ok(matcher) = builder.build() {
+            self.matchers.push((dir.to_path_buf(), matcher));
+        }
+    }
+}
+
+/// A directory holding a build manifest such as `Cargo.toml` or `package.json`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectRoot {
+    pub path: String,
+    /// e.g. `Rust`, or `Node.js, Rust` when several manifests sit together.
+    pub project_type: String,
+    /// Everything beneath the root, including nested projects.
+    pub total_size_mb: f64,
+}
+
+/// The kind of project a manifest file marks, if `path` is one. Manifests
+/// inside `node_modules` belong to dependencies, not projects.
+fn project_type(path: &Path) -> Option<&'static str> {
+    if path.components().any(|component| component.as_os_str() == "node_modules") {
+        return None;
+    }
+    let name = path.file_name()?.to_str()?;
+    let kind = match name {
+        "Cargo.toml" => "Rust",
+        "package.json" => "Node.js",
+        "pom.xml" => "Maven",
+        "build.gradle" | "build.gradle.kts" => "Gradle",
+        "go.mod" => "Go",
+        "pyproject.toml" | "setup.py" => "Python",
+        "Gemfile" => "Ruby",
+        "composer.json" => "PHP",
+        "CMakeLists.txt" => "CMake",
+        "mix.exs" => "Elixir",
+        _ if name.ends_with(".csproj") || name.ends_with(".sln") => ".NET",
+        _ => return None,
+    };
+    Some(kind)
+}
+
+/// A directory of build output or installed dependencies, recognized by its
+/// name and a marker file next to it, e.g. `target` beside `Cargo.toml`, so
+/// that any folder called `build` isn't taken for one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ArtifactRule {
+    pub dir_name: String,
+    /// File names, or `*.ext` patterns, any one of which must sit in the
+    /// directory's parent.
+    pub markers: Vec<String>,
+}
+
+impl ArtifactRule {
+    pub fn defaults() -> Vec<ArtifactRule> {
+        let rule = |dir_name: &str, markers: &[&str]| ArtifactRule {
+            dir_name: dir_name.to_string(),
+            markers: markers.iter().map(|marker| marker.to_string()).collect(),
+        };
+        vec![
+            rule("target", &["Cargo.toml"]),
+            rule("node_modules", &["package.json"]),
+            rule(".venv", &["pyproject.toml", "requirements.txt", "setup.py", "Pipfile"]),
+            rule("build", &["build.gradle", "build.gradle.kts", "CMakeLists.txt", "setup.py"]),
+            rule("__pycache__", &["*.py"]),
+        ]
+    }
+
+    fn marker_present(&self, parent: &Path) -> bool {
+        self.markers.iter().any(|marker| match marker.strip_prefix("*.") {
+            Some(extension) => fs::read_dir(parent).is_ok_and(|entries| {
+                entries.flatten().any(|entry| {
+                    Path::new(&entry.file_name()).extension().is_some_and(|ext| ext.eq_ignore_ascii_case(extension))
+                })
+            }),
+            None => parent.join(marker).exists(),
+        })
+    }
+}
+
+/// A directory matched by an `ArtifactRule`, with what the walk found in it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactDir {
+    pub path: String,
+    /// The directory holding it and its marker file.
+    pub project_root: String,
+    pub size_mb: f64,
+    pub files: usize,
+}
+
+/// The artifact directories holding any of `files` or `unstored`, largest
+/// first. Where they nest, as `node_modules` inside `node_modules`, only the
+/// outermost counts. Marker files are looked up on disk, once per candidate
+/// directory, since small ones may not have been kept as entries.
+fn developer_artifacts(files: &[FileInfo], unstored: &HashMap<String, UnstoredFiles>, rules: &[ArtifactRule]) -> Vec<ArtifactDir> {
+    if rules.is_empty() {
+        return Vec::new();
+    }
+    let mut verdicts: HashMap<PathBuf, bool> = HashMap::new();
+    let mut found: HashMap<PathBuf, (f64, usize)> = HashMap::new();
+    let mut add = |dir: &Path, size_mb: f64, count: usize| {
+        if let Some(artifact) = artifact_dir_of(dir, rules, &mut verdicts) {
+            let entry = found.entry(artifact).or_default();
+            entry.0 += size_mb;
+            entry.1 += count;
+        }
+    };
+    for file in files {
+        if let Some(parent) = Path::new(&*file.path).parent() {
+            add(parent, file.size_mb, 1);
+        }
+    }
+    for (dir, small) in unstored {
+        add(Path::new(dir), small.size_mb, small.count);
+    }
+    let mut artifacts: Vec<ArtifactDir> = found
+        .into_iter()
+        .map(|(path, (size_mb, files))| ArtifactDir {
+            project_root: path.parent().map(|parent| parent.display().to_string()).unwrap_or_default(),
+            path: path.display().to_string(),
+            size_mb,
+            files,
+        })
+        .collect();
+    artifacts.sort_by(|a, b| b.size_mb.total_cmp(&a.size_mb).then_with(|| a.path.cmp(&b.path)));
+    artifacts
+}
+
+/// The outermost artifact directory that is `dir` or holds it.
+fn artifact_dir_of(dir: &Path, rules: &[ArtifactRule], verdicts: &mut HashMap<PathBuf, bool>) -> Option<PathBuf> {
+    let mut ancestors: Vec<&Path> = dir.ancestors().collect();
+    ancestors.reverse();
+    for candidate in ancestors {
+        let (Some(name), Some(parent)) = (candidate.file_name().and_then(|name| name.to_str()), candidate.parent()) else {
+            continue;
+        };
+        if !rules.iter().any(|rule| rule.dir_name == name) {
+            continue;
+        }
+        let is_artifact = *verdicts
+            .entry(candidate.to_path_buf())
+            .or_insert_with(|| rules.iter().any(|rule| rule.dir_name == name && rule.marker_present(parent)));
+        if is_artifact {
+            return Some(candidate.to_path_buf());
+        }
+    }
+    None
+}
+
+#[derive(Debug, Clone)]
+pub struct SubtreeScan {
+    pub walk: WalkOutput,
+    /// Fresh `(total, used)` space of the containing disk, in GB.
+    pub space: Option<(f64, f64)>,
+}
+
+/// Walks `root` in parallel and returns every regular file and symlink beneath
+/// it, in `config.storage_order`. Symlinks are sized by the link itself, not
+/// their target.
+/// `started` is when the overall scan began, so a time limit spans every disk.
+pub fn walk_files(root: &Path, config: &ScanConfig, progress: &ScanProgress, started: Instant) -> WalkOutput {
+    // Per walk, so each disk's scan guards only its own paths.
+    let files = Arc::new(Mutex::new(WalkFiles::new(
+        config.max_file_entries,
+        config.buffer_size.unwrap_or(DEFAULT_BUFFER_SIZE),
+        config.checkpoint_interval.is_some(),
+    )));
+    // Files this walk counted; `max_files` caps each disk's walk on its own.
+    let walked = AtomicUsize::new(0);
+    let resumed = if config.resume_checkpoints { checkpoint::load(root) } else { None };
+    let resume_from = resumed.as_ref().map(|resumed| resumed.info.resume_from.clone());
+    let resumed_entries = resumed.as_ref().map_or(0, |resumed| resumed.info.files);
+    let resumed = resumed.map(|resumed| {
+        walked.store(resumed.info.files_scanned, Ordering::Relaxed);
+        progress.files_scanned.fetch_add(resumed.info.files_scanned, Ordering::Relaxed);
+        files.lock().unwrap().resume(resumed.files);
+        resumed.unstored
+    });
+    let tracker = config
+        .checkpoint_interval
+        .map(|interval| checkpoint::Tracker::new(interval, walked.load(Ordering::Relaxed)));
+    let checkpoints = tracker.as_ref().map(|_| checkpoint::Writer::start(root, resumed_entries));
+    let skipped_placeholders = AtomicUsize::new(0);
+    let truncated = AtomicBool::new(false);
+    let quota_exceeded = AtomicBool::new(false);
+    let entries_seen = AtomicUsize::new(0);
+    let count_file = || {
+        walked.fetch_add(1, Ordering::Relaxed);
+        progress.files_scanned.fetch_add(1, Ordering::Relaxed);
+    };
+    // Bytes this walk read; `io_quota_bytes` limits each disk's walk on its own.
+    let walk_io = AtomicU64::new(0);
+    let count_io = |bytes: u64| {
+        walk_io.fetch_add(bytes, Ordering::Relaxed);
+        progress.io_bytes.fetch_add(bytes, Ordering::Relaxed);
+    };
+    let project_markers: Mutex<HashMap<PathBuf, BTreeSet<&'static str>>> = Mutex::new(HashMap::new());
+    let unstored: Mutex<HashMap<String, UnstoredFiles>> = Mutex::new(resumed.unwrap_or_default());
+    let archive_members: Mutex<Vec<FileInfo>> = Mutex::new(Vec::new());
+    let mut duignore = DuignoreStack::default();
+    // Held by whichever thread is checking, so the others pause at their
+    // next check too. Usage is measured between two refreshes, so this one
+    // is the baseline, and refreshes closer together than sysinfo's minimum
+    // interval would only read noise.
+    let cpu_monitor = config.throttle_at_cpu_pct.map(|_| {
+        let mut system = System::new();
+        system.refresh_cpu();
+        Mutex::new((system, Instant::now()))
+    });
+
+    let root_watch = RootWatch::new(root);
+
+    let nested_mounts: HashSet<&Path> =
+        config.skip_mounts.iter().map(PathBuf::as_path).filter(|mount| *mount != root && mount.starts_with(root)).collect();
+    let skipped_mounts: Mutex<BTreeSet<String>> = Mutex::new(BTreeSet::new());
+    let outside_mounts = |entry: &walkdir::DirEntry| {
+        let nested = entry.depth() > 0 && entry.file_type().is_dir() && nested_mounts.contains(entry.path());
+        if nested {
+            skipped_mounts.lock().unwrap().insert(entry.path().display().to_string());
+        }
+        !nested
+    };
+
+    let mut walker = WalkDir::new(root);
+    if let Some(max_depth) = config.max_depth {
+        walker = walker.max_depth(max_depth);
+    }
+    // A fixed order lets a checkpoint say which part of the tree is done.
+    if tracker.is_some() || resume_from.is_some() {
+        walker = walker.sort_by_file_name();
+    }
+    // Everything sorting before `resume_from` is in the checkpoint, except
+    // the directories leading to it.
+    let unwalked = |path: &Path| resume_from.as_ref().is_none_or(|from| path >= from.as_path() || from.starts_with(path));
+    let under_file_cap = || {
+        config
+            .max_files
+            .is_none_or(|max_files| walked.load(Ordering::Relaxed) < max_files)
+    };
+    let within_time_limit = || match config.max_scan_duration {
+        Some(limit) if started.elapsed() > limit => {
+            truncated.store(true, Ordering::Relaxed);
+            false
+        }
+        _ => true,
+    };
+    let within_io_quota = || match config.io_quota_bytes {
+        Some(quota) if walk_io.load(Ordering::Relaxed) > quota => {
+            quota_exceeded.store(true, Ordering::Relaxed);
+            truncated.store(true, Ordering::Relaxed);
+            false
+        }
+        _ => true,
+    };
+
+    // The walk gets its own pool so its threads can run at the scan priority
+    // without changing rayon's global pool.
+    let priority = config.priority;
+    let threads = config.threads.unwrap_or_else(|| auto_threads(disk_kind(root)));
+    progress.threads.fetch_max(threads, Ordering::Relaxed);
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|i| format!("scan-{}", i))
+        .start_handler(move |_| priority.apply_to_current_thread())
+        .build();
+    let keep_walking = || {
+        !progress.is_cancelled() && !root_watch.is_disconnected() && under_file_cap() && within_time_limit() && within_io_quota()
+    };
+    let visit = |path: &Path, is_symlink: bool| {
+        if let (Some(tracker), Some(checkpoints)) = (&tracker, &checkpoints) {
+            let files_scanned = walked.load(Ordering::Relaxed);
+            if tracker.due(files_scanned) {
+                save_checkpoint(tracker, checkpoints, &files, &unstored, files_scanned);
+            }
+        }
+        count_io(METADATA_RECORD_BYTES);
+        let seen = entries_seen.fetch_add(1, Ordering::Relaxed);
+        if seen.is_multiple_of(CURRENT_DIR_INTERVAL) {
+            if let Some(parent) = path.parent() {
+                *progress.current_dir.lock().unwrap() = parent.display().to_string();
+            }
+        }
+        if seen.is_multiple_of(ACTIVE_PATH_INTERVAL) {
+            if let Some(parent) = path.parent() {
+                progress.active_paths.lock().unwrap().insert(thread::current().id(), parent.display().to_string());
+            }
+        }
+        if seen.is_multiple_of(ROOT_CHECK_INTERVAL) {
+            root_watch.check();
+        }
+        if let (Some(threshold), Some(monitor)) = (config.throttle_at_cpu_pct, &cpu_monitor) {
+            if seen.is_multiple_of(THROTTLE_CHECK_INTERVAL) {
+                let mut monitor = monitor.lock().unwrap();
+                let (system, refreshed) = &mut *monitor;
+                if refreshed.elapsed() >= System::MINIMUM_CPU_UPDATE_INTERVAL {
+                    system.refresh_cpu();
+                    *refreshed = Instant::now();
+                }
+                let pause = THROTTLE_PAUSE.max(System::MINIMUM_CPU_UPDATE_INTERVAL);
+                while system.global_cpu_info().cpu_usage() > threshold && !progress.is_cancelled() {
+                    thread::sleep(pause);
+                    progress.throttled_ms.fetch_add(pause.as_millis() as u64, Ordering::Relaxed);
+                    system.refresh_cpu();
+                    *refreshed = Instant::now();
+                }
+            }
+        }
+        if is_symlink {
+            let link_metadata = util::retry_io(&progress.retry_count, || fs::symlink_metadata(path)).ok();
+            let size = link_metadata.as_ref().map(|metadata| metadata.len()).unwrap_or(0);
+            files.lock().unwrap().push(FileInfo {
+                path: Arc::from(util::normalize_path(path).display().to_string()),
+                size_mb: size as f64 / 1_048_576.0,
+                symlink_target: fs::read_link(path).ok().map(|p| p.display().to_string()),
+                broken_symlink: fs::metadata(path).is_err(),
+                is_hidden: link_metadata.as_ref().is_some_and(|metadata| is_hidden(root, path, metadata)),
+                modified: link_metadata.as_ref().and_then(modified_secs),
+                ..FileInfo::default()
+            });
+            count_file();
+        } else if let Ok(metadata) = util::retry_io(&progress.retry_count, || fs::metadata(path)) {
+            if metadata.is_file() {
+                if let (Some(kind), Some(parent)) = (project_type(path), path.parent()) {
+                    project_markers.lock().unwrap().entry(util::normalize_path(parent)).or_default().insert(kind);
+                }
+                let placeholder = is_cloud_placeholder(&metadata);
+                if placeholder && !config.include_network {
+                    skipped_placeholders.fetch_add(1, Ordering::Relaxed);
+                    return;
+                }
+                // Placeholders are sized by what is actually allocated on disk;
+                // their logical size would only exist after hydrating them.
+                let size = if placeholder { allocated_size(path, &metadata) } else { metadata.len() };
+                let size_mb = size as f64 / 1_048_576.0;
+                if size_mb < config.store_files_above_mb {
+                    let dir = path.parent().map(|parent| util::normalize_path(parent).display().to_string()).unwrap_or_default();
+                    let mut unstored = unstored.lock().unwrap();
+                    let entry = unstored.entry(dir).or_default();
+                    entry.count += 1;
+                    entry.size_mb += size_mb;
+                    count_file();
+                    progress.bytes_scanned.fetch_add(size, Ordering::Relaxed);
+                    return;
+                }
+                let (compressed_size_bytes, uncompressed_size_bytes) =
+                    if config.inspect_archives && !placeholder && is_zip_archive(path) {
+                        match inspect_zip(path) {
+                            Some((compressed, uncompressed)) => (Some(compressed), Some(uncompressed)),
+                            None => (None, None),
+                        }
+                    } else {
+                        (None, None)
+                    };
+                // One read of the file's start serves both content checks.
+                let check_binary = !placeholder && config.binary_check_max_bytes > 0 && size <= config.binary_check_max_bytes;
+                let head = if placeholder {
+                    None
+                } else {
+                    read_head(path, if check_binary { BINARY_SNIFF_BYTES } else { MIME_SNIFF_BYTES })
+                };
+                if let Some(head) = &head {
+                    count_io(head.len() as u64);
+                }
+                let path_string: Arc<str> = Arc::from(util::normalize_path(path).display().to_string());
+                let mut file_info = FileInfo {
+                    is_temp: classify::classify_as_temp(&path_string),
+                    path: path_string,
+                    size_mb,
+                    compressed_size_bytes,
+                    uncompressed_size_bytes,
+                    is_hidden: is_hidden(root, path, &metadata),
+                    modified: modified_secs(&metadata),
+                    inode: file_id(&metadata).map(|(_, inode)| inode),
+                    device: file_id(&metadata).map(|(device, _)| device),
+                    is_binary: head.as_deref().filter(|_| check_binary).map(|head| head.contains(&0)),
+                    mime_type: head.as_deref().and_then(mime_type_of),
+                    is_archive: is_archive(path),
+                    uid: owner_and_mode(&metadata).map(|(uid, _)| uid),
+                    mode: owner_and_mode(&metadata).map(|(_, mode)| mode),
+                    ..FileInfo::default()
+                };
+                if !placeholder
+                    && config.line_count_max_bytes > 0
+                    && size <= config.line_count_max_bytes
+                    && classify::categorize(&file_info.path) == classify::FileCategory::Code
+                {
+                    file_info.line_count = count_lines(path);
+                    count_io(size);
+                }
+                let members = if config.recurse_archives && file_info.is_archive && !placeholder {
+                    // Tar archives are read end to end to find their members.
+                    count_io(size);
+                    let nesting = config.max_depth.map_or(archives::DEFAULT_NESTING, |max_depth| max_depth.saturating_sub(1));
+                    archives::list_members(path, nesting)
+                } else {
+                    Vec::new()
+                };
+                let modified = file_info.modified;
+                files.lock().unwrap().push(file_info);
+                count_file();
+                progress.bytes_scanned.fetch_add(size, Ordering::Relaxed);
+                let members: Vec<FileInfo> = members
+                    .into_iter()
+                    .map(|member| {
+                        let member_path: Arc<str> = Arc::from(member.path);
+                        FileInfo {
+                            is_temp: classify::classify_as_temp(&member_path),
+                            is_archive: is_archive(Path::new(&*member_path)),
+                            path: member_path,
+                            size_mb: member.size as f64 / 1_048_576.0,
+                            // Zip members carry no usable time; they take the archive's.
+                            modified: member.modified.or(modified),
+                            ..FileInfo::default()
+                        }
+                    })
+                    .collect();
+                if !members.is_empty() {
+                    archive_members.lock().unwrap().extend(members);
+                }
+            }
+        }
+    };
+    // A directory `WalkDir` failed to read until a retry got through: its
+    // contents are walked here, as `WalkDir` has already moved past them.
+    // Directories recovered the same way inside it are walked in turn.
+    let walk_retried = |dir: PathBuf, depth: usize| {
+        let mut pending = vec![(dir, depth)];
+        while let Some((dir, depth)) = pending.pop() {
+            if config.max_depth.is_some_and(|max_depth| depth >= max_depth) {
+                continue;
+            }
+            // The `.duignore` files above it apply as they do in the main walk.
+            let mut ignore = DuignoreStack::default();
+            if config.use_duignore {
+                let ancestors: Vec<&Path> = dir.ancestors().filter(|ancestor| ancestor.starts_with(root)).collect();
+                for ancestor in ancestors.into_iter().rev() {
+                    ignore.load(ancestor);
+                }
+            }
+            let mut subtree = WalkDir::new(&dir).min_depth(1);
+            if let Some(max_depth) = config.max_depth {
+                subtree = subtree.max_depth(max_depth - depth);
+            }
+            let entries = subtree
+                .into_iter()
+                .filter_entry(|entry| unwalked(entry.path()) && outside_mounts(entry) && (!config.use_duignore || ignore.admit(entry)))
+                .take_while(|_| keep_walking());
+            for entry in entries {
+                match entry {
+                    Ok(entry) => visit(entry.path(), entry.file_type().is_symlink()),
+                    Err(error) => {
+                        if error.io_error().is_some_and(|error| error.kind() != std::io::ErrorKind::PermissionDenied) {
+                            root_watch.note_error();
+                        }
+                        if let Some((path, is_symlink)) = retry_entry(&error, &progress.retry_count) {
+                            visit(&path, is_symlink);
+                            if !is_symlink && path.is_dir() {
+                                pending.push((path, depth + error.depth()));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    };
+    let walk = || {
+        walker
+            .into_iter()
+            .filter_entry(|entry| unwalked(entry.path()) && outside_mounts(entry) && (!config.use_duignore || duignore.admit(entry)))
+            .take_while(|_| keep_walking())
+            // Still on the walker's thread, so no entry is in flight unseen.
+            .inspect(|entry| {
+                let path = match entry {
+                    Ok(entry) => Some(entry.path()),
+                    Err(error) => error.path(),
+                };
+                if let (Some(tracker), Some(path)) = (&tracker, path) {
+                    tracker.started(path);
+                }
+            })
+            .par_bridge()
+            .filter_map(|entry| match entry {
+                Ok(entry) => Some((entry.path().to_path_buf(), entry.file_type().is_symlink(), None)),
+                Err(error) => {
+                    if error.io_error().is_some_and(|error| error.kind() != std::io::ErrorKind::PermissionDenied) {
+                        root_watch.note_error();
+                    }
+                    let retried = retry_entry(&error, &progress.retry_count);
+                    if let (Some(tracker), Some(path), None) = (&tracker, error.path(), &retried) {
+                        tracker.finished(path);
+                    }
+                    retried.map(|(path, is_symlink)| (path, is_symlink, Some(error.depth())))
+                }
+            })
+            .for_each(|(path, is_symlink, retried_at)| {
+                // Held until a recovered directory's contents are walked too.
+                let _finished = tracker.as_ref().map(|tracker| Finished(tracker, &path));
+                visit(&path, is_symlink);
+                if let Some(depth) = retried_at.filter(|_| !is_symlink && path.is_dir()) {
+                    walk_retried(path.clone(), depth);
+                }
+            })
+    };
+    let pool = pool.ok();
+    in_pool(pool.as_ref(), walk);
+    if let Some(checkpoints) = checkpoints {
+        checkpoints.finish();
+    }
+    // A cancelled walk keeps its checkpoint to resume from next time.
+    if !progress.is_cancelled() {
+        checkpoint::remove(root);
+    }
+
+    progress.active_paths.lock().unwrap().clear();
+    let WalkFiles { retained, .. } = Arc::into_inner(files).unwrap().into_inner().unwrap();
+    let (mut files, dropped) = retained.into_files();
+    let capped = !dropped.is_empty();
+    config.storage_order.apply(&mut files);
+    if config.checksum_top_n > 0 {
+        in_pool(pool.as_ref(), || hash_largest(&mut files, config.checksum_top_n, &progress.cancelled));
+    }
+    let projects = project_roots(project_markers.into_inner().unwrap(), &files);
+    let mut unstored = unstored.into_inner().unwrap();
+    add_unstored(&mut unstored, dropped);
+    let artifacts = developer_artifacts(&files, &unstored, &config.artifact_rules);
+    let mut archive_members = archive_members.into_inner().unwrap();
+    archive_members.sort_by(|a, b| a.path.cmp(&b.path));
+    WalkOutput {
+        files,
+        skipped_placeholders: skipped_placeholders.into_inner(),
+        truncated: truncated.into_inner(),
+        quota_exceeded: config.io_quota_bytes.filter(|_| quota_exceeded.into_inner()),
+        projects,
+        ignore_patterns: duignore.patterns,
+        unstored,
+        capped,
+        artifacts,
+        skipped_mounts: skipped_mounts.into_inner().unwrap().into_iter().collect(),
+        disconnected: root_watch.is_disconnected(),
+        archive_members,
+    }
+}
+
+/// Marks an entry done with when dropped, however its processing ended.
+struct Finished<'a>(&'a checkpoint::Tracker, &'a Path);
+
+impl Drop for Finished<'_> {
+    fn drop(&mut self) {
+        self.0.finished(self.1);
+    }
+}
+
+/// Hands what the walk found since the last checkpoint to `checkpoints`,
+/// which writes it in the background. Failures only cost the checkpoint,
+/// never the scan.
+fn save_checkpoint(
+    tracker: &checkpoint::Tracker,
+    checkpoints: &checkpoint::Writer,
+    files: &Mutex<WalkFiles>,
+    unstored: &Mutex<HashMap<String, UnstoredFiles>>,
+    files_scanned: usize,
+) {
+    // Taken first: whatever finishes afterwards sorts after it.
+    let Some(resume_from) = tracker.resume_from() else {
+        return;
+    };
+    let files = files.lock().unwrap().take_journal();
+    let unstored = unstored.lock().unwrap().clone();
+    checkpoints.save(resume_from, files, files_scanned, unstored);
+}
+
+/// Notices a walked root going away, e.g. a USB drive pulled mid-scan: the
+/// root can no longer be read, or now sits on another device because the
+/// mount point is left behind on the parent file system.
+struct RootWatch<'a> {
+    root: &'a Path,
+    device: Option<u64>,
+    errors: AtomicUsize,
+    disconnected: AtomicBool,
+}
+
+impl<'a> RootWatch<'a> {
+    fn new(root: &'a Path) -> RootWatch<'a> {
+        let device = fs::metadata(root).ok().as_ref().and_then(file_id).map(|(device, _)| device);
+        RootWatch { root, device, errors: AtomicUsize::new(0), disconnected: AtomicBool::new(false) }
+    }
+
+    fn is_disconnected(&self) -> bool {
+        self.disconnected.load(Ordering::Relaxed)
+    }
+
+    /// A burst of failing entries is what a removed drive looks like first.
+    fn note_error(&self) {
+        if (self.errors.fetch_add(1, Ordering::Relaxed) + 1).is_multiple_of(ROOT_CHECK_ERRORS) {
+            self.check();
+        }
+    }
+
+    fn check(&self) {
+        let gone = match fs::metadata(self.root) {
+            Ok(metadata) => self.device.is_some() && file_id(&metadata).map(|(device, _)| device) != self.device,
+            Err(_) => true,
+        };
+        if gone {
+            self.disconnected.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+/// The walk's entries, each path added once: a walk resumed from a
+/// checkpoint comes round again to entries finished just past its resume
+/// point. Only retained entries are remembered, sharing their path strings,
+/// so `max_file_entries` bounds this set too.
+struct WalkFiles {
+    seen: HashSet<Arc<str>>,
+    retained: RetainedFiles,
+    /// Entries added since the last checkpoint, when the walk writes them.
+    journal: Option<Vec<FileInfo>>,
+}
+
+impl WalkFiles {
+    fn new(cap: Option<usize>, buffer_size: usize, checkpointed: bool) -> WalkFiles {
+        WalkFiles {
+            seen: HashSet::new(),
+            retained: RetainedFiles::new(cap, buffer_size),
+            journal: checkpointed.then(Vec::new),
+        }
+    }
+
+    fn push(&mut self, file: FileInfo) {
+        if self.seen.insert(Arc::clone(&file.path)) {
+            if let Some(journal) = &mut self.journal {
+                journal.push(file.clone());
+            }
+            if self.retained.push(file) {
+                self.seen = self.retained.paths();
+            }
+        }
+    }
+
+    /// Adds a checkpoint's entries, which are already written to it.
+    fn resume(&mut self, files: Vec<FileInfo>) {
+        let journal = self.journal.take();
+        for file in files {
+            self.push(file);
+        }
+        self.journal = journal;
+    }
+
+    fn take_journal(&mut self) -> Vec<FileInfo> {
+        self.journal.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+}
+
+/// The entries a walk keeps: all of them, or with a cap only the largest.
+/// Capped entries are collected into a buffer of `buffer_size` past the cap,
+/// which is cut back to the largest `cap` each time it fills up, so the cost
+/// of selecting them is paid once per buffer rather than per entry.
+enum RetainedFiles {
+    All(Vec<FileInfo>),
+    Largest { cap: usize, buffer_size: usize, files: Vec<FileInfo>, dropped: HashMap<String, UnstoredFiles> },
+}
+
+impl RetainedFiles {
+    fn new(cap: Option<usize>, buffer_size: usize) -> RetainedFiles {
+        match cap {
+            Some(cap) => RetainedFiles::Largest { cap, buffer_size: buffer_size.max(1), files: Vec::new(), dropped: HashMap::new() },
+            None => RetainedFiles::All(Vec::new()),
+        }
+    }
+
+    /// Adds `file`, returning whether entries were dropped to make room.
+    fn push(&mut self, file: FileInfo) -> bool {
+        match self {
+            RetainedFiles::All(files) => {
+                files.push(file);
+                false
+            }
+            RetainedFiles::Largest { cap, buffer_size, files, dropped } => {
+                files.push(file);
+                if files.len() < cap.saturating_add(*buffer_size) {
+                    return false;
+                }
+                let evicted = keep_largest(&mut [files], *cap).remove(0);
+                let any = !evicted.is_empty();
+                add_unstored(dropped, evicted);
+                any
+            }
+        }
+    }
+
+    fn paths(&self) -> HashSet<Arc<str>> {
+        match self {
+            RetainedFiles::All(files) | RetainedFiles::Largest { files, .. } => {
+                files.iter().map(|file| Arc::clone(&file.path)).collect()
+            }
+        }
+    }
+
+    /// The kept entries, and the dropped ones tallied by directory.
+    fn into_files(self) -> (Vec<FileInfo>, HashMap<String, UnstoredFiles>) {
+        match self {
+            RetainedFiles::All(files) => (files, HashMap::new()),
+            RetainedFiles::Largest { cap, mut files, mut dropped, .. } => {
+                add_unstored(&mut dropped, keep_largest(&mut [&mut files], cap).remove(0));
+                files.shrink_to_fit();
+                (files, dropped)
+            }
+        }
+    }
+}
+
+/// Adds the tallies of `more` to `unstored`, directory by directory.
+pub fn add_unstored(unstored: &mut HashMap<String, UnstoredFiles>, more: HashMap<String, UnstoredFiles>) {
+    for (dir, tally) in more {
+        let entry = unstored.entry(dir).or_default();
+        entry.count += tally.count;
+        entry.size_mb += tally.size_mb;
+    }
+}
+
+/// Trims `lists` to the `cap` largest entries across all of them, each
+/// entry staying in its own list, and returns per list the entries it lost,
+/// tallied by directory so they still count towards its totals. Between
+/// equally sized entries at the cut, earlier lists win.
+pub fn keep_largest(lists: &mut [&mut Vec<FileInfo>], cap: usize) -> Vec<HashMap<String, UnstoredFiles>> {
+    let total: usize = lists.iter().map(|files| files.len()).sum();
+    if total <= cap {
+        return vec![HashMap::new(); lists.len()];
+    }
+    let mut sizes: Vec<f64> = lists.iter().flat_map(|files| files.iter().map(|file| file.size_mb)).collect();
+    let smallest_kept = if cap == 0 {
+        f64::INFINITY
+    } else {
+        *sizes.select_nth_unstable_by(total - cap, |a, b| a.total_cmp(b)).1
+    };
+    let mut ties = cap - sizes.iter().filter(|&&size| size > smallest_kept).count();
+    lists
+        .iter_mut()
+        .map(|files| {
+            let mut dropped: HashMap<String, UnstoredFiles> = HashMap::new();
+            files.retain(|file| {
+                if file.size_mb > smallest_kept {
+                    return true;
+                } else if file.size_mb == smallest_kept && ties > 0 {
+                    ties -= 1;
+                    return true;
+                }
+                let dir = Path::new(&*file.path).parent().map(|parent| parent.display().to_string()).unwrap_or_default();
+                let tally = dropped.entry(dir).or_default();
+                tally.count += 1;
+                tally.size_mb += file.size_mb;
+                false
+            });
+            dropped
+        })
+        .collect()
+}
+
+/// Runs `op` on `pool` when there is one, else on rayon's global pool.
+fn in_pool<R: Send>(pool: Option<&rayon::ThreadPool>, op: impl FnOnce() -> R + Send) -> R {
+    match pool {
+        Some(pool) => pool.install(op),
+        None => op(),
+    }
+}
+
+/// Fills in `sha256` for the `count` largest regular files of `files`.
+/// Reading them is I/O-heavy, so it runs as its own pass once the walk is
+/// done, and stops early on cancel.
+fn hash_largest(files: &mut [FileInfo], count: usize, cancelled: &AtomicBool) {
+    let mut largest: Vec<&mut FileInfo> = files.iter_mut().filter(|file| file.symlink_target.is_none()).collect();
+    if largest.len() > count {
+        largest.select_nth_unstable_by(count, |a, b| b.size_mb.partial_cmp(&a.size_mb).unwrap_or(std::cmp::Ordering::Equal));
+        largest.truncate(count);
+    }
+    largest.into_par_iter().for_each(|file| {
+        file.sha256 = checksum::hash_file_cancellable(Path::new(&*file.path), cancelled).ok().flatten();
+    });
+}
+
+/// Retries an entry `WalkDir` failed to read, returning its path and whether
+/// it is a symlink if a later attempt gets through. `WalkDir` has already
+/// moved past the contents of a directory recovered this way, so the caller
+/// walks them itself.
+fn retry_entry(error: &walkdir::Error, retry_count: &AtomicUsize) -> Option<(PathBuf, bool)> {
+    let path = error.path()?;
+    // Loop errors carry no I/O error and would fail the same way again.
+    error.io_error()?;
+    let metadata = util::retry_io(retry_count, || fs::symlink_metadata(path)).ok()?;
+    Some((path.to_path_buf(), metadata.file_type().is_symlink()))
+}
+
+/// Sizes each marked directory by the files beneath it, largest first.
+fn project_roots(markers: HashMap<PathBuf, BTreeSet<&'static str>>, files: &[FileInfo]) -> Vec<ProjectRoot> {
+    let mut sizes: HashMap<&Path, f64> = markers.keys().map(|path| (path.as_path(), 0.0)).collect();
+    for file in files {
+        for ancestor in Path::new(&*file.path).ancestors().skip(1) {
+            if let Some(size) = sizes.get_mut(ancestor) {
+                *size += file.size_mb;
+            }
+        }
+    }
+    let mut projects: Vec<ProjectRoot> = markers
+        .iter()
+        .map(|(path, kinds)| ProjectRoot {
+            path: path.display().to_string(),
+            project_type: kinds.iter().copied().collect::<Vec<_>>().join(", "),
+            total_size_mb: sizes[path.as_path()],
+        })
+        .collect();
+    projects.sort_by(|a, b| b.total_size_mb.partial_cmp(&a.total_size_mb).unwrap_or(std::cmp::Ordering::Equal));
+    projects
+}
+
+/// Dot-prefixed names anywhere below `root` hide a file by convention on every
+/// platform; Windows and macOS also have a hidden flag of their own.
+pub(crate) fn is_hidden(root: &Path, path: &Path, metadata: &fs::Metadata) -> bool {
+    let dot_prefixed = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .components()
+        .any(|component| component.as_os_str().to_string_lossy().starts_with('.'));
+    dot_prefixed || has_hidden_flag(metadata)
+}
+
+#[cfg(windows)]
+fn has_hidden_flag(metadata: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_HIDDEN: u32 = 0x2;
+    metadata.file_attributes() & FILE_ATTRIBUTE_HIDDEN != 0
+}
+
+/// Set by `chflags hidden` or the Finder; the flag behind `GetFileInfo -a V`.
+#[cfg(target_os = "macos")]
+fn has_hidden_flag(metadata: &fs::Metadata) -> bool {
+    use std::os::macos::fs::MetadataExt;
+    const UF_HIDDEN: u32 = 0x0000_8000;
+    metadata.st_flags() & UF_HIDDEN != 0
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn has_hidden_flag(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+/// `(device, inode)`, which together identify a file's data across hardlinks.
+#[cfg(unix)]
+fn file_id(metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.dev(), metadata.ino()))
+}
+
+#[cfg(not(unix))]
+fn file_id(_metadata: &fs::Metadata) -> Option<(u64, u64)> {
+    None
+}
+
+/// `(uid, st_mode)`: who owns the file and its permission bits.
+#[cfg(unix)]
+pub fn owner_and_mode(metadata: &fs::Metadata) -> Option<(u32, u32)> {
+    use std::os::unix::fs::MetadataExt;
+    Some((metadata.uid(), metadata.mode()))
+}
+
+#[cfg(not(unix))]
+pub fn owner_and_mode(_metadata: &fs::Metadata) -> Option<(u32, u32)> {
+    None
+}
+
+const BINARY_SNIFF_BYTES: usize = 512;
+const MIME_SNIFF_BYTES: usize = 16;
+
+/// Up to `len` bytes from the start of the file; `None` if it can't be read.
+fn read_head(path: &Path, len: usize) -> Option<Vec<u8>> {
+    let mut buffer = vec![0u8; len];
+    let read = File::open(path).and_then(|mut file| file.read(&mut buffer)).ok()?;
+    buffer.truncate(read);
+    Some(buffer)
+}
+
+/// The number of newlines in the file; `None` if it can't be read.
+fn count_lines(path: &Path) -> Option<u64> {
+    let mut file = File::open(path).ok()?;
+    let mut buffer = [0u8; 64 * 1024];
+    let mut lines = 0;
+    loop {
+        match file.read(&mut buffer) {
+            Ok(0) => return Some(lines),
+            Ok(read) => lines += buffer[..read].iter().filter(|&&byte| byte == b'\n').count() as u64,
+            Err(error) if error.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(_) => return None,
+        }
+    }
+}
+
+/// Git's heuristic: a file is binary if its first 512 bytes contain a NUL.
+/// `None` if it can't be read.
+pub fn is_binary(path: &Path) -> Option<bool> {
+    read_head(path, BINARY_SNIFF_BYTES).map(|head| head.contains(&0))
+}
+
+/// The MIME type the file's magic bytes point to, whatever its extension
+/// says; `None` if it can't be read or no known signature matches.
+pub fn mime_type(path: &Path) -> Option<String> {
+    read_head(path, MIME_SNIFF_BYTES).as_deref().and_then(mime_type_of)
+}
+
+fn mime_type_of(head: &[u8]) -> Option<String> {
+    infer::get(head).map(|kind| kind.mime_type().to_string())
+}
+
+/// Last modification time in seconds since the Unix epoch.
+pub fn modified_secs(metadata: &fs::Metadata) -> Option<u64> {
+    metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok().map(|d| d.as_secs())
+}
+
+/// Directories kept per disk by `most_files_dirs`, enough that filtering
+/// them by average file size still leaves a list to show.
+pub const MOST_FILES_KEPT: usize = 200;
+
+/// A directory by how many files are beneath it, at any depth.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileCountDir {
+    pub path: String,
+    pub file_count: usize,
+    pub size_mb: f64,
+}
+
+impl FileCountDir {
+    pub fn average_size_mb(&self) -> f64 {
+        if self.file_count == 0 {
+            0.0
+        } else {
+            self.size_mb / self.file_count as f64
+        }
+    }
+}
+
+/// The `MOST_FILES_KEPT` directories below `mount_point` holding the most
+/// files, counted recursively, most first. Files only tallied in `unstored`
+/// count too, since tiny files are what these directories are full of.
+/// Symlinks and `mount_point` itself are left out.
+pub fn most_files_dirs(mount_point: &str, files: &[FileInfo], unstored: &HashMap<String, UnstoredFiles>) -> Vec<FileCountDir> {
+    let root = Path::new(mount_point);
+    let listed = files
+        .iter()
+        .filter(|file| file.symlink_target.is_none())
+        .filter_map(|file| Some((Path::new(&*file.path).parent()?, 1, file.size_mb)));
+    let tallied = unstored.iter().map(|(dir, tally)| (Path::new(dir.as_str()), tally.count, tally.size_mb));
+    let mut totals: HashMap<&Path, (usize, f64)> = HashMap::new();
+    for (dir, count, size_mb) in listed.chain(tallied) {
+        for ancestor in dir.ancestors().take_while(|ancestor| *ancestor != root && ancestor.starts_with(root)) {
+            let total = totals.entry(ancestor).or_default();
+            total.0 += count;
+            total.1 += size_mb;
+        }
+    }
+    let mut dirs: Vec<FileCountDir> = totals
+        .into_iter()
+        .map(|(path, (file_count, size_mb))| FileCountDir { path: path.display().to_string(), file_count, size_mb })
+        .collect();
+    dirs.sort_by(|a, b| b.file_count.cmp(&a.file_count).then_with(|| a.path.cmp(&b.path)));
+    dirs.truncate(MOST_FILES_KEPT);
+    dirs
+}
+
+/// File counts by depth below `root`, as walkdir counts it (a file directly
+/// in `root` is at depth 1, matching `ScanConfig::max_depth`), indexed from 0
+/// up to the deepest file seen.
+pub fn depth_histogram(root: &str, files: &[FileInfo]) -> Vec<usize> {
+    let mut histogram = Vec::new();
+    for file in files {
+        let Ok(relative) = Path::new(&*file.path).strip_prefix(root) else {
+            continue;
+        };
+        let depth = relative.components().count();
+        if histogram.len() <= depth {
+            histogram.resize(depth + 1, 0);
+        }
+        histogram[depth] += 1;
+    }
+    histogram
+}
+
+/// File size ranges of the size histogram, smallest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeRange {
+    UpTo1Kb,
+    UpTo100Kb,
+    UpTo1Mb,
+    UpTo100Mb,
+    UpTo1Gb,
+    Over1Gb,
+}
+
+impl SizeRange {
+    pub const ALL: [SizeRange; 6] = [
+        SizeRange::UpTo1Kb,
+        SizeRange::UpTo100Kb,
+        SizeRange::UpTo1Mb,
+        SizeRange::UpTo100Mb,
+        SizeRange::UpTo1Gb,
+        SizeRange::Over1Gb,
+    ];
+
+    /// Exclusive upper bound in bytes; `None` for the open-ended last range.
+    fn upper_bytes(self) -> Option<u64> {
+        match self {
+            SizeRange::UpTo1Kb => Some(1024),
+            SizeRange::UpTo100Kb => Some(100 * 1024),
+            SizeRange::UpTo1Mb => Some(1024 * 1024),
+            SizeRange::UpTo100Mb => Some(100 * 1024 * 1024),
+            SizeRange::UpTo1Gb => Some(1024 * 1024 * 1024),
+            SizeRange::Over1Gb => None,
+        }
+    }
+}
+
+impl fmt::Display for SizeRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = match self {
+            SizeRange::UpTo1Kb => "size-range-1kb",
+            SizeRange::UpTo100Kb => "size-range-100kb",
+            SizeRange::UpTo1Mb => "size-range-1mb",
+            SizeRange::UpTo100Mb => "size-range-100mb",
+            SizeRange::UpTo1Gb => "size-range-1gb",
+            SizeRange::Over1Gb => "size-range-over-1gb",
+        };
+        write!(f, "{}", crate::i18n::tr(key))
+    }
+}
+
+/// File counts per `SizeRange`, to tell disks full of small files from ones
+/// dominated by a few large ones.
+pub fn compute_size_histogram(files: &[FileInfo]) -> [usize; 6] {
+    let mut buckets = [0; 6];
+    for file in files {
+        let bytes = (file.size_mb * 1_048_576.0) as u64;
+        let index = SizeRange::ALL.iter().position(|range| range.upper_bytes().is_none_or(|upper| bytes < upper)).unwrap_or(5);
+        buckets[index] += 1;
+    }
+    buckets
+}
+
+/// Total space saved by compression across all inspected archives, in MB.
+pub fn archive_overhead_mb(files: &[FileInfo]) -> f64 {
+    files
+        .iter()
+        .filter_map(|f| Some(f.uncompressed_size_bytes?.saturating_sub(f.compressed_size_bytes?)))
+        .sum::<u64>() as f64 / 1_048_576.0
+}
+
+/// Current `(total, used)` space in GB of the disk holding `path`, i.e. the
+/// one with the longest mount point above it.
+pub fn disk_space(path: &Path) -> Option<(f64, f64)> {
+    let mut system = System::new();
+    system.refresh_disks_list();
+    system
+        .disks()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().components().count())
+        .map(space_gb)
+}
+
+/// `(total, used)` space of `disk` in GB. Some systems report more space
+/// available than the disk holds; used space is then 0, never negative.
+pub fn space_gb(disk: &sysinfo::Disk) -> (f64, f64) {
+    (
+        disk.total_space() as f64 / 1_073_741_824.0,
+        disk.total_space().saturating_sub(disk.available_space()) as f64 / 1_073_741_824.0,
+    )
+}
+
+/// A disk as the system lists it, before anything on it is walked.
+#[derive(Debug, Clone)]
+pub struct DetectedDisk {
+    pub name: String,
+    pub mount_point: String,
+    /// In GB, like `DiskInfo`'s.
+    pub total_space: f64,
+    pub used_space: f64,
+}
+
+/// Whether a full scan with `config` walks `disk`, before the disks the user
+/// left out: it reports a size, and is neither virtual nor a network share
+/// unless those are included.
+pub fn is_walked(disk: &sysinfo::Disk, config: &ScanConfig) -> bool {
+    disk.total_space() > 0
+        && (config.include_virtual_filesystems || !is_virtual_filesystem(disk.file_system(), &config.virtual_filesystems))
+        && (config.include_network || !is_network_disk(disk.file_system(), disk.mount_point()))
+}
+
+/// The disks a full scan with `config` would walk, see `is_walked`. Only
+/// asks the system, so it is cheap.
+pub fn detect_disks(config: &ScanConfig) -> Vec<DetectedDisk> {
+    let mut system = System::new();
+    system.refresh_disks_list();
+    system
+        .disks()
+        .iter()
+        .filter(|disk| is_walked(disk, config))
+        .map(|disk| {
+            let (total_space, used_space) = space_gb(disk);
+            DetectedDisk {
+                name: disk.name().to_string_lossy().to_string(),
+                mount_point: disk.mount_point().display().to_string(),
+                total_space,
+                used_space,
+            }
+        })
+        .collect()
+}
+
+/// Whether the disk holding `path` spins, where the system reports it.
+pub fn disk_kind(path: &Path) -> Option<DiskKind> {
+    let mut system = System::new();
+    system.refresh_disks_list();
+    system
+        .disks()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().components().count())
+        .map(|disk| disk.kind())
+}
+
+/// Walker threads for a disk of `kind`. A spinning disk seeks between every
+/// thread's directories, so it gets few; an SSD serves more requests at once
+/// than there are cores, and the threads mostly wait on it.
+pub fn auto_threads(kind: Option<DiskKind>) -> usize {
+    let cores = thread::available_parallelism().map_or(1, |cores| cores.get());
+    match kind {
+        Some(DiskKind::HDD) => HDD_THREADS.min(cores),
+        Some(DiskKind::SSD) => (cores * 2).min(MAX_SSD_THREADS).max(cores),
+        _ => cores,
+    }
+}
+
+/// The most walker threads a disk may be given: beyond a few per core they
+/// only add contention.
+pub fn max_threads() -> usize {
+    thread::available_parallelism().map_or(1, |cores| cores.get()) * 4
+}
+
+/// File system type (e.g. `ext4`, `NTFS`) of the disk holding `path`.
+#[cfg(feature = "sqlite")]
+pub fn file_system(path: &Path) -> Option<String> {
+    let mut system = System::new();
+    system.refresh_disks_list();
+    system
+        .disks()
+        .iter()
+        .filter(|disk| path.starts_with(disk.mount_point()))
+        .max_by_key(|disk| disk.mount_point().components().count())
+        .map(|disk| String::from_utf8_lossy(disk.file_system()).to_string())
+}
+
+/// Every mount point on the machine, for `ScanConfig::skip_mounts`. On Linux
+/// this is read from `/proc/self/mounts`, which unlike the disk list also has
+/// bind mounts and virtual file systems.
+pub fn mount_points() -> Vec<PathBuf> {
+    #[cfg(target_os = "linux")]
+    if let Ok(mounts) = fs::read_to_string("/proc/self/mounts") {
+        return mounts.lines().filter_map(|line| line.split(' ').nth(1)).map(|field| PathBuf::from(unescape_mount_field(field))).collect();
+    }
+    let mut system = System::new();
+    system.refresh_disks_list();
+    system.disks().iter().map(|disk| disk.mount_point().to_path_buf()).collect()
+}
+
+/// Undoes the octal escapes `/proc/self/mounts` uses for space, tab, newline
+/// and backslash.
+#[cfg(target_os = "linux")]
+fn unescape_mount_field(field: &str) -> String {
+    field.replace("\\040", " ").replace("\\011", "\t").replace("\\012", "\n").replace("\\134", "\\")
+}
+
+/// The file system types `ScanConfig::virtual_filesystems` starts out with.
+pub const DEFAULT_VIRTUAL_FILESYSTEMS: &[&str] = &["proc", "sysfs", "devtmpfs", "tmpfs", "cgroup", "cgroup2", "devpts"];
+
+/// True when `file_system` is one of `virtual_types`.
+pub fn is_virtual_filesystem(file_system: &[u8], virtual_types: &[String]) -> bool {
+    let file_system = String::from_utf8_lossy(file_system);
+    virtual_types.iter().any(|kind| kind.eq_ignore_ascii_case(&file_system))
+}
+
+/// True for NFS/SMB/SSHFS-style mounts and Windows network drives.
+pub fn is_network_disk(file_system: &[u8], mount_point: &Path) -> bool {
+    let file_system = String::from_utf8_lossy(file_system).to_ascii_lowercase();
+    let network_types = ["nfs", "cifs", "smb", "afp", "9p", "sshfs", "davfs", "webdav", "ncpfs", "glusterfs", "ceph"];
+    network_types.iter().any(|kind| file_system.contains(kind)) || is_remote_drive(mount_point)
+}
+
+/// Space saved by block-level deduplication, as logical/physical size (e.g.
+/// 1.35). ZFS reports the pool's `dedupratio`; on Btrfs it is derived from
+/// `btrfs filesystem du`, counting shared extents once, only with
+/// `ScanConfig::btrfs_dedup` as that reads the whole file system. `None` on
+/// other file systems, when the tools are missing, or without the needed
+/// privileges.
+#[cfg(target_os = "linux")]
+pub fn dedup_ratio(file_system: &[u8], disk_name: &str, mount_point: &Path, config: &ScanConfig) -> Option<f64> {
+    use std::process::Command;
+
+    let ratio = match String::from_utf8_lossy(file_system).as_ref() {
+        "zfs" => {
+            let pool = disk_name.split('/').next()?;
+            let output = Command::new("zpool").args(["get", "-Hp", "-o", "value", "dedupratio", pool]).output().ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            String::from_utf8_lossy(&output.stdout).trim().trim_end_matches('x').parse().ok()?
+        }
+        "btrfs" if config.btrfs_dedup => {
+            let output = Command::new("btrfs")
+                .args(["filesystem", "du", "-s", "--raw"])
+                .arg(mount_point)
+                .output()
+                .ok()?;
+            if !output.status.success() {
+                return None;
+            }
+            // Header line, then: <total> <exclusive> <set shared> <path>
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let fields: Vec<f64> = stdout.lines().nth(1)?.split_whitespace().take(3).filter_map(|field| field.parse().ok()).collect();
+            match fields[..] {
+                [total, exclusive, shared] if exclusive + shared > 0.0 => total / (exclusive + shared),
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+    (ratio >= 1.0).then_some(ratio)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn dedup_ratio(_file_system: &[u8], _disk_name: &str, _mount_point: &Path, _config: &ScanConfig) -> Option<f64> {
+    None
+}
+
+#[cfg(windows)]
+fn is_remote_drive(mount_point: &Path) -> bool {
+    use std::os::windows::ffi::OsStrExt;
+    extern "system" {
+        fn GetDriveTypeW(root_path_name: *const u16) -> u32;
+    }
+    const DRIVE_REMOTE: u32 = 4;
+    let wide: Vec<u16> = mount_point.as_os_str().encode_wide().chain(Some(0)).collect();
+    unsafe { GetDriveTypeW(wide.as_ptr()) == DRIVE_REMOTE }
+}
+
+#[cfg(not(windows))]
+fn is_remote_drive(_mount_point: &Path) -> bool {
+    false
+}
+
+/// Cloud-sync placeholders (OneDrive/Dropbox online-only files) whose contents
+/// would be downloaded as soon as they are read.
+#[cfg(windows)]
+fn is_cloud_placeholder(metadata: &fs::Metadata) -> bool {
+    use std::os::windows::fs::MetadataExt;
+    const FILE_ATTRIBUTE_OFFLINE: u32 = 0x0000_1000;
+    const FILE_ATTRIBUTE_RECALL_ON_OPEN: u32 = 0x0004_0000;
+    const FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS: u32 = 0x0040_0000;
+    metadata.file_attributes()
+        & (FILE_ATTRIBUTE_OFFLINE | FILE_ATTRIBUTE_RECALL_ON_OPEN | FILE_ATTRIBUTE_RECALL_ON_DATA_ACCESS)
+        != 0
+}
+
+#[cfg(target_os = "macos")]
+fn is_cloud_placeholder(metadata: &fs::Metadata) -> bool {
+    use std::os::macos::fs::MetadataExt;
+    const SF_DATALESS: u32 = 0x4000_0000;
+    metadata.st_flags() & SF_DATALESS != 0
+}
+
+#[cfg(not(any(windows, target_os = "macos")))]
+fn is_cloud_placeholder(_metadata: &fs::Metadata) -> bool {
+    false
+}
+
+#[cfg(unix)]
+fn allocated_size(_path: &Path, metadata: &fs::Metadata) -> u64 {
+    use std::os::unix::fs::MetadataExt;
+    metadata.blocks() * 512
+}
+
+#[cfg(windows)]
+fn allocated_size(path: &Path, _metadata: &fs::Metadata) -> u64 {
+    use std::os::windows::ffi::OsStrExt;
+    extern "system" {
+        fn GetCompressedFileSizeW(file_name: *const u16, file_size_high: *mut u32) -> u32;
+    }
+    let wide: Vec<u16> = path.as_os_str().encode_wide().chain(Some(0)).collect();
+    let mut high = 0u32;
+    let low = unsafe { GetCompressedFileSizeW(wide.as_ptr(), &mut high) };
+    if low == u32::MAX && high == 0 {
+        // INVALID_FILE_SIZE; count nothing rather than risk touching the file.
+        return 0;
+    }
+    ((high as u64) << 32) | low as u64
+}
+
+#[cfg(not(any(unix, windows)))]
+fn allocated_size(_path: &Path, metadata: &fs::Metadata) -> u64 {
+    metadata.len()
+}
+
+/// Extensions of the archive and compression formats flagged as
+/// `FileInfo::is_archive`.
+const ARCHIVE_EXTENSIONS: &[&str] = &["zip", "tar", "gz", "bz2", "xz", "7z", "rar", "tgz", "tbz2", "txz"];
+
+pub fn is_archive(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ARCHIVE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+fn is_zip_archive(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| matches!(ext.to_ascii_lowercase().as_str(), "zip" | "jar" | "apk"))
+}
+
+/// Sums the compressed and uncompressed sizes of every member of a zip archive.
+/// Members are read raw, so nothing is actually decompressed.
+fn inspect_zip(path: &Path) -> Option<(u64, u64)> {
+    let mut archive = zip::ZipArchive::new(File::open(path).ok()?).ok()?;
+    let mut compressed = 0;
+    let mut uncompressed = 0;
+    for i in 0..archive.len() {
+        let member = archive.by_index_raw(i).ok()?;
+        compressed += member.compressed_size();
+        uncompressed += member.size();
+    }
+    Some((compressed, uncompressed))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    /// Files of `(relative path, size in bytes)` under a fresh directory.
+    fn fixture_tree(name: &str, files: &[(&str, usize)]) -> PathBuf {
+        let root = util::test_dir(name);
+        for (path, size) in files {
+            let path = root.join(path);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, vec![b'x'; *size]).unwrap();
+        }
+        root
+    }
+
+    fn paths(files: &[FileInfo]) -> Vec<String> {
+        let mut paths: Vec<String> = files.iter().map(|file| file.path.to_string()).collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn resumes_a_crashed_walk_from_its_checkpoint() {
+        let root = fixture_tree(
+            "checkpoint-resume",
+            &[("a/1.txt", 10), ("a/2.txt", 20), ("b/3.txt", 30), ("b/4.txt", 40), ("c/5.txt", 50), ("d/6.txt", 60)],
+        );
+        let all = walk_files(&root, &ScanConfig::default(), &ScanProgress::default(), Instant::now()).files;
+        assert_eq!(all.len(), 6);
+        let under = |dir: &str| -> Vec<FileInfo> {
+            all.iter().filter(|file| Path::new(&*file.path).starts_with(root.join(dir))).cloned().collect()
+        };
+
+        // What a walk that died in `c` leaves behind: two checkpoints, then
+        // half an entry appended when the process went away.
+        let writer = checkpoint::Writer::start(&root, 0);
+        writer.save(root.join("c"), under("a"), 2, HashMap::new());
+        writer.save(root.join("c"), under("b"), 4, HashMap::new());
+        writer.finish();
+        let data = checkpoint::list().into_iter().find(|info| Path::new(&info.root) == root).unwrap();
+        assert_eq!((data.files, data.files_scanned), (4, 4));
+        fs::OpenOptions::new().append(true).open(checkpoint::data_path(&root)).unwrap().write_all(b"{\"path\":\"/trunc").unwrap();
+
+        // Already walked, so a resumed walk must take them from the checkpoint.
+        fs::remove_dir_all(root.join("a")).unwrap();
+        fs::remove_dir_all(root.join("b")).unwrap();
+        let config = ScanConfig { resume_checkpoints: true, ..ScanConfig::default() };
+        let progress = ScanProgress::default();
+        let resumed = walk_files(&root, &config, &progress, Instant::now());
+        assert_eq!(paths(&resumed.files), paths(&all));
+        assert_eq!(progress.files_scanned.load(Ordering::Relaxed), 6);
+        assert!(checkpoint::load(&root).is_none());
+    }
+
+    #[test]
+    fn checkpoints_of_similarly_named_roots_stay_apart() {
+        let root = fixture_tree("checkpoint-names", &[("data/a/1.txt", 10), ("data_a/2.txt", 20)]);
+        let (nested, flat) = (root.join("data").join("a"), root.join("data_a"));
+        assert_ne!(checkpoint::data_path(&nested), checkpoint::data_path(&flat));
+
+        let writer = checkpoint::Writer::start(&nested, 0);
+        writer.save(nested.join("z"), Vec::new(), 1, HashMap::new());
+        writer.finish();
+        assert!(checkpoint::load(&nested).is_some());
+        assert!(checkpoint::load(&flat).is_none());
+        checkpoint::remove(&nested);
+    }
+
+    #[test]
+    fn depths_count_from_the_scanned_root() {
+        let root = fixture_tree("depths", &[("top.txt", 1), ("a/b/deep.txt", 1)]);
+        let walk = walk_files(&root, &ScanConfig::default(), &ScanProgress::default(), Instant::now());
+        assert_eq!(depth_histogram(&root.display().to_string(), &walk.files), [0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn mount_points_below_the_root_are_left_out() {
+        let root = fixture_tree("nested-mounts", &[("a/1.txt", 10), ("home/2.txt", 20), ("home/b/3.txt", 30)]);
+        let config = ScanConfig { skip_mounts: vec![root.clone(), root.join("home")], ..ScanConfig::default() };
+        let walk = walk_files(&root, &config, &ScanProgress::default(), Instant::now());
+        assert_eq!(paths(&walk.files), [root.join("a").join("1.txt").display().to_string()]);
+        assert_eq!(walk.skipped_mounts, [root.join("home").display().to_string()]);
+
+        // Walking the mount point itself lists its files.
+        let walk = walk_files(&root.join("home"), &config, &ScanProgress::default(), Instant::now());
+        assert_eq!(walk.files.len(), 2);
+        assert!(walk.skipped_mounts.is_empty());
+    }
+
+    #[test]
+    fn file_cap_keeps_the_largest_entries_and_tallies_the_rest() {
+        let root = fixture_tree(
+            "file-cap",
+            &[("a/1.bin", 100), ("a/2.bin", 500), ("b/3.bin", 300), ("b/4.bin", 200), ("c/5.bin", 400)],
+        );
+        // A one-entry buffer cuts the list back during the walk as well as at its end.
+        let config = ScanConfig { max_file_entries: Some(2), buffer_size: Some(1), ..ScanConfig::default() };
+        let walk = walk_files(&root, &config, &ScanProgress::default(), Instant::now());
+        assert!(walk.capped);
+        let mut names: Vec<String> =
+            walk.files.iter().map(|file| Path::new(&*file.path).file_name().unwrap().to_string_lossy().to_string()).collect();
+        names.sort();
+        assert_eq!(names, ["2.bin", "5.bin"]);
+        let tallied: usize = walk.unstored.values().map(|dir| dir.count).sum();
+        assert_eq!(tallied, 3);
+        let tallied_bytes: f64 = walk.unstored.values().map(|dir| dir.size_mb).sum::<f64>() * 1_048_576.0;
+        assert!((tallied_bytes - 600.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn keep_largest_breaks_ties_in_favour_of_earlier_lists() {
+        let files = |sizes: &[f64]| -> Vec<FileInfo> {
+            sizes
+                .iter()
+                .enumerate()
+                .map(|(i, size)| serde_json::from_value(serde_json::json!({ "path": format!("/d/{}", i), "size_mb": size })).unwrap())
+                .collect()
+        };
+        let (mut first, mut second) = (files(&[2.0, 5.0]), files(&[2.0, 1.0, 7.0]));
+        let dropped = keep_largest(&mut [&mut first, &mut second], 3);
+        assert_eq!(first.iter().map(|file| file.size_mb).collect::<Vec<_>>(), [2.0, 5.0]);
+        assert_eq!(second.iter().map(|file| file.size_mb).collect::<Vec<_>>(), [7.0]);
+        assert!(dropped[0].is_empty());
+        assert_eq!(dropped[1]["/d"].count, 2);
+        assert_eq!(dropped[1]["/d"].size_mb, 3.0);
+    }
+
+    #[test]
+    fn finds_artifact_dirs_only_next_to_their_markers() {
+        let root = fixture_tree(
+            "artifacts",
+            &[
+                ("rust/Cargo.toml", 10),
+                ("rust/target/debug/app", 1000),
+                ("web/package.json", 10),
+                ("web/node_modules/left-pad/index.js", 200),
+                ("web/node_modules/left-pad/node_modules/pad/index.js", 50),
+                ("py/app.py", 10),
+                ("py/__pycache__/app.cpython-311.pyc", 30),
+                // Same names, but nothing beside them that builds into them.
+                ("photos/target/shot.jpg", 500),
+                ("notes/build/out.txt", 100),
+                ("docs/node_modules/readme.md", 40),
+                ("rust/src/target/mod.rs", 20),
+            ],
+        );
+        let config = ScanConfig { artifact_rules: ArtifactRule::defaults(), ..ScanConfig::default() };
+        let walk = walk_files(&root, &config, &ScanProgress::default(), Instant::now());
+        let found: Vec<(String, usize)> = walk
+            .artifacts
+            .iter()
+            .map(|artifact| (Path::new(&artifact.path).strip_prefix(&root).unwrap().display().to_string(), artifact.files))
+            .collect();
+        let expected = [("rust/target", 1), ("web/node_modules", 2), ("py/__pycache__", 1)];
+        assert_eq!(found, expected.map(|(path, files)| (Path::new(path).display().to_string(), files)));
+        assert!(walk.artifacts.iter().all(|artifact| Path::new(&artifact.path).parent() == Some(Path::new(&artifact.project_root))));
+    }
+
+    #[test]
+    fn keeps_what_it_found_when_the_root_goes_away() {
+        let names: Vec<String> = (0..5000).map(|i| format!("d{}/f{}.txt", i % 50, i)).collect();
+        let files: Vec<(&str, usize)> = names.iter().map(|name| (name.as_str(), 1)).collect();
+        let root = fixture_tree("root-removed", &files);
+        let moved = root.with_extension("moved");
+        let _ = fs::remove_dir_all(&moved);
+
+        // Moving the root away is what unmounting looks like from the walk.
+        let progress = ScanProgress::default();
+        let walk = thread::scope(|scope| {
+            scope.spawn(|| {
+                let started = Instant::now();
+                while progress.files_scanned.load(Ordering::Relaxed) < 50 && started.elapsed() < Duration::from_secs(10) {
+                    thread::yield_now();
+                }
+                fs::rename(&root, &moved).unwrap();
+            });
+            walk_files(&root, &ScanConfig::default(), &progress, Instant::now())
+        });
+        assert!(walk.disconnected);
+        assert!(!walk.files.is_empty());
+        assert!(walk.files.len() < files.len());
+        fs::remove_dir_all(&moved).unwrap();
+    }
+}
@@ -0,0 +1,394 @@
+use crate::dir_chart::ChartColoring;
+use crate::i18n::Language;
+use crate::scan::{self, ArtifactRule, ScanPriority, ScanProfile, SortMode};
+use crate::{AgeFilter, BinaryFilter, ExportFormat, FileFacts, HiddenFilter, SortKey};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::fs;
+use std::path::PathBuf;
+
+pub const DEFAULT_LOW_SPACE_PERCENT: u8 = 10;
+
+/// User preferences persisted between launches in `settings.json` under the data dir.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    pub notify_on_complete: bool,
+    pub notify_threshold_secs: u64,
+    pub window: Option<WindowState>,
+    pub scan_profile: ScanProfile,
+    pub custom_max_depth: Option<usize>,
+    pub custom_max_files: Option<usize>,
+    pub max_scan_duration_secs: Option<u64>,
+    /// Stop scans after reading about this many MB; `None` for no limit.
+    pub io_quota_mb: Option<u64>,
+    /// Pause scans while CPU usage is above this percentage.
+    pub throttle_at_cpu_pct: Option<f32>,
+    /// Walker threads per disk; `None` picks them by disk kind.
+    pub scan_threads: Option<usize>,
+    /// How developer artifact directories are recognized; see
+    /// `ScanConfig::artifact_rules`.
+    pub artifact_rules: Vec<ArtifactRule>,
+    /// File system types skipped as virtual unless included; see
+    /// `ScanConfig::virtual_filesystems`.
+    pub virtual_filesystems: Vec<String>,
+    /// See `ScanConfig::excluded_disks`.
+    pub excluded_disks: Vec<String>,
+    /// Keep at most this many file entries across all disks, the largest;
+    /// `None` keeps every one.
+    pub max_file_entries: Option<usize>,
+    /// See `ScanConfig::buffer_size`.
+    pub entry_buffer_size: Option<usize>,
+    /// See `ScanConfig::checkpoint_interval`.
+    pub checkpoint_interval: Option<usize>,
+    /// Scan results kept in memory to flip between, the latest included.
+    pub kept_scans: usize,
+    pub language: Language,
+    pub ui_scale: UiScale,
+    pub compress_exports: bool,
+    pub export_format: ExportFormat,
+    /// A new scan is refused this soon after the previous one started.
+    pub scan_cooldown_secs: u64,
+    /// Append a line per disk to the growth log after every scan.
+    pub growth_log_enabled: bool,
+    /// Defaults to `growth.csv` in the data dir; `.ndjson` selects NDJSON.
+    pub growth_log_path: Option<PathBuf>,
+    /// Export every successful scan in the background without clicking Export.
+    pub auto_export_enabled: bool,
+    pub auto_export_format: ExportFormat,
+    /// Destination with `{date}`, `{time}` and `{ext}` placeholders.
+    pub auto_export_path: String,
+    /// Export only the files matching the current filters instead of everything.
+    pub auto_export_filtered: bool,
+    pub filter_presets: Vec<FilterPreset>,
+    pub scan_priority: ScanPriority,
+    /// Order scanned files are kept in; `Unsorted` saves sorting time on
+    /// huge scans.
+    pub storage_order: SortMode,
+    pub sort_key: SortKey,
+    pub sort_descending: bool,
+    /// Disks used beyond this percentage are listed in the "nearly full" banner.
+    pub nearly_full_percent: u8,
+    /// Also send a desktop notification when a disk first crosses the threshold.
+    pub notify_nearly_full: bool,
+    /// Rescan every this many minutes while the app runs; `None` turns it off.
+    pub auto_refresh_minutes: Option<u64>,
+    /// Per disk name, the free-space percentage below which auto-refresh
+    /// warns; disks not listed use `DEFAULT_LOW_SPACE_PERCENT`.
+    pub low_space_thresholds: HashMap<String, u8>,
+    /// Closing the window hides it to the tray icon instead of quitting.
+    pub minimize_to_tray: bool,
+    /// Files up to this size are checked for binary content during scans;
+    /// `None`, the default, and 0 skip the check.
+    pub binary_check_max_mb: Option<u64>,
+    /// Source files up to this size get their lines counted during scans;
+    /// `None`, the default, skips counting.
+    pub line_count_max_mb: Option<u64>,
+    pub dark_mode: bool,
+    /// SHA-256 this many of the largest files on each disk during scans.
+    pub checksum_top_n: usize,
+    /// Directories smaller than this are left out of the largest-directories chart.
+    pub min_dir_size_mb: f64,
+    pub chart_coloring: ChartColoring,
+    /// Scans keep only files of at least this size as entries; 0 keeps all.
+    pub store_files_above_mb: f64,
+}
+
+/// A saved combination of the file filters, shown as a chip above them and
+/// usable from the command line with `dupes --preset`. The built-in presets
+/// live in the same list, so they can be reordered and hidden like the rest.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FilterPreset {
+    /// For built-ins, the translation key of the label instead.
+    pub name: String,
+    /// One or more endings, separated by commas, matched case-insensitively.
+    #[serde(default)]
+    pub file_type: String,
+    /// Matched anywhere in the path.
+    #[serde(default)]
+    pub file_name: String,
+    #[serde(default)]
+    pub hidden: HiddenFilter,
+    #[serde(default)]
+    pub binary: BinaryFilter,
+    #[serde(default)]
+    pub mime_type: String,
+    #[serde(default)]
+    pub mime_mismatch: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_size_mb: Option<f64>,
+    /// Only files last modified more than this many days ago.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub older_than_days: Option<u64>,
+    #[serde(default)]
+    pub age: AgeFilter,
+    /// Only files owned by this user id.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<u32>,
+    /// Only files anyone may write to.
+    #[serde(default)]
+    pub world_writable: bool,
+    /// Only files with the setuid bit.
+    #[serde(default)]
+    pub setuid: bool,
+    /// Shipped with the app: can be hidden but not renamed or deleted.
+    #[serde(default)]
+    pub builtin: bool,
+    /// Left out of the chips above the filters.
+    #[serde(default)]
+    pub hide_chip: bool,
+}
+
+impl FilterPreset {
+    /// The presets shipped with the app, in their default order.
+    pub fn builtins() -> Vec<FilterPreset> {
+        let builtin = |name: &str| FilterPreset { name: name.to_string(), builtin: true, ..FilterPreset::default() };
+        vec![
+            FilterPreset {
+                file_type: ".mp4, .mkv, .avi, .mov, .wmv, .webm, .m4v".to_string(),
+                min_size_mb: Some(500.0),
+                ..builtin("preset-large-videos")
+            },
+            FilterPreset {
+                file_type: ".zip, .7z, .rar, .tar, .gz, .xz, .bz2, .zst, .iso".to_string(),
+                min_size_mb: Some(100.0),
+                ..builtin("preset-large-archives")
+            },
+            FilterPreset { min_size_mb: Some(1024.0), ..builtin("preset-huge-files") },
+            FilterPreset { older_than_days: Some(365), ..builtin("preset-old-files") },
+            FilterPreset { file_name: "Downloads".to_string(), ..builtin("preset-downloads") },
+        ]
+    }
+
+    /// The name shown on the chip and in the settings.
+    pub fn label(&self) -> String {
+        if self.builtin {
+            crate::i18n::tr(&self.name)
+        } else {
+            self.name.clone()
+        }
+    }
+
+    /// The MIME filter matches any part of the type, case-insensitively, so
+    /// "image" and "png" both work; files without a detected type never
+    /// match it. Files without a modification time never match the age
+    /// filters, which measure age at `now`, in seconds since the Unix epoch.
+    pub fn matches(&self, file: &FileFacts, now: u64) -> bool {
+        let file_type_matches = self.file_type.is_empty() || {
+            let path = file.path.to_lowercase();
+            self.file_type
+                .split(',')
+                .map(|ending| ending.trim().to_lowercase())
+                .filter(|ending| !ending.is_empty())
+                .any(|ending| path.ends_with(&ending))
+        };
+        let age_matches = self.older_than_days.is_none_or(|days| {
+            file.modified.is_some_and(|modified| now.saturating_sub(modified) > days.saturating_mul(86_400))
+        });
+        file_type_matches
+            && (self.file_name.is_empty() || file.path.contains(&self.file_name))
+            && (self.mime_type.is_empty() || file.mime_type.is_some_and(|mime| mime.contains(&self.mime_type.to_ascii_lowercase())))
+            && self.hidden.matches(file.is_hidden)
+            && self.binary.matches(file.is_binary)
+            && (!self.mime_mismatch || file.mime_mismatch())
+            && self.min_size_mb.is_none_or(|min| file.size_mb >= min)
+            && age_matches
+            && self.age.matches(file.modified, now)
+            && self.owner.is_none_or(|owner| file.uid == Some(owner))
+            && (!self.world_writable || file.mode.is_some_and(|mode| mode & 0o002 != 0))
+            && (!self.setuid || file.mode.is_some_and(|mode| mode & 0o4000 != 0))
+    }
+
+    /// Whether this preset sets the same filters as `other`, whatever the names.
+    pub fn same_filters(&self, other: &FilterPreset) -> bool {
+        self.file_type == other.file_type
+            && self.file_name == other.file_name
+            && self.hidden == other.hidden
+            && self.binary == other.binary
+            && self.mime_type == other.mime_type
+            && self.mime_mismatch == other.mime_mismatch
+            && self.min_size_mb == other.min_size_mb
+            && self.older_than_days == other.older_than_days
+            && self.age == other.age
+            && self.owner == other.owner
+            && self.world_writable == other.world_writable
+            && self.setuid == other.setuid
+    }
+
+    /// Whether matching looks at the MIME type, so callers only detect it
+    /// when it is needed.
+    pub fn needs_mime_type(&self) -> bool {
+        !self.mime_type.is_empty() || self.mime_mismatch
+    }
+}
+
+/// Whole-UI zoom as a percentage, applied through `Application::scale_factor`
+/// so text, padding and spacing all grow together.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UiScale(pub u16);
+
+impl UiScale {
+    pub const PRESETS: &[UiScale] = &[UiScale(80), UiScale(100), UiScale(125), UiScale(150), UiScale(175), UiScale(200)];
+
+    pub fn factor(self) -> f64 {
+        self.0.clamp(80, 200) as f64 / 100.0
+    }
+}
+
+impl Default for UiScale {
+    fn default() -> Self {
+        UiScale(100)
+    }
+}
+
+impl fmt::Display for UiScale {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.0)
+    }
+}
+
+/// Last known window geometry, in logical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowState {
+    pub width: u32,
+    pub height: u32,
+    pub x: Option<i32>,
+    pub y: Option<i32>,
+}
+
+pub const MIN_WINDOW_SIZE: (u32, u32) = (640, 480);
+
+impl WindowState {
+    /// Fits the saved geometry onto the monitors that exist right now. The size
+    /// is capped to the largest display, and a position whose title bar would
+    /// land off every display (e.g. an unplugged external monitor) is dropped
+    /// so the window opens centered instead.
+    pub fn clamped_to_displays(self) -> WindowState {
+        let displays = match display_info::DisplayInfo::all() {
+            Ok(displays) if !displays.is_empty() => displays,
+            _ => return self,
+        };
+
+        let max_width = displays.iter().map(|d| d.width).max().unwrap_or(self.width);
+        let max_height = displays.iter().map(|d| d.height).max().unwrap_or(self.height);
+        let width = self.width.clamp(MIN_WINDOW_SIZE.0, max_width.max(MIN_WINDOW_SIZE.0));
+        let height = self.height.clamp(MIN_WINDOW_SIZE.1, max_height.max(MIN_WINDOW_SIZE.1));
+
+        let (x, y) = match (self.x, self.y) {
+            (Some(x), Some(y)) => {
+                // Require a reasonable grab area of the title bar to be visible.
+                let visible = displays.iter().any(|d| {
+                    x + 100 > d.x
+                        && x + 100 < d.x + d.width as i32
+                        && y >= d.y
+                        && y + 30 < d.y + d.height as i32
+                });
+                if visible {
+                    (Some(x), Some(y))
+                } else {
+                    (None, None)
+                }
+            }
+            _ => (None, None),
+        };
+
+        WindowState { width, height, x, y }
+    }
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        AppSettings {
+            notify_on_complete: true,
+            notify_threshold_secs: 30,
+            window: None,
+            scan_profile: ScanProfile::Deep,
+            custom_max_depth: None,
+            custom_max_files: None,
+            language: Language::System,
+            ui_scale: UiScale::default(),
+            max_scan_duration_secs: None,
+            io_quota_mb: None,
+            throttle_at_cpu_pct: None,
+            scan_threads: None,
+            max_file_entries: None,
+            entry_buffer_size: None,
+            checkpoint_interval: Some(50_000),
+            kept_scans: 3,
+            artifact_rules: ArtifactRule::defaults(),
+            virtual_filesystems: scan::DEFAULT_VIRTUAL_FILESYSTEMS.iter().map(|kind| kind.to_string()).collect(),
+            excluded_disks: Vec::new(),
+            compress_exports: false,
+            export_format: ExportFormat::default(),
+            scan_cooldown_secs: 5,
+            growth_log_enabled: false,
+            growth_log_path: None,
+            auto_export_enabled: false,
+            auto_export_format: ExportFormat::default(),
+            auto_export_path: "disk_usage_{date}.{ext}".to_string(),
+            auto_export_filtered: false,
+            filter_presets: FilterPreset::builtins(),
+            scan_priority: ScanPriority::Background,
+            storage_order: SortMode::BySize,
+            sort_key: SortKey::Size,
+            sort_descending: true,
+            nearly_full_percent: 90,
+            notify_nearly_full: false,
+            auto_refresh_minutes: None,
+            low_space_thresholds: HashMap::new(),
+            minimize_to_tray: false,
+            binary_check_max_mb: None,
+            line_count_max_mb: None,
+            dark_mode: false,
+            checksum_top_n: 0,
+            min_dir_size_mb: 1.0,
+            chart_coloring: ChartColoring::SizeShare,
+            store_files_above_mb: 0.0,
+        }
+    }
+}
+
+fn settings_path() -> PathBuf {
+    crate::util::data_dir().join("settings.json")
+}
+
+impl AppSettings {
+    /// Loads saved settings, falling back to defaults if the file is missing or unreadable.
+    pub fn load() -> Self {
+        let mut settings: AppSettings = fs::read_to_string(settings_path())
+            .ok()
+            .and_then(|contents| serde_json::from_str(&contents).ok())
+            .unwrap_or_default();
+        settings.add_missing_builtins();
+        settings
+    }
+
+    /// Appends built-in presets missing from the saved list, such as ones
+    /// added since the settings were written.
+    fn add_missing_builtins(&mut self) {
+        for builtin in FilterPreset::builtins() {
+            if !self.filter_presets.iter().any(|preset| preset.builtin && preset.name == builtin.name) {
+                self.filter_presets.push(builtin);
+            }
+        }
+    }
+
+    /// A preset by its name or, for built-ins, by its label.
+    pub fn preset(&self, name: &str) -> Option<&FilterPreset> {
+        self.filter_presets.iter().find(|preset| preset.name == name || preset.label() == name)
+    }
+
+    pub fn low_space_percent(&self, disk: &str) -> u8 {
+        self.low_space_thresholds.get(disk).copied().unwrap_or(DEFAULT_LOW_SPACE_PERCENT)
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        let path = settings_path();
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let contents = serde_json::to_string_pretty(self).map_err(|e| e.to_string())?;
+        fs::write(path, contents).map_err(|e| e.to_string())
+    }
+}
@@ -0,0 +1,279 @@
+use crate::history::{ScanKind, ScanRun};
+use crate::i18n::{format_count, format_date_time, format_gb, format_number, format_size_mb, tr, tr_args};
+use iced::alignment;
+use iced::mouse;
+use iced::widget::canvas::{self, Canvas, Frame, Geometry, Path, Stroke};
+use iced::{Color, Element, Length, Point, Rectangle, Renderer, Size, Theme};
+use std::time::{Duration, UNIX_EPOCH};
+
+/// Number of samples kept for each sparkline; at one sample per tick this is
+/// the last minute of the scan.
+pub const SAMPLES: usize = 60;
+
+/// A miniature bar chart of recent samples, scaled against `max`, drawn
+/// right-aligned so the newest sample is always at the right edge.
+pub struct Sparkline {
+    values: Vec<f32>,
+    max: f32,
+    color: Color,
+}
+
+impl Sparkline {
+    pub fn new(values: impl IntoIterator<Item = f32>, max: f32, color: Color) -> Self {
+        Sparkline { values: values.into_iter().collect(), max, color }
+    }
+
+    pub fn view<'a, Message: 'a>(self) -> Element<'a, Message> {
+        Canvas::new(self).width(Length::Fixed(120.0)).height(Length::Fixed(30.0)).into()
+    }
+}
+
+impl<Message> canvas::Program<Message> for Sparkline {
+    type State = ();
+
+    fn draw(&self, _state: &(), renderer: &Renderer, _theme: &Theme, bounds: Rectangle, _cursor: mouse::Cursor) -> Vec<Geometry> {
+        let mut frame = Frame::new(renderer, bounds.size());
+        frame.fill_rectangle(Point::ORIGIN, bounds.size(), Color { a: 0.08, ..self.color });
+
+        let bar_width = bounds.width / SAMPLES as f32;
+        let offset = SAMPLES.saturating_sub(self.values.len()) as f32 * bar_width;
+        for (i, value) in self.values.iter().enumerate() {
+            let fraction = if self.max > 0.0 { (value / self.max).clamp(0.0, 1.0) } else { 0.0 };
+            let height = (fraction * bounds.height).max(1.0);
+            frame.fill_rectangle(
+                Point::new(offset + i as f32 * bar_width, bounds.height - height),
+                Size::new((bar_width - 0.5).max(0.5), height),
+                self.color,
+            );
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Recorded scans shown in each disk header's usage sparkline.
+pub const HISTORY_POINTS: usize = 30;
+const HISTORY_WIDTH: f32 = 120.0;
+/// Room to the right of the line for the hovered point's date and value.
+const HISTORY_LABEL_WIDTH: f32 = 200.0;
+const HISTORY_MARGIN: f32 = 3.0;
+
+/// A disk's used space over its recorded scans as a line, scaled to the
+/// disk's capacity so a full disk touches the top. The latest point is
+/// highlighted; hovering near a point shows its date and value beside it.
+pub struct UsageSparkline {
+    /// `(timestamp, used GB)`, oldest first.
+    points: Vec<(u64, f64)>,
+    capacity_gb: f64,
+}
+
+impl UsageSparkline {
+    pub fn new(points: Vec<(u64, f64)>, capacity_gb: f64) -> Self {
+        UsageSparkline { points, capacity_gb }
+    }
+
+    pub fn view<'a, Message: 'a>(self) -> Element<'a, Message> {
+        Canvas::new(self)
+            .width(Length::Fixed(HISTORY_WIDTH + HISTORY_LABEL_WIDTH))
+            .height(Length::Fixed(30.0))
+            .into()
+    }
+
+    fn step(&self) -> f32 {
+        (HISTORY_WIDTH - 2.0 * HISTORY_MARGIN) / (self.points.len().max(2) - 1) as f32
+    }
+
+    fn position(&self, index: usize, height: f32) -> Point {
+        let used = self.points[index].1;
+        let fraction = if self.capacity_gb > 0.0 { (used / self.capacity_gb).clamp(0.0, 1.0) as f32 } else { 0.0 };
+        Point::new(
+            HISTORY_MARGIN + index as f32 * self.step(),
+            HISTORY_MARGIN + (1.0 - fraction) * (height - 2.0 * HISTORY_MARGIN),
+        )
+    }
+
+    /// The point whose column the cursor is over, if it is over the line.
+    fn hovered(&self, cursor: Point) -> Option<usize> {
+        if cursor.x > HISTORY_WIDTH || self.points.is_empty() {
+            return None;
+        }
+        let index = ((cursor.x - HISTORY_MARGIN) / self.step()).round().max(0.0) as usize;
+        Some(index.min(self.points.len() - 1))
+    }
+}
+
+impl<Message> canvas::Program<Message> for UsageSparkline {
+    type State = ();
+
+    fn draw(&self, _state: &(), renderer: &Renderer, theme: &Theme, bounds: Rectangle, cursor: mouse::Cursor) -> Vec<Geometry> {
+        let palette = theme.extended_palette();
+        let color = palette.primary.base.color;
+        let mut frame = Frame::new(renderer, bounds.size());
+        frame.fill_rectangle(Point::ORIGIN, Size::new(HISTORY_WIDTH, bounds.height), Color { a: 0.08, ..color });
+        if self.points.len() < 2 {
+            return vec![frame.into_geometry()];
+        }
+
+        let line = Path::new(|builder| {
+            builder.move_to(self.position(0, bounds.height));
+            for index in 1..self.points.len() {
+                builder.line_to(self.position(index, bounds.height));
+            }
+        });
+        frame.stroke(&line, Stroke::default().with_color(color).with_width(1.5));
+        let latest = self.points.len() - 1;
+        frame.fill(&Path::circle(self.position(latest, bounds.height), 2.5), palette.primary.strong.color);
+
+        if let Some(index) = cursor.position_in(bounds).and_then(|point| self.hovered(point)) {
+            frame.fill(&Path::circle(self.position(index, bounds.height), 3.0), palette.primary.strong.color);
+            let (timestamp, used) = self.points[index];
+            frame.fill_text(canvas::Text {
+                content: tr_args("usage-history-point", &[
+                    ("date", format_date_time(UNIX_EPOCH + Duration::from_secs(timestamp))),
+                    ("used", format_gb(used)),
+                ]),
+                position: Point::new(HISTORY_WIDTH + 8.0, bounds.height / 2.0),
+                color: palette.background.base.text,
+                size: 12.0,
+                vertical_alignment: alignment::Vertical::Center,
+                ..canvas::Text::default()
+            });
+        }
+        vec![frame.into_geometry()]
+    }
+}
+
+/// Scans shown in the scan performance chart.
+pub const PERF_POINTS: usize = 30;
+const PERF_WIDTH: f32 = 300.0;
+const PERF_HEIGHT: f32 = 60.0;
+/// Room to the right of the chart for the hovered scan's settings.
+const PERF_LABEL_WIDTH: f32 = 420.0;
+const PERF_MARGIN: f32 = 5.0;
+const DURATION_COLOR: Color = Color::from_rgb(0.26, 0.52, 0.96);
+const SPEED_COLOR: Color = Color::from_rgb(0.85, 0.45, 0.1);
+
+/// Reads the figure one line of the chart plots off a scan.
+type Metric = fn(&ScanRun) -> f64;
+
+/// Duration and throughput of recent scans as two lines, each scaled to its
+/// own maximum. Markers tell full scans (dots), quick scans (squares) and
+/// folder scans (rings) apart; hovering a scan shows its numbers and the
+/// settings it ran with beside the chart.
+pub struct ScanPerfChart {
+    /// Oldest first.
+    runs: Vec<ScanRun>,
+}
+
+impl ScanPerfChart {
+    pub fn new(runs: Vec<ScanRun>) -> Self {
+        ScanPerfChart { runs }
+    }
+
+    pub fn view<'a, Message: 'a>(self) -> Element<'a, Message> {
+        Canvas::new(self)
+            .width(Length::Fixed(PERF_WIDTH + PERF_LABEL_WIDTH))
+            .height(Length::Fixed(PERF_HEIGHT))
+            .into()
+    }
+
+    fn step(&self) -> f32 {
+        (PERF_WIDTH - 2.0 * PERF_MARGIN) / (self.runs.len().max(2) - 1) as f32
+    }
+
+    fn position(&self, index: usize, value: f64, max: f64) -> Point {
+        let fraction = if max > 0.0 { (value / max).clamp(0.0, 1.0) as f32 } else { 0.0 };
+        Point::new(
+            PERF_MARGIN + index as f32 * self.step(),
+            PERF_MARGIN + (1.0 - fraction) * (PERF_HEIGHT - 2.0 * PERF_MARGIN),
+        )
+    }
+
+    fn hovered(&self, cursor: Point) -> Option<usize> {
+        if cursor.x > PERF_WIDTH || self.runs.is_empty() {
+            return None;
+        }
+        let index = ((cursor.x - PERF_MARGIN) / self.step()).round().max(0.0) as usize;
+        Some(index.min(self.runs.len() - 1))
+    }
+
+    fn marker(frame: &mut Frame, kind: ScanKind, at: Point, radius: f32, color: Color) {
+        match kind {
+            ScanKind::Full => frame.fill(&Path::circle(at, radius), color),
+            ScanKind::Quick => frame.fill_rectangle(
+                Point::new(at.x - radius, at.y - radius),
+                Size::new(2.0 * radius, 2.0 * radius),
+                color,
+            ),
+            ScanKind::Folder => frame.stroke(&Path::circle(at, radius), Stroke::default().with_color(color).with_width(1.5)),
+        }
+    }
+}
+
+impl<Message> canvas::Program<Message> for ScanPerfChart {
+    type State = ();
+
+    fn draw(&self, _state: &(), renderer: &Renderer, theme: &Theme, bounds: Rectangle, cursor: mouse::Cursor) -> Vec<Geometry> {
+        let palette = theme.extended_palette();
+        let mut frame = Frame::new(renderer, bounds.size());
+        frame.fill_rectangle(Point::ORIGIN, Size::new(PERF_WIDTH, bounds.height), Color { a: 0.08, ..DURATION_COLOR });
+        if self.runs.is_empty() {
+            return vec![frame.into_geometry()];
+        }
+
+        let max_duration = self.runs.iter().map(|run| run.duration_secs).fold(0.0, f64::max);
+        let max_speed = self.runs.iter().map(ScanRun::mb_per_sec).fold(0.0, f64::max);
+        let series: [(Color, Metric, f64); 2] =
+            [(DURATION_COLOR, |run| run.duration_secs, max_duration), (SPEED_COLOR, ScanRun::mb_per_sec, max_speed)];
+        let hovered = cursor.position_in(bounds).and_then(|point| self.hovered(point));
+        for (color, value, max) in &series {
+            if self.runs.len() > 1 {
+                let line = Path::new(|builder| {
+                    builder.move_to(self.position(0, value(&self.runs[0]), *max));
+                    for (index, run) in self.runs.iter().enumerate().skip(1) {
+                        builder.line_to(self.position(index, value(run), *max));
+                    }
+                });
+                frame.stroke(&line, Stroke::default().with_color(Color { a: 0.6, ..*color }).with_width(1.5));
+            }
+            for (index, run) in self.runs.iter().enumerate() {
+                let radius = if hovered == Some(index) { 4.0 } else { 2.5 };
+                ScanPerfChart::marker(&mut frame, run.kind, self.position(index, value(run), *max), radius, *color);
+            }
+        }
+
+        if let Some(index) = hovered {
+            let run = &self.runs[index];
+            let kind = match run.kind {
+                ScanKind::Full => "scan-kind-full",
+                ScanKind::Quick => "scan-kind-quick",
+                ScanKind::Folder => "scan-kind-folder",
+            };
+            let lines = [
+                tr_args("scan-perf-point", &[
+                    ("date", format_date_time(UNIX_EPOCH + Duration::from_secs(run.timestamp))),
+                    ("kind", tr(kind)),
+                    ("seconds", format_number(run.duration_secs, 1)),
+                    ("speed", format_number(run.mb_per_sec(), 1)),
+                    ("files", format_count(run.files)),
+                ]),
+                tr_args("scan-perf-settings", &[
+                    ("profile", run.profile.to_string()),
+                    ("threads", format_count(run.threads)),
+                    ("priority", run.priority.to_string()),
+                    ("size", format_size_mb(run.store_files_above_mb)),
+                ]),
+            ];
+            for (line, content) in lines.into_iter().enumerate() {
+                frame.fill_text(canvas::Text {
+                    content,
+                    position: Point::new(PERF_WIDTH + 8.0, bounds.height / 2.0 + (line as f32 - 0.5) * 16.0),
+                    color: palette.background.base.text,
+                    size: 12.0,
+                    vertical_alignment: alignment::Vertical::Center,
+                    ..canvas::Text::default()
+                });
+            }
+        }
+        vec![frame.into_geometry()]
+    }
+}
@@ -0,0 +1,98 @@
+//! SQLite export, for querying scan results with SQL, e.g.
+//! `SELECT category, SUM(size_mb) FROM files GROUP BY category ORDER BY 2 DESC`.
+//! Only built with the `sqlite` feature; without it `export` returns an error
+//! and the button is hidden.
+
+/// Whether this build can write SQLite exports.
+pub const AVAILABLE: bool = cfg!(feature = "sqlite");
+
+pub const EXPORT_PATH: &str = "disk_usage.sqlite";
+
+#[cfg(feature = "sqlite")]
+mod imp {
+    use crate::classify;
+    use crate::{checksum, scan, DiskInfo, ExportProgress};
+    use rusqlite::{params, Connection};
+    use std::collections::HashSet;
+    use std::path::Path;
+
+    const SCHEMA: &str = "
+        PRAGMA foreign_keys = ON;
+        CREATE TABLE disks (
+            name TEXT PRIMARY KEY,
+            total_gb REAL NOT NULL,
+            used_gb REAL NOT NULL,
+            filesystem TEXT,
+            mount_point TEXT NOT NULL
+        );
+        CREATE TABLE files (
+            id INTEGER PRIMARY KEY,
+            disk_name TEXT NOT NULL REFERENCES disks(name),
+            path TEXT NOT NULL,
+            size_mb REAL NOT NULL,
+            modified_ts INTEGER,
+            category TEXT NOT NULL,
+            is_temp INTEGER NOT NULL
+        );
+        CREATE INDEX files_disk_name ON files(disk_name);
+        CREATE INDEX files_size_mb ON files(size_mb);
+    ";
+
+    /// Writes `disks` to a fresh database at `path`, replacing any earlier
+    /// export, in one transaction. Returns the file's SHA-256, also stored
+    /// in the `.sha256` sidecar like the other exports.
+    pub fn export(disks: &[DiskInfo], path: &Path, progress: &ExportProgress) -> Result<String, String> {
+        if path.exists() {
+            std::fs::remove_file(path).map_err(|e| e.to_string())?;
+        }
+        let mut conn = Connection::open(path).map_err(|e| e.to_string())?;
+        conn.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        {
+            let mut insert_disk = tx
+                .prepare("INSERT INTO disks (name, total_gb, used_gb, filesystem, mount_point) VALUES (?1, ?2, ?3, ?4, ?5)")
+                .map_err(|e| e.to_string())?;
+            let mut insert_file = tx
+                .prepare(
+                    "INSERT INTO files (disk_name, path, size_mb, modified_ts, category, is_temp) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                )
+                .map_err(|e| e.to_string())?;
+            let mut used_names = HashSet::new();
+            for disk in disks {
+                // `name` is the key, and several disks can share a device
+                // name (e.g. `overlay`).
+                let mut name = disk.name.clone();
+                let mut suffix = 2;
+                while !used_names.insert(name.clone()) {
+                    name = format!("{}-{}", disk.name, suffix);
+                    suffix += 1;
+                }
+                // Imported disks were scanned elsewhere; their file system isn't known here.
+                let file_system = disk.source.is_none().then(|| scan::file_system(Path::new(&disk.mount_point))).flatten();
+                insert_disk
+                    .execute(params![name, disk.total_space, disk.used_space, file_system, disk.mount_point])
+                    .map_err(|e| e.to_string())?;
+                for file in &disk.files {
+                    progress.row()?;
+                    let category = format!("{:?}", classify::categorize(&file.path)).to_lowercase();
+                    insert_file
+                        .execute(params![name, &*file.path, file.size_mb, file.modified.map(|secs| secs as i64), category, file.is_temp])
+                        .map_err(|e| e.to_string())?;
+                }
+            }
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+        conn.close().map_err(|(_, e)| e.to_string())?;
+        let checksum = checksum::hash_file(path).map_err(|e| e.to_string())?;
+        checksum::write_sidecar(path, &checksum)?;
+        Ok(checksum)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+pub use imp::export;
+
+#[cfg(not(feature = "sqlite"))]
+pub fn export(_disks: &[crate::DiskInfo], _path: &std::path::Path, _progress: &crate::ExportProgress) -> Result<String, String> {
+    Err(crate::i18n::tr("sqlite-unavailable"))
+}
@@ -0,0 +1,65 @@
+use crate::util;
+use crate::FileInfo;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Directories removable volumes get mounted in, and whether a per-user
+/// directory sits between them and the volume, as in `/media/<user>/<label>`.
+const REMOVABLE_MOUNT_DIRS: &[(&str, bool)] = &[("/media", true), ("/run/media", true), ("/mnt", false), ("/Volumes", false)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkStatus {
+    Ok,
+    /// The target is missing from a volume that is there.
+    Broken,
+    /// The target is on a volume that isn't mounted, such as an unplugged
+    /// USB drive; the link may work again once it is back.
+    MediaMissing,
+}
+
+/// One symlink found by the walk, which records links rather than following
+/// them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SymlinkEntry {
+    pub path: String,
+    pub target: String,
+    pub status: LinkStatus,
+    /// The target resolves to somewhere outside the scanned root.
+    pub outside_root: bool,
+}
+
+/// Builds the report for the symlinks among `files`, broken ones first.
+/// Only broken links are looked at again on disk, to tell a missing volume
+/// from a missing file.
+pub fn symlink_report(root: &str, files: &[FileInfo]) -> Vec<SymlinkEntry> {
+    let mut links: Vec<SymlinkEntry> = files
+        .iter()
+        .filter_map(|file| {
+            let target = file.symlink_target.as_ref()?;
+            let link = Path::new(&*file.path);
+            let resolved = util::normalize_path(&link.parent().unwrap_or(link).join(target));
+            let status = if !file.broken_symlink {
+                LinkStatus::Ok
+            } else if mount_missing(&resolved) {
+                LinkStatus::MediaMissing
+            } else {
+                LinkStatus::Broken
+            };
+            Some(SymlinkEntry { path: file.path.to_string(), target: target.clone(), status, outside_root: !resolved.starts_with(root) })
+        })
+        .collect();
+    links.sort_by(|a, b| (a.status == LinkStatus::Ok).cmp(&(b.status == LinkStatus::Ok)).then_with(|| a.path.cmp(&b.path)));
+    links
+}
+
+/// Whether `target` lies on a volume that is gone: nothing of it exists below
+/// a removable-media mount directory, or not even its drive root exists.
+fn mount_missing(target: &Path) -> bool {
+    let Some(existing) = target.ancestors().find(|dir| dir.exists()) else {
+        return true;
+    };
+    REMOVABLE_MOUNT_DIRS.iter().any(|&(dir, per_user)| {
+        let dir = Path::new(dir);
+        existing == dir || (per_user && existing.parent() == Some(dir))
+    })
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Whether this platform lets the app empty the trash; the `trash` crate
+/// can't list or purge it on macOS.
+pub const CAN_EMPTY: bool = cfg!(any(target_os = "linux", target_os = "freebsd", target_os = "windows"));
+
+/// Whether this platform can move files to the trash.
+pub const CAN_MOVE: bool = cfg!(any(target_os = "linux", target_os = "freebsd", target_os = "macos", target_os = "windows"));
+
+/// What the current user's trash on one disk holds.
+#[derive(Debug, Clone, Default)]
+pub struct TrashUsage {
+    pub size_mb: f64,
+    pub files: usize,
+    /// Part of it couldn't be read, so the size is a lower bound.
+    pub partial: bool,
+}
+
+/// Measures the current user's trash on each of `mount_points` with a walk
+/// of just those trash folders, keyed by mount point. Other users' trash is
+/// left out, since `empty` can't delete it. The trash in the home directory
+/// counts toward the disk holding it. Disks without any trash are left out.
+pub fn measure(mount_points: &[String]) -> HashMap<String, TrashUsage> {
+    let mut usage: HashMap<String, TrashUsage> = HashMap::new();
+    for mount_point in mount_points {
+        for dir in mount_trash_dirs(Path::new(mount_point)) {
+            measure_dir(&dir, usage.entry(mount_point.clone()).or_default());
+        }
+    }
+    if let Some(home_trash) = home_trash().filter(|dir| dir.exists()) {
+        if let Some(mount_point) = holder(mount_points, &home_trash) {
+            measure_dir(&home_trash, usage.entry(mount_point.to_string()).or_default());
+        }
+    }
+    usage.retain(|_, usage| usage.files > 0 || usage.partial);
+    usage
+}
+
+/// The mount point among `mount_points` that holds `path`.
+fn holder<'a>(mount_points: &'a [String], path: &Path) -> Option<&'a str> {
+    mount_points
+        .iter()
+        .filter(|mount_point| path.starts_with(mount_point))
+        .max_by_key(|mount_point| Path::new(mount_point).components().count())
+        .map(String::as_str)
+}
+
+/// Adds up the files under `dir`. Anything unreadable marks the usage as
+/// partial rather than failing.
+fn measure_dir(dir: &Path, usage: &mut TrashUsage) {
+    for entry in WalkDir::new(dir) {
+        match entry {
+            Ok(entry) if entry.file_type().is_file() => match entry.metadata() {
+                Ok(metadata) => {
+                    usage.size_mb += metadata.len() as f64 / 1_048_576.0;
+                    usage.files += 1;
+                }
+                Err(_) => usage.partial = true,
+            },
+            Ok(_) => {}
+            Err(_) => usage.partial = true,
+        }
+    }
+}
+
+/// The current user's trash folders at the root of a disk: `.Trash/<uid>`
+/// and `.Trash-<uid>` (`.Trashes/<uid>` on macOS), or on Windows the
+/// `$Recycle.Bin` folders that can be read, since other users' are closed
+/// to everyone but them.
+fn mount_trash_dirs(mount_point: &Path) -> Vec<PathBuf> {
+    #[cfg(windows)]
+    let dirs: Vec<PathBuf> = std::fs::read_dir(mount_point.join("$Recycle.Bin"))
+        .map(|entries| {
+            entries
+                .filter_map(Result::ok)
+                .map(|entry| entry.path())
+                .filter(|path| std::fs::read_dir(path).is_ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    #[cfg(target_os = "macos")]
+    let dirs = vec![mount_point.join(".Trashes").join(unsafe { libc::getuid() }.to_string())];
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let dirs = {
+        let uid = unsafe { libc::getuid() };
+        vec![mount_point.join(".Trash").join(uid.to_string()), mount_point.join(format!(".Trash-{}", uid))]
+    };
+    #[cfg(not(any(unix, windows)))]
+    let dirs: Vec<PathBuf> = Vec::new();
+    dirs.into_iter().filter(|dir| dir.is_dir()).collect()
+}
+
+/// The current user's trash in their home directory; Windows keeps it per
+/// drive only.
+fn home_trash() -> Option<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    if cfg!(windows) {
+        None
+    } else if cfg!(target_os = "macos") {
+        home.map(|home| home.join(".Trash"))
+    } else {
+        std::env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| home.map(|home| home.join(".local/share")))
+            .map(|data| data.join("Trash"))
+    }
+}
+
+/// Permanently deletes what the current user trashed from `mount_point`,
+/// one of `mount_points`: the items whose original folder is on that disk.
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "windows"))]
+pub fn empty(mount_point: &str, mount_points: &[String]) -> Result<(), String> {
+    let items: Vec<_> = ::trash::os_limited::list()
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .filter(|item| holder(mount_points, &item.original_parent) == Some(mount_point))
+        .collect();
+    ::trash::os_limited::purge_all(items).map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "freebsd", target_os = "windows")))]
+pub fn empty(_mount_point: &str, _mount_points: &[String]) -> Result<(), String> {
+    Err(crate::i18n::tr("trash-empty-unsupported"))
+}
+
+/// Moves `path`, a file or a whole directory, to the current user's trash.
+#[cfg(any(target_os = "linux", target_os = "freebsd", target_os = "macos", target_os = "windows"))]
+pub fn move_to_trash(path: &Path) -> Result<(), String> {
+    ::trash::delete(path).map_err(|e| e.to_string())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "freebsd", target_os = "macos", target_os = "windows")))]
+pub fn move_to_trash(_path: &Path) -> Result<(), String> {
+    Err(crate::i18n::tr("trash-move-unsupported"))
+}
@@ -0,0 +1,187 @@
+//! System tray icon with a small menu, so the app can keep running with its
+//! window hidden. Only built with the `tray` feature on Linux, macOS and
+//! Windows; elsewhere, or when the desktop has no tray (e.g. no StatusNotifier
+//! host on Linux), `Tray::new` returns `None` and closing the window only
+//! minimizes it.
+
+/// What the user picked from the tray menu. Builds without a tray never
+/// report one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(all(feature = "tray", any(target_os = "linux", target_os = "macos", target_os = "windows"))), allow(dead_code))]
+pub enum TrayEvent {
+    Show,
+    ScanNow,
+    Quit,
+}
+
+#[cfg(all(feature = "tray", any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+mod imp {
+    use super::TrayEvent;
+    use crate::i18n::tr;
+    use tray_icon::menu::{Menu, MenuEvent, MenuId, MenuItem, PredefinedMenuItem};
+    use tray_icon::{Icon, TrayIcon, TrayIconBuilder};
+
+    const ICON_SIZE: u32 = 32;
+
+    pub struct Tray {
+        show: MenuId,
+        scan: MenuId,
+        quit: MenuId,
+        backend: Backend,
+    }
+
+    /// GTK objects must stay on the thread running the GTK main loop, so on
+    /// Linux the icon lives there and summary updates are sent over.
+    #[cfg(target_os = "linux")]
+    struct Backend {
+        summary: std::sync::mpsc::Sender<String>,
+    }
+
+    /// Whether something on the session bus shows StatusNotifier icons.
+    /// Without it the icon is made just the same but never appears.
+    #[cfg(target_os = "linux")]
+    fn has_status_notifier_host() -> bool {
+        use gtk::gio;
+        use gtk::glib::{ToVariant, VariantTy};
+        let Ok(bus) = gio::bus_get_sync(gio::BusType::Session, gio::Cancellable::NONE) else {
+            return false;
+        };
+        bus.call_sync(
+            Some("org.freedesktop.DBus"),
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus",
+            "NameHasOwner",
+            Some(&("org.kde.StatusNotifierWatcher",).to_variant()),
+            VariantTy::new("(b)").ok(),
+            gio::DBusCallFlags::NONE,
+            1000,
+            gio::Cancellable::NONE,
+        )
+        .ok()
+        .and_then(|reply| reply.get::<(bool,)>())
+        .is_some_and(|(owned,)| owned)
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    struct Backend {
+        _icon: TrayIcon,
+        summary: MenuItem,
+    }
+
+    struct Built {
+        icon: TrayIcon,
+        summary: MenuItem,
+        ids: [MenuId; 3],
+    }
+
+    fn build() -> Result<Built, String> {
+        let show = MenuItem::new(tr("tray-show"), true, None);
+        let scan = MenuItem::new(tr("tray-scan-now"), true, None);
+        let summary = MenuItem::new(tr("tray-no-scan-yet"), false, None);
+        let quit = MenuItem::new(tr("quit"), true, None);
+        let menu = Menu::new();
+        menu.append_items(&[&show, &scan, &PredefinedMenuItem::separator(), &summary, &PredefinedMenuItem::separator(), &quit])
+            .map_err(|e| e.to_string())?;
+        let icon = TrayIconBuilder::new()
+            .with_menu(Box::new(menu))
+            .with_tooltip(tr("app-title"))
+            .with_icon(icon()?)
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Built { icon, summary, ids: [show.id().clone(), scan.id().clone(), quit.id().clone()] })
+    }
+
+    /// A filled disc in the app's accent blue, drawn rather than shipped as a file.
+    fn icon() -> Result<Icon, String> {
+        let center = (ICON_SIZE as f32 - 1.0) / 2.0;
+        let mut rgba = Vec::with_capacity((ICON_SIZE * ICON_SIZE * 4) as usize);
+        for y in 0..ICON_SIZE {
+            for x in 0..ICON_SIZE {
+                let distance = ((x as f32 - center).powi(2) + (y as f32 - center).powi(2)).sqrt();
+                let alpha = if distance <= center { 255 } else { 0 };
+                rgba.extend_from_slice(&[0x2f, 0x6f, 0xd6, alpha]);
+            }
+        }
+        Icon::from_rgba(rgba, ICON_SIZE, ICON_SIZE).map_err(|e| e.to_string())
+    }
+
+    impl Tray {
+        #[cfg(target_os = "linux")]
+        pub fn new() -> Option<Tray> {
+            let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+            let (summary_tx, summary_rx) = std::sync::mpsc::channel::<String>();
+            std::thread::spawn(move || {
+                if gtk::init().is_err() || !has_status_notifier_host() {
+                    let _ = ready_tx.send(None);
+                    return;
+                }
+                let Ok(Built { icon, summary, ids }) = build() else {
+                    let _ = ready_tx.send(None);
+                    return;
+                };
+                let _ = ready_tx.send(Some(ids));
+                gtk::glib::timeout_add_local(std::time::Duration::from_millis(250), move || {
+                    // Owning the icon here keeps it in the tray for as long as the loop runs.
+                    let _ = &icon;
+                    while let Ok(text) = summary_rx.try_recv() {
+                        summary.set_text(text);
+                    }
+                    gtk::glib::ControlFlow::Continue
+                });
+                gtk::main();
+            });
+            let [show, scan, quit] = ready_rx.recv().ok()??;
+            Some(Tray { show, scan, quit, backend: Backend { summary: summary_tx } })
+        }
+
+        #[cfg(not(target_os = "linux"))]
+        pub fn new() -> Option<Tray> {
+            let built = build().ok()?;
+            let [show, scan, quit] = built.ids;
+            Some(Tray { show, scan, quit, backend: Backend { _icon: built.icon, summary: built.summary } })
+        }
+
+        /// Replaces the greyed-out last-scan line in the menu.
+        pub fn set_summary(&self, text: String) {
+            #[cfg(target_os = "linux")]
+            let _ = self.backend.summary.send(text);
+            #[cfg(not(target_os = "linux"))]
+            self.backend.summary.set_text(text);
+        }
+
+        /// The next menu pick since the last call, if any.
+        pub fn poll(&self) -> Option<TrayEvent> {
+            while let Ok(event) = MenuEvent::receiver().try_recv() {
+                if event.id == self.show {
+                    return Some(TrayEvent::Show);
+                } else if event.id == self.scan {
+                    return Some(TrayEvent::ScanNow);
+                } else if event.id == self.quit {
+                    return Some(TrayEvent::Quit);
+                }
+            }
+            None
+        }
+    }
+}
+
+#[cfg(not(all(feature = "tray", any(target_os = "linux", target_os = "macos", target_os = "windows"))))]
+mod imp {
+    use super::TrayEvent;
+
+    pub struct Tray;
+
+    impl Tray {
+        pub fn new() -> Option<Tray> {
+            None
+        }
+
+        pub fn set_summary(&self, _text: String) {}
+
+        pub fn poll(&self) -> Option<TrayEvent> {
+            None
+        }
+    }
+}
+
+pub use imp::Tray;
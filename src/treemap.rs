@@ -0,0 +1,335 @@
+use crate::i18n::{format_count, format_size_mb, tr_args};
+use crate::scan::UnstoredFiles;
+use crate::{util, DiskInfo, FileInfo, Message};
+use iced::mouse;
+use iced::widget::canvas::{self, Cache, Canvas, Frame, Geometry, Path, Stroke};
+use iced::{Color, Element, Length, Point, Rectangle, Renderer, Size, Theme};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const HEIGHT: f32 = 420.0;
+/// Files drawn per disk; the rest become one "other files" tile.
+const FILES_PER_DISK: usize = 200;
+const HEADER_HEIGHT: f32 = 18.0;
+/// Rough width of one label character at the tile font size.
+const CHAR_WIDTH: f32 = 7.0;
+/// One color per disk, cycled when there are more disks than colors.
+const DISK_COLORS: &[Color] = &[
+    Color::from_rgb(0.26, 0.52, 0.96),
+    Color::from_rgb(0.92, 0.42, 0.20),
+    Color::from_rgb(0.20, 0.66, 0.33),
+    Color::from_rgb(0.61, 0.35, 0.85),
+    Color::from_rgb(0.85, 0.65, 0.13),
+    Color::from_rgb(0.10, 0.65, 0.70),
+    Color::from_rgb(0.85, 0.30, 0.55),
+];
+
+/// Every disk's files in one flat sequence, each with the name of the disk
+/// it came from. The files are borrowed, not copied.
+pub fn merged_files(disks: &[DiskInfo]) -> impl Iterator<Item = (Arc<str>, &FileInfo)> {
+    disks.iter().flat_map(|disk| {
+        let name: Arc<str> = Arc::from(disk.display_name());
+        disk.files.iter().map(move |file| (Arc::clone(&name), file))
+    })
+}
+
+/// Per disk, by the same names as `merged_files`, the files only tallied
+/// rather than kept as entries.
+pub fn merged_unstored(disks: &[DiskInfo]) -> impl Iterator<Item = (Arc<str>, &UnstoredFiles)> {
+    disks.iter().flat_map(|disk| {
+        let name: Arc<str> = Arc::from(disk.display_name());
+        disk.unstored.values().map(move |tally| (Arc::clone(&name), tally))
+    })
+}
+
+struct DiskGroup {
+    name: Arc<str>,
+    size_mb: f64,
+    /// Path and size of the largest files, largest first.
+    files: Vec<(Arc<str>, f64)>,
+    other_files: usize,
+    other_mb: f64,
+}
+
+/// The "All disks" treemap: one block per disk, sized by its files, split
+/// into tiles for its largest files. Built when the view opens or the
+/// scanned files change.
+pub struct Treemap {
+    disks: Vec<DiskGroup>,
+    cache: Cache,
+}
+
+struct Tile<'a> {
+    bounds: Rectangle,
+    disk: usize,
+    /// `None` for a disk's "other files" tile.
+    path: Option<&'a str>,
+    size_mb: f64,
+    /// Position among its disk's tiles, for shading.
+    rank: usize,
+}
+
+impl Treemap {
+    /// Groups `files` by the name of their disk, largest disk first. The
+    /// files of `unstored` join their disk's "other files" tile.
+    pub fn new<'a>(
+        files: impl Iterator<Item = (Arc<str>, &'a FileInfo)>,
+        unstored: impl Iterator<Item = (Arc<str>, &'a UnstoredFiles)>,
+    ) -> Treemap {
+        let mut order: Vec<Arc<str>> = Vec::new();
+        let mut by_disk: HashMap<Arc<str>, (Vec<&FileInfo>, usize, f64)> = HashMap::new();
+        for (name, file) in files.filter(|(_, file)| file.symlink_target.is_none() && file.size_mb > 0.0) {
+            by_disk
+                .entry(Arc::clone(&name))
+                .or_insert_with(|| {
+                    order.push(name);
+                    (Vec::new(), 0, 0.0)
+                })
+                .0
+                .push(file);
+        }
+        for (name, tally) in unstored.filter(|(_, tally)| tally.size_mb > 0.0) {
+            let (_, count, size_mb) = by_disk.entry(Arc::clone(&name)).or_insert_with(|| {
+                order.push(name);
+                (Vec::new(), 0, 0.0)
+            });
+            *count += tally.count;
+            *size_mb += tally.size_mb;
+        }
+        let mut disks: Vec<DiskGroup> = order
+            .into_iter()
+            .map(|name| {
+                let (mut files, unstored_files, unstored_mb) = by_disk.remove(&name).unwrap_or_default();
+                files.sort_by(|a, b| b.size_mb.total_cmp(&a.size_mb));
+                let rest = files.split_off(files.len().min(FILES_PER_DISK));
+                let other_mb: f64 = rest.iter().map(|file| file.size_mb).sum::<f64>() + unstored_mb;
+                let files: Vec<(Arc<str>, f64)> = files.iter().map(|file| (Arc::clone(&file.path), file.size_mb)).collect();
+                DiskGroup {
+                    size_mb: files.iter().map(|(_, size)| size).sum::<f64>() + other_mb,
+                    name,
+                    files,
+                    other_files: rest.len() + unstored_files,
+                    other_mb,
+                }
+            })
+            .collect();
+        disks.sort_by(|a, b| b.size_mb.total_cmp(&a.size_mb));
+        Treemap { disks, cache: Cache::new() }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.disks.is_empty()
+    }
+
+    pub fn view(&self) -> Element<'_, Message> {
+        Canvas::new(self).width(Length::Fill).height(Length::Fixed(HEIGHT)).into()
+    }
+
+    /// Each disk's block and the space for its tiles below the name header.
+    fn blocks(&self, bounds: Size) -> Vec<(Rectangle, Rectangle)> {
+        let sizes: Vec<f64> = self.disks.iter().map(|disk| disk.size_mb).collect();
+        squarify(&sizes, Rectangle::new(Point::ORIGIN, bounds))
+            .into_iter()
+            .map(|block| {
+                let header = if block.height > HEADER_HEIGHT * 2.0 { HEADER_HEIGHT } else { 0.0 };
+                let inner = Rectangle::new(
+                    Point::new(block.x + 2.0, block.y + header + 2.0),
+                    Size::new((block.width - 4.0).max(0.0), (block.height - header - 4.0).max(0.0)),
+                );
+                (block, inner)
+            })
+            .collect()
+    }
+
+    fn tiles(&self, bounds: Size) -> Vec<Tile<'_>> {
+        let mut tiles = Vec::new();
+        for (disk_index, (disk, (_, inner))) in self.disks.iter().zip(self.blocks(bounds)).enumerate() {
+            let mut sizes: Vec<f64> = disk.files.iter().map(|(_, size)| *size).collect();
+            if disk.other_mb > 0.0 {
+                sizes.push(disk.other_mb);
+            }
+            for (rank, bounds) in squarify(&sizes, inner).into_iter().enumerate() {
+                let (path, size_mb) = match disk.files.get(rank) {
+                    Some((path, size)) => (Some(&**path), *size),
+                    None => (None, disk.other_mb),
+                };
+                tiles.push(Tile { bounds, disk: disk_index, path, size_mb, rank });
+            }
+        }
+        tiles
+    }
+
+    fn tile_at<'a>(tiles: &'a [Tile<'a>], point: Point) -> Option<&'a Tile<'a>> {
+        tiles.iter().find(|tile| tile.bounds.contains(point))
+    }
+
+    /// The disk's color, fading for smaller files; "other files" is the
+    /// faintest.
+    fn color(&self, tile: &Tile) -> Color {
+        let base = DISK_COLORS[tile.disk % DISK_COLORS.len()];
+        let count = self.disks[tile.disk].files.len().max(1);
+        let fade = if tile.path.is_none() { 1.0 } else { tile.rank as f32 / count as f32 };
+        Color { a: 0.9 - 0.6 * fade, ..base }
+    }
+
+    fn label(&self, tile: &Tile) -> String {
+        let disk = &self.disks[tile.disk];
+        match tile.path {
+            Some(path) => format!("{} — {}", path, format_size_mb(tile.size_mb)),
+            None => tr_args("treemap-other-files", &[
+                ("count", format_count(disk.other_files)),
+                ("size", format_size_mb(disk.other_mb)),
+            ]),
+        }
+    }
+}
+
+impl canvas::Program<Message> for Treemap {
+    type State = ();
+
+    fn update(
+        &self,
+        _state: &mut (),
+        event: canvas::Event,
+        bounds: Rectangle,
+        cursor: mouse::Cursor,
+    ) -> (canvas::event::Status, Option<Message>) {
+        let canvas::Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) = event else {
+            return (canvas::event::Status::Ignored, None);
+        };
+        let Some(point) = cursor.position_in(bounds) else {
+            return (canvas::event::Status::Ignored, None);
+        };
+        let tiles = self.tiles(bounds.size());
+        match Treemap::tile_at(&tiles, point).and_then(|tile| tile.path) {
+            Some(path) => (canvas::event::Status::Captured, Some(Message::RevealInFolder(path.to_string()))),
+            None => (canvas::event::Status::Ignored, None),
+        }
+    }
+
+    fn draw(&self, _state: &(), renderer: &Renderer, theme: &Theme, bounds: Rectangle, cursor: mouse::Cursor) -> Vec<Geometry> {
+        let palette = theme.extended_palette();
+        let tiles = self.tiles(bounds.size());
+        let geometry = self.cache.draw(renderer, bounds.size(), |frame| {
+            for (disk, (block, _)) in self.disks.iter().zip(self.blocks(bounds.size())) {
+                frame.fill_rectangle(block.position(), block.size(), palette.background.weak.color);
+                if block.height > HEADER_HEIGHT * 2.0 {
+                    let label = format!("{} — {}", disk.name, format_size_mb(disk.size_mb));
+                    frame.fill_text(canvas::Text {
+                        content: util::truncate_middle(&label, (block.width / CHAR_WIDTH) as usize),
+                        position: Point::new(block.x + 3.0, block.y + 2.0),
+                        color: palette.background.base.text,
+                        size: 14.0,
+                        ..canvas::Text::default()
+                    });
+                }
+            }
+            for tile in &tiles {
+                frame.fill_rectangle(tile.bounds.position(), tile.bounds.size(), self.color(tile));
+                frame.stroke(
+                    &Path::rectangle(tile.bounds.position(), tile.bounds.size()),
+                    Stroke::default().with_color(palette.background.base.color).with_width(1.0),
+                );
+                // Only tiles with room for a few characters get a name.
+                let name = tile.path.map(|path| std::path::Path::new(path).file_name().map_or(path.into(), |name| name.to_string_lossy()));
+                if let Some(name) = name.filter(|_| tile.bounds.width > 60.0 && tile.bounds.height > 16.0) {
+                    frame.fill_text(canvas::Text {
+                        content: util::truncate_middle(&name, (tile.bounds.width / CHAR_WIDTH) as usize),
+                        position: Point::new(tile.bounds.x + 3.0, tile.bounds.y + 2.0),
+                        color: palette.background.base.text,
+                        size: 12.0,
+                        ..canvas::Text::default()
+                    });
+                }
+            }
+        });
+
+        // The hovered tile's full path and size follow the cursor.
+        let mut hover = Frame::new(renderer, bounds.size());
+        if let Some(point) = cursor.position_in(bounds) {
+            if let Some(tile) = Treemap::tile_at(&tiles, point) {
+                hover.fill_rectangle(tile.bounds.position(), tile.bounds.size(), Color { a: 0.25, ..palette.primary.strong.color });
+                let label = util::truncate_middle(&self.label(tile), (bounds.width / CHAR_WIDTH) as usize);
+                let width = (label.chars().count() as f32 * CHAR_WIDTH + 8.0).min(bounds.width);
+                let x = (point.x + 12.0).min(bounds.width - width).max(0.0);
+                let y = if point.y + 40.0 > bounds.height { point.y - 28.0 } else { point.y + 16.0 };
+                hover.fill_rectangle(Point::new(x, y), Size::new(width, 20.0), palette.background.base.color);
+                hover.fill_text(canvas::Text {
+                    content: label,
+                    position: Point::new(x + 4.0, y + 3.0),
+                    color: palette.background.base.text,
+                    size: 13.0,
+                    ..canvas::Text::default()
+                });
+            }
+        }
+        vec![geometry, hover.into_geometry()]
+    }
+
+    fn mouse_interaction(&self, _state: &(), bounds: Rectangle, cursor: mouse::Cursor) -> mouse::Interaction {
+        let tiles = self.tiles(bounds.size());
+        match cursor.position_in(bounds).and_then(|point| Treemap::tile_at(&tiles, point)) {
+            Some(tile) if tile.path.is_some() => mouse::Interaction::Pointer,
+            _ => mouse::Interaction::default(),
+        }
+    }
+}
+
+/// Lays `sizes`, largest first, out over `rect` with the squarified
+/// algorithm, which keeps tiles close to square. Returns one rectangle per
+/// size, in the same order.
+fn squarify(sizes: &[f64], rect: Rectangle) -> Vec<Rectangle> {
+    let total: f64 = sizes.iter().sum();
+    let mut tiles = Vec::with_capacity(sizes.len());
+    if total <= 0.0 || rect.width <= 0.0 || rect.height <= 0.0 {
+        tiles.resize(sizes.len(), Rectangle::new(rect.position(), Size::ZERO));
+        return tiles;
+    }
+    let scale = (rect.width * rect.height) as f64 / total;
+    let areas: Vec<f64> = sizes.iter().map(|size| size * scale).collect();
+    let mut free = rect;
+    let mut start = 0;
+    while start < areas.len() {
+        let side = free.width.min(free.height) as f64;
+        if side <= 0.0 {
+            tiles.resize(sizes.len(), Rectangle::new(free.position(), Size::ZERO));
+            break;
+        }
+        // Grow the row while that keeps its worst aspect ratio improving.
+        let mut end = start + 1;
+        while end < areas.len() && worst_ratio(&areas[start..=end], side) <= worst_ratio(&areas[start..end], side) {
+            end += 1;
+        }
+        let row = &areas[start..end];
+        let thickness = (row.iter().sum::<f64>() / side) as f32;
+        let mut offset = 0.0;
+        for area in row {
+            let length = (*area / thickness as f64) as f32;
+            tiles.push(if free.width >= free.height {
+                Rectangle::new(Point::new(free.x, free.y + offset), Size::new(thickness, length))
+            } else {
+                Rectangle::new(Point::new(free.x + offset, free.y), Size::new(length, thickness))
+            });
+            offset += length;
+        }
+        if free.width >= free.height {
+            free.x += thickness;
+            free.width = (free.width - thickness).max(0.0);
+        } else {
+            free.y += thickness;
+            free.height = (free.height - thickness).max(0.0);
+        }
+        start = end;
+    }
+    tiles
+}
+
+/// The largest aspect ratio of a row of `areas` laid along a side of length
+/// `side`.
+fn worst_ratio(areas: &[f64], side: f64) -> f64 {
+    let sum: f64 = areas.iter().sum();
+    let largest = areas.iter().cloned().fold(f64::MIN, f64::max);
+    let smallest = areas.iter().cloned().fold(f64::MAX, f64::min);
+    let side2 = side * side;
+    (side2 * largest / (sum * sum)).max(sum * sum / (side2 * smallest))
+}
@@ -0,0 +1,230 @@
+use std::env;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Per-user directory for the app's own files (lock file, settings, history).
+pub fn data_dir() -> PathBuf {
+    let base = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    } else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    } else {
+        env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+    };
+    base.unwrap_or_else(env::temp_dir).join("disk_usage_visualizer")
+}
+
+/// Shortens `text` to at most `max_chars` characters by replacing its middle
+/// with an ellipsis, keeping both the root and the file name of long paths.
+pub fn truncate_middle(text: &str, max_chars: usize) -> String {
+    let len = text.chars().count();
+    if len <= max_chars || max_chars < 3 {
+        return text.to_string();
+    }
+    let keep = max_chars - 1;
+    let head = keep / 2;
+    let tail = keep - head;
+    let start: String = text.chars().take(head).collect();
+    let end: String = text.chars().skip(len - tail).collect();
+    format!("{}…{}", start, end)
+}
+
+/// Resolves `.` and `..` components lexically, without touching the
+/// filesystem. Unlike `canonicalize`, symlinks are left as they are. A `..`
+/// at the root stays at the root.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match normalized.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    normalized.pop();
+                }
+                Some(Component::RootDir) | Some(Component::Prefix(_)) => {}
+                _ => normalized.push(".."),
+            },
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    if normalized.as_os_str().is_empty() {
+        normalized.push(".");
+    }
+    normalized
+}
+
+/// Replaces characters that are unsafe in file names on any platform, such as
+/// the slashes in `/dev/sda1`, with underscores.
+pub fn sanitize_file_name(name: &str) -> String {
+    let sanitized: String = name
+        .chars()
+        .map(|c| if c.is_control() || matches!(c, '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|') { '_' } else { c })
+        .collect();
+    sanitized.trim_matches(|c: char| c == '_' || c == '.' || c.is_whitespace()).to_string()
+}
+
+/// Today's date as `YYYY-MM-DD` (UTC).
+pub fn today_iso() -> String {
+    let (year, month, day) = civil_from_days((unix_now() / 86_400) as i64);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// The current UTC time as `HHMMSS`, for file names.
+pub fn now_hms() -> String {
+    let secs = unix_now() % 86_400;
+    format!("{:02}{:02}{:02}", secs / 3600, secs / 60 % 60, secs % 60)
+}
+
+/// Compares strings the way people read them: runs of digits by their value,
+/// so `file2` sorts before `file10`, and everything else case-insensitively.
+pub fn natural_cmp(a: &str, b: &str) -> std::cmp::Ordering {
+    use std::cmp::Ordering;
+    let (mut i, mut j) = (0, 0);
+    loop {
+        match (a[i..].chars().next(), b[j..].chars().next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                // Digits are single bytes, so the runs can be sliced out as they are.
+                let digits_a = &a[i..i + a[i..].bytes().take_while(u8::is_ascii_digit).count()];
+                let digits_b = &b[j..j + b[j..].bytes().take_while(u8::is_ascii_digit).count()];
+                i += digits_a.len();
+                j += digits_b.len();
+                // Compare by value without parsing, so long runs can't overflow.
+                let (trimmed_a, trimmed_b) = (digits_a.trim_start_matches('0'), digits_b.trim_start_matches('0'));
+                let ordering = trimmed_a
+                    .len()
+                    .cmp(&trimmed_b.len())
+                    .then_with(|| trimmed_a.cmp(trimmed_b))
+                    .then_with(|| digits_a.len().cmp(&digits_b.len()));
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.to_lowercase().cmp(y.to_lowercase());
+                if ordering != Ordering::Equal {
+                    return ordering;
+                }
+                i += x.len_utf8();
+                j += y.len_utf8();
+            }
+        }
+    }
+}
+
+/// Opens the system file manager at `path`, selected where the platform
+/// supports that (Explorer, Finder); elsewhere its folder is opened.
+pub fn reveal_in_folder(path: &Path) -> Result<(), String> {
+    use std::process::Command;
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("explorer");
+        command.arg(format!("/select,{}", path.display()));
+        command
+    } else if cfg!(target_os = "macos") {
+        let mut command = Command::new("open");
+        command.arg("-R").arg(path);
+        command
+    } else {
+        let mut command = Command::new("xdg-open");
+        command.arg(path.parent().unwrap_or(path));
+        command
+    };
+    let mut child = command.spawn().map_err(|e| e.to_string())?;
+    // Reaped in the background so it doesn't linger as a zombie.
+    thread::spawn(move || child.wait());
+    Ok(())
+}
+
+/// This machine's host name, or an empty string if it can't be determined.
+pub fn hostname() -> String {
+    use sysinfo::{System, SystemExt};
+    System::new().host_name().unwrap_or_default()
+}
+
+/// Delays before each retry in `retry_io`.
+const RETRY_BACKOFF_MS: [u64; 3] = [100, 200, 400];
+
+/// Errors that won't go away by asking again.
+fn is_permanent(error: &io::Error) -> bool {
+    matches!(error.kind(), io::ErrorKind::PermissionDenied | io::ErrorKind::NotFound)
+}
+
+/// Runs `op`, retrying transient failures (as network mounts sometimes
+/// return) up to three times with 100/200/400ms backoff. Permission and
+/// not-found errors are returned straight away. `retried` is bumped once per
+/// call that needed a retry, whether or not it eventually succeeded.
+pub fn retry_io<T>(retried: &AtomicUsize, mut op: impl FnMut() -> io::Result<T>) -> io::Result<T> {
+    let mut result = op();
+    for (attempt, delay) in RETRY_BACKOFF_MS.iter().enumerate() {
+        match &result {
+            Err(error) if !is_permanent(error) => {
+                if attempt == 0 {
+                    retried.fetch_add(1, Ordering::Relaxed);
+                }
+                thread::sleep(Duration::from_millis(*delay));
+                result = op();
+            }
+            _ => break,
+        }
+    }
+    result
+}
+
+/// Seconds since the Unix epoch.
+pub fn unix_now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0)
+}
+
+/// Converts days since 1970-01-01 to a `(year, month, day)` civil date.
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = if z >= 0 { z } else { z - 146_096 } / 146_097;
+    let day_of_era = (z - era * 146_097) as u64;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+    let year = year_of_era as i64 + era * 400;
+    (if month <= 2 { year + 1 } else { year }, month, day)
+}
+
+/// Scores `candidate` against `query` as a case-insensitive subsequence, or
+/// `None` if the query's characters don't all appear in order. Runs of
+/// consecutive characters and matches at word starts score higher, skipped
+/// characters lower. Whitespace in the query is ignored.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let chars: Vec<char> = candidate.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut next = 0;
+    for wanted in query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase) {
+        let found = (next..chars.len()).find(|&i| chars[i] == wanted)?;
+        score += 1;
+        if found > 0 && found == next {
+            score += 5;
+        }
+        if found == 0 || !chars[found - 1].is_alphanumeric() {
+            score += 3;
+        }
+        score -= (found - next) as i64;
+        next = found + 1;
+    }
+    Some(score)
+}
+
+/// A fresh, empty directory under the system temp dir, for tests that need
+/// files on disk.
+#[cfg(test)]
+pub fn test_dir(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("disk-usage-test-{}-{}", std::process::id(), name));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}